@@ -0,0 +1,12 @@
+CREATE TABLE note_revisions (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    note_id         UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    revision_number INTEGER NOT NULL,
+    title           TEXT NOT NULL,
+    body            JSONB NOT NULL DEFAULT '{}',
+    body_text       TEXT NOT NULL DEFAULT '',
+    created_by      UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX idx_note_revisions_number ON note_revisions(note_id, revision_number);
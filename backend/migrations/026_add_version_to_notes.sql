@@ -0,0 +1,2 @@
+-- Monotonic edit counter used for optimistic concurrency (ETag / If-Match)
+ALTER TABLE notes ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
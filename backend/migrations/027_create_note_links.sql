@@ -0,0 +1,12 @@
+ALTER TYPE edge_type ADD VALUE IF NOT EXISTS 'note_link';
+
+CREATE TABLE note_links (
+    source_note_id  UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    target_note_id  UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    link_count      INTEGER NOT NULL DEFAULT 1,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (source_note_id, target_note_id),
+    CHECK (source_note_id <> target_note_id)
+);
+
+CREATE INDEX idx_nl_target ON note_links(target_note_id);
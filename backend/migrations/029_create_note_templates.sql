@@ -0,0 +1,16 @@
+CREATE TABLE note_templates (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id        UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    name                TEXT NOT NULL,
+    description         TEXT,
+    title               TEXT NOT NULL DEFAULT '',
+    body                JSONB NOT NULL DEFAULT '{}',
+    note_type           note_type NOT NULL DEFAULT 'field_note',
+    default_tags        TEXT[] NOT NULL DEFAULT '{}',
+    default_concept_ids UUID[] NOT NULL DEFAULT '{}',
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_note_templates_workspace ON note_templates(workspace_id);
+CREATE UNIQUE INDEX idx_note_templates_name ON note_templates(workspace_id, name);
@@ -0,0 +1,13 @@
+-- Days a soft-deleted note stays in the trash before it is purged.
+-- NULL keeps trashed notes until they are deleted by hand.
+--
+-- Added without a default so existing workspaces start at NULL and nothing
+-- already in their trash is purged until they opt in; workspaces created
+-- from now on get 30 days.
+ALTER TABLE workspaces
+    ADD COLUMN trash_retention_days INTEGER
+        CHECK (trash_retention_days BETWEEN 1 AND 3650);
+
+ALTER TABLE workspaces ALTER COLUMN trash_retention_days SET DEFAULT 30;
+
+CREATE INDEX idx_notes_trashed ON notes(deleted_at) WHERE deleted_at IS NOT NULL;
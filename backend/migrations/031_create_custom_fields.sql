@@ -0,0 +1,18 @@
+CREATE TABLE custom_field_definitions (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id    UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    key             TEXT NOT NULL CHECK (key ~ '^[a-z][a-z0-9_]{0,62}$'),
+    label           TEXT NOT NULL,
+    field_type      TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'enum', 'boolean')),
+    options         TEXT[] NOT NULL DEFAULT '{}',
+    sort_order      INTEGER NOT NULL DEFAULT 0,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX idx_custom_fields_key ON custom_field_definitions(workspace_id, key);
+
+-- Values keyed by custom_field_definitions.key, validated by the API.
+ALTER TABLE notes ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_notes_custom_fields ON notes USING GIN (custom_fields jsonb_path_ops);
@@ -0,0 +1,45 @@
+-- Workspace-defined note types, replacing the fixed note_type enum. Notes and
+-- templates keep their `note_type` column, now holding a note_types.key.
+CREATE TABLE note_types (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id        UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    key                 TEXT NOT NULL CHECK (key ~ '^[a-z][a-z0-9_]{0,62}$'),
+    name                TEXT NOT NULL,
+    icon                TEXT,
+    default_template_id UUID REFERENCES note_templates(id) ON DELETE SET NULL,
+    -- custom_field_definitions.key values a note of this type must fill in
+    required_fields     TEXT[] NOT NULL DEFAULT '{}',
+    sort_order          INTEGER NOT NULL DEFAULT 0,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX idx_note_types_key ON note_types(workspace_id, key);
+
+-- The former enum values become every existing workspace's built-in types.
+INSERT INTO note_types (workspace_id, key, name, icon, sort_order)
+SELECT w.id, t.key, t.name, t.icon, t.sort_order
+FROM workspaces w
+CROSS JOIN (VALUES
+    ('interview',  'Interview',  '🎙', 0),
+    ('field_note', 'Field Note', '📋', 1),
+    ('voice_memo', 'Voice Memo', '🎤', 2),
+    ('photo',      'Photo',      '📷', 3)
+) AS t(key, name, icon, sort_order);
+
+ALTER TABLE notes ALTER COLUMN note_type DROP DEFAULT;
+ALTER TABLE notes ALTER COLUMN note_type TYPE TEXT USING note_type::text;
+ALTER TABLE notes ALTER COLUMN note_type SET DEFAULT 'field_note';
+
+ALTER TABLE note_templates ALTER COLUMN note_type DROP DEFAULT;
+ALTER TABLE note_templates ALTER COLUMN note_type TYPE TEXT USING note_type::text;
+ALTER TABLE note_templates ALTER COLUMN note_type SET DEFAULT 'field_note';
+
+DROP TYPE note_type;
+
+-- A type cannot be deleted while notes (including trashed ones) use it.
+ALTER TABLE notes ADD CONSTRAINT notes_note_type_fkey
+    FOREIGN KEY (workspace_id, note_type) REFERENCES note_types(workspace_id, key);
+
+DROP INDEX idx_notes_type;
+CREATE INDEX idx_notes_type ON notes(workspace_id, note_type);
@@ -0,0 +1,6 @@
+-- Set on notes soft-deleted by a merge, pointing at the note they were merged
+-- into. Deferred so archive restores can insert notes in any order.
+ALTER TABLE notes ADD COLUMN merged_into_id UUID
+    REFERENCES notes(id) ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED;
+
+CREATE INDEX idx_notes_merged_into ON notes(merged_into_id) WHERE merged_into_id IS NOT NULL;
@@ -0,0 +1,22 @@
+CREATE TABLE note_comments (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    note_id         UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    -- Thread root for replies; replies are never nested deeper.
+    parent_id       UUID REFERENCES note_comments(id) ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED,
+    author_id       UUID REFERENCES users(id) ON DELETE SET NULL,
+    body            TEXT NOT NULL,
+    -- Optional text range in the note's Tiptap document (ProseMirror
+    -- positions), with the text it covered when the comment was made.
+    anchor_from     INTEGER,
+    anchor_to       INTEGER,
+    anchor_text     TEXT,
+    resolved_at     TIMESTAMPTZ,
+    resolved_by     UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((anchor_from IS NULL) = (anchor_to IS NULL)),
+    CHECK (anchor_from IS NULL OR (anchor_from >= 0 AND anchor_from <= anchor_to))
+);
+
+CREATE INDEX idx_note_comments_note ON note_comments(note_id, created_at);
+CREATE INDEX idx_note_comments_parent ON note_comments(parent_id) WHERE parent_id IS NOT NULL;
@@ -0,0 +1,19 @@
+-- Unauthenticated read-only links to a note or a whole field trip.
+CREATE TABLE share_links (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id    UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    note_id         UUID REFERENCES notes(id) ON DELETE CASCADE,
+    field_trip_id   UUID REFERENCES field_trips(id) ON DELETE CASCADE,
+    -- SHA-256 of the token; the token itself is only shown on creation.
+    token_hash      TEXT NOT NULL UNIQUE,
+    password_hash   TEXT,
+    expires_at      TIMESTAMPTZ,
+    revoked_at      TIMESTAMPTZ,
+    created_by      UUID REFERENCES users(id) ON DELETE SET NULL,
+    view_count      BIGINT NOT NULL DEFAULT 0,
+    last_viewed_at  TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((note_id IS NULL) <> (field_trip_id IS NULL))
+);
+
+CREATE INDEX idx_share_links_workspace ON share_links(workspace_id, created_at DESC);
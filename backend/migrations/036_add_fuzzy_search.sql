@@ -0,0 +1,17 @@
+-- Fuzzy (trigram) and accent-insensitive matching for search, so
+-- inconsistently romanised names ("Peradeniya", "Peradenia", "Peradeniyā")
+-- still find each other.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+-- unaccent() is only STABLE because its dictionary could change; pinning the
+-- dictionary makes it safe to use in index expressions.
+CREATE FUNCTION search_normalize(text) RETURNS text
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+    AS $$ SELECT lower(public.unaccent('public.unaccent'::regdictionary, $1)) $$;
+
+-- Serves `<%` (word similarity) on note text. The expression must match the
+-- one used by search queries exactly. Entity and concept names are short
+-- enough per workspace to be compared without an index.
+CREATE INDEX idx_notes_search_trgm ON notes
+    USING GIN (search_normalize(coalesce(title, '') || ' ' || coalesce(body_text, '')) gin_trgm_ops);
@@ -0,0 +1,12 @@
+-- Timed transcription segments, when the transcriber provides them: a JSON
+-- array of {"start": seconds, "end": seconds, "text": "..."}.
+ALTER TABLE media ADD COLUMN transcription_segments JSONB;
+
+-- Full-text and trigram search over a media item's filename, label and
+-- transcription. The expressions must match the ones used by search queries.
+CREATE INDEX idx_media_search ON media USING GIN (
+    to_tsvector('english', coalesce(original_filename, '') || ' ' || coalesce(label, '') || ' ' || coalesce(transcription_text, ''))
+);
+CREATE INDEX idx_media_search_trgm ON media USING GIN (
+    search_normalize(coalesce(original_filename, '') || ' ' || coalesce(label, '') || ' ' || coalesce(transcription_text, '')) gin_trgm_ops
+);
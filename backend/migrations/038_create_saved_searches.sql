@@ -0,0 +1,20 @@
+-- Saved searches: a search query plus note filters, re-run on demand.
+-- Pinned ones are listed in the sidebar as dynamic collections.
+CREATE TABLE saved_searches (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id    UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    name            TEXT NOT NULL,
+    icon            TEXT NOT NULL DEFAULT '🔍',
+    -- Search query language; empty matches every note.
+    query           TEXT NOT NULL DEFAULT '',
+    -- The filtering fields of NoteFilters
+    filters         JSONB NOT NULL DEFAULT '{}',
+    pinned          BOOLEAN NOT NULL DEFAULT false,
+    -- Notes created or edited after this count as new matches.
+    last_viewed_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_by      UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_saved_searches_workspace ON saved_searches(workspace_id, name);
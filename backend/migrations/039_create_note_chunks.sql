@@ -0,0 +1,23 @@
+-- Chunk-level note embeddings for semantic search. Vectors are stored as
+-- real[] so no extension is needed; search scans the workspace's chunks.
+-- Vectors are unit length, so cosine similarity is their dot product.
+CREATE TABLE note_chunks (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    note_id      UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    chunk_index  INT NOT NULL,
+    content      TEXT NOT NULL,
+    -- Embedding model; vectors of different models are never compared.
+    model        TEXT NOT NULL,
+    -- Hash of the model and note text the chunks were built from, to skip
+    -- re-embedding unchanged notes.
+    source_hash  TEXT NOT NULL,
+    embedding    REAL[] NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (note_id, model, chunk_index)
+);
+
+CREATE INDEX idx_note_chunks_model ON note_chunks(model, note_id);
+
+CREATE FUNCTION embedding_similarity(real[], real[]) RETURNS real
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+    AS $$ SELECT sum(a * b)::real FROM unnest($1, $2) AS t(a, b) $$;
@@ -0,0 +1,22 @@
+-- Radius, bounding-box and date-range filters on notes.
+
+-- Great-circle (haversine) distance in kilometres.
+CREATE FUNCTION geo_distance_km(lat1 double precision, lng1 double precision,
+                                lat2 double precision, lng2 double precision)
+    RETURNS double precision
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+    AS $$
+        SELECT 2 * 6371.0088 * asin(least(1, sqrt(
+            power(sin(radians(lat2 - lat1) / 2), 2)
+            + cos(radians(lat1)) * cos(radians(lat2)) * power(sin(radians(lng2 - lng1) / 2), 2))))
+    $$;
+
+-- Serves `point(...) <@ box(...)` for bounding boxes and the box around a
+-- radius. The expression and predicate must match the queries exactly.
+CREATE INDEX idx_notes_location ON notes
+    USING GIST (point(location_lng, location_lat))
+    WHERE location_lat IS NOT NULL AND location_lng IS NOT NULL;
+
+-- A note's time span; undated notes fall back to their creation time.
+CREATE INDEX idx_notes_time_start ON notes (workspace_id, (COALESCE(time_start, created_at)));
+CREATE INDEX idx_notes_time_end ON notes (workspace_id, (COALESCE(time_end, time_start, created_at)));
@@ -0,0 +1,9 @@
+-- Prefix suggestions for the search box and mention picker. Trigram
+-- indexes serve both `LIKE 'pre%'` (start of the name) and
+-- `LIKE '% pre%'` (start of a later word); the expressions must match the
+-- suggest query exactly.
+CREATE INDEX idx_entities_name_trgm ON entities USING GIN (search_normalize(name) gin_trgm_ops);
+CREATE INDEX idx_concepts_name_trgm ON concepts USING GIN (search_normalize(name) gin_trgm_ops);
+CREATE INDEX idx_tags_name_trgm ON tags USING GIN (search_normalize(name) gin_trgm_ops);
+CREATE INDEX idx_notes_title_trgm ON notes USING GIN (search_normalize(title) gin_trgm_ops)
+    WHERE deleted_at IS NULL;
@@ -0,0 +1,7 @@
+-- The AI provider and model a workspace uses. NULL falls back to the
+-- server's AI_PROVIDER / AI_MODEL; 'none' turns AI features off.
+ALTER TABLE workspaces
+    ADD COLUMN ai_provider TEXT
+        CHECK (ai_provider IN ('anthropic', 'perplexity', 'openai', 'mock', 'none')),
+    ADD COLUMN ai_model TEXT
+        CHECK (length(ai_model) BETWEEN 1 AND 200);
@@ -0,0 +1,11 @@
+-- At most one pending or running export per workspace, so concurrent
+-- requests cannot both start a job.
+UPDATE workspace_exports e SET status = 'failed', error = 'Superseded by another export',
+       completed_at = now()
+WHERE status IN ('pending', 'running')
+  AND EXISTS (SELECT 1 FROM workspace_exports o
+              WHERE o.workspace_id = e.workspace_id AND o.status IN ('pending', 'running')
+                AND (o.created_at, o.id) > (e.created_at, e.id));
+
+CREATE UNIQUE INDEX idx_workspace_exports_active ON workspace_exports(workspace_id)
+    WHERE status IN ('pending', 'running');
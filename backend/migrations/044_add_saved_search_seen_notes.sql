@@ -0,0 +1,5 @@
+-- Notes matching a saved search when it was last viewed. Matches outside
+-- this set are new, whether the note was just created or only now fits the
+-- query. NULL until the search is first viewed; until then, notes created
+-- after last_viewed_at count as new.
+ALTER TABLE saved_searches ADD COLUMN seen_note_ids UUID[];
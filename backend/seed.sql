@@ -39,6 +39,14 @@ VALUES (
   'Field Research'
 ) ON CONFLICT DO NOTHING;
 
+-- ─── Note Types (the built-in types every workspace starts with) ───
+INSERT INTO note_types (workspace_id, key, name, icon, sort_order) VALUES
+  ('b0000000-0000-0000-0000-000000000001', 'interview', 'Interview', '🎙', 0),
+  ('b0000000-0000-0000-0000-000000000001', 'field_note', 'Field Note', '📋', 1),
+  ('b0000000-0000-0000-0000-000000000001', 'voice_memo', 'Voice Memo', '🎤', 2),
+  ('b0000000-0000-0000-0000-000000000001', 'photo', 'Photo', '📷', 3)
+ON CONFLICT DO NOTHING;
+
 -- ─── Field Trips ───
 INSERT INTO field_trips (id, workspace_id, name, icon) VALUES
   ('c0000000-0000-0000-0000-000000000001', 'b0000000-0000-0000-0000-000000000001', 'Kandy Highlands', '🌿'),
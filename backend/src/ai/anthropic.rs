@@ -0,0 +1,110 @@
+//! Claude through the Anthropic Messages API.
+
+use serde::{Deserialize, Serialize};
+
+use super::{AiProvider, ChatMessage, ProviderInfo};
+use crate::error::AppError;
+
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-6";
+
+const API_URL: &str = "https://api.anthropic.com/v1/messages";
+const API_VERSION: &str = "2023-06-01";
+
+#[derive(Clone)]
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    system: &'a str,
+    messages: Vec<Message<'a>>,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+/// Only text blocks carry `text`.
+#[derive(Deserialize)]
+struct ContentBlock {
+    #[serde(default)]
+    text: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(client: reqwest::Client, api_key: &str, model: &str) -> Self {
+        AnthropicProvider {
+            client,
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        }
+    }
+}
+
+impl AiProvider for AnthropicProvider {
+    fn metadata(&self) -> ProviderInfo {
+        ProviderInfo {
+            provider: "anthropic",
+            label: "Claude",
+            model: self.model.clone(),
+        }
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: u32) -> Result<String, AppError> {
+        self.chat(system, &[ChatMessage::user(prompt)], max_tokens).await
+    }
+
+    async fn chat(&self, system: &str, messages: &[ChatMessage], max_tokens: u32) -> Result<String, AppError> {
+        let request = MessagesRequest {
+            model: &self.model,
+            max_tokens,
+            system,
+            messages: messages
+                .iter()
+                .map(|m| Message { role: &m.role, content: &m.content })
+                .collect(),
+        };
+
+        let response = self
+            .client
+            .post(API_URL)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Claude API error: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(AppError::Internal(format!(
+                "Claude API returned {}: {}",
+                status, text
+            )));
+        }
+
+        let parsed: MessagesResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to parse Claude response: {}", e)))?;
+
+        let text: String = parsed.content.into_iter().map(|b| b.text).collect();
+        if text.is_empty() {
+            return Err(AppError::Internal("Empty response from Claude".to_string()));
+        }
+        Ok(text)
+    }
+}
@@ -0,0 +1,109 @@
+//! A deterministic stand-in for test and development setups
+//! (`AI_PROVIDER=mock`). Replies describe what was asked without any
+//! network call, so the same request always gets the same answer.
+
+use super::{AiProvider, ChatMessage, ProviderInfo};
+use crate::error::AppError;
+
+pub const DEFAULT_MODEL: &str = "mock";
+
+/// Characters of the prompt quoted in a reply.
+const QUOTE_CHARS: usize = 60;
+
+#[derive(Clone)]
+pub struct MockProvider {
+    model: String,
+}
+
+impl MockProvider {
+    pub fn new(model: &str) -> Self {
+        MockProvider { model: model.to_string() }
+    }
+
+    /// `[model] <kind> of "<start of text>" (<n> words)`, cut to
+    /// `max_tokens` words.
+    fn reply(&self, kind: &str, text: &str, max_tokens: u32) -> String {
+        let quoted: String = text.chars().take(QUOTE_CHARS).collect();
+        let quoted = quoted.split_whitespace().collect::<Vec<_>>().join(" ");
+        let reply = format!(
+            "[{}] {} of \"{}\" ({} words)",
+            self.model,
+            kind,
+            quoted,
+            text.split_whitespace().count()
+        );
+        reply
+            .split(' ')
+            .take(max_tokens.max(1) as usize)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl AiProvider for MockProvider {
+    fn metadata(&self) -> ProviderInfo {
+        ProviderInfo {
+            provider: "mock",
+            label: "Mock",
+            model: self.model.clone(),
+        }
+    }
+
+    async fn complete(&self, _system: &str, prompt: &str, max_tokens: u32) -> Result<String, AppError> {
+        Ok(self.reply("completion", prompt, max_tokens))
+    }
+
+    async fn chat(&self, _system: &str, messages: &[ChatMessage], max_tokens: u32) -> Result<String, AppError> {
+        let last = messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .map(|m| m.content.as_str())
+            .unwrap_or_default();
+        let kind = format!("reply {}", messages.len().div_ceil(2));
+        Ok(self.reply(&kind, last, max_tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn complete_describes_the_prompt() {
+        let mock = MockProvider::new(DEFAULT_MODEL);
+        let reply = mock.complete("system", "Summarise the  harvest\nnotes", 100).await.unwrap();
+        assert_eq!(reply, "[mock] completion of \"Summarise the harvest notes\" (4 words)");
+        assert_eq!(reply, mock.complete("other", "Summarise the  harvest\nnotes", 100).await.unwrap());
+        assert_eq!(mock.metadata().model, "mock");
+    }
+
+    #[tokio::test]
+    async fn chat_answers_the_last_user_message() {
+        let mock = MockProvider::new("echo");
+        let messages = vec![
+            ChatMessage::user("first question"),
+            ChatMessage { role: "assistant".to_string(), content: "an answer".to_string() },
+            ChatMessage::user("second question here"),
+        ];
+        let reply = mock.chat("system", &messages, 100).await.unwrap();
+        assert_eq!(reply, "[echo] reply 2 of \"second question here\" (3 words)");
+    }
+
+    #[tokio::test]
+    async fn long_prompts_are_quoted_in_part() {
+        let prompt = "word ".repeat(50);
+        let reply = MockProvider::new("mock").complete("", &prompt, 1000).await.unwrap();
+        let quoted = "word ".repeat(QUOTE_CHARS / 5);
+        assert_eq!(reply, format!("[mock] completion of \"{}\" (50 words)", quoted.trim_end()));
+    }
+
+    #[tokio::test]
+    async fn replies_are_cut_to_max_tokens() {
+        let mock = MockProvider::new("mock");
+        let reply = mock.complete("", "one two three", 3).await.unwrap();
+        assert_eq!(reply, "[mock] completion of");
+        // At least one word, even with no token budget.
+        assert_eq!(mock.complete("", "one two three", 0).await.unwrap(), "[mock]");
+    }
+}
@@ -0,0 +1,348 @@
+//! Text generation for the research assistant. Each backend implements
+//! `AiProvider`; `Provider` picks one per workspace from its `ai_provider`
+//! and `ai_model` settings, falling back to `AI_PROVIDER` and `AI_MODEL`.
+
+pub mod anthropic;
+pub mod mock;
+pub mod openai;
+pub mod perplexity;
+
+use std::future::Future;
+
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::ai::AiSettings;
+
+pub use anthropic::AnthropicProvider;
+pub use mock::MockProvider;
+pub use openai::OpenAiProvider;
+pub use perplexity::PerplexityProvider;
+
+/// Provider names accepted by `AI_PROVIDER` and workspace settings.
+pub const PROVIDERS: &[&str] = &["anthropic", "perplexity", "openai", "mock"];
+
+/// One turn of a conversation; `role` is `user` or `assistant`.
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: &str) -> Self {
+        ChatMessage { role: "user".to_string(), content: content.to_string() }
+    }
+}
+
+/// Which backend and model answer, as shown to users and stored with
+/// assistant messages.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderInfo {
+    /// One of `PROVIDERS`.
+    pub provider: &'static str,
+    /// Display name, e.g. "Claude".
+    pub label: &'static str,
+    pub model: String,
+}
+
+/// A chat model behind some API.
+pub trait AiProvider {
+    fn metadata(&self) -> ProviderInfo;
+
+    /// A single reply to `prompt`.
+    fn complete(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> impl Future<Output = Result<String, AppError>> + Send;
+
+    /// The next assistant turn after `messages`, which alternate between
+    /// user and assistant and start with the user.
+    fn chat(
+        &self,
+        system: &str,
+        messages: &[ChatMessage],
+        max_tokens: u32,
+    ) -> impl Future<Output = Result<String, AppError>> + Send;
+}
+
+/// The provider a workspace uses.
+#[derive(Clone)]
+pub enum Provider {
+    Anthropic(AnthropicProvider),
+    Perplexity(PerplexityProvider),
+    OpenAi(OpenAiProvider),
+    Mock(MockProvider),
+}
+
+impl Provider {
+    /// Whether `name` has the API key or URL it needs. The mock is only
+    /// offered when it is the server default, so test setups cannot leak
+    /// into real workspaces.
+    pub fn is_available(name: &str, config: &Config) -> bool {
+        match name {
+            "anthropic" => !config.anthropic_api_key.is_empty(),
+            "perplexity" => !config.perplexity_api_key.is_empty(),
+            "openai" => !config.ai_api_url.is_empty(),
+            "mock" => config.ai_provider == "mock",
+            _ => false,
+        }
+    }
+
+    /// Providers workspaces may choose.
+    pub fn available(config: &Config) -> Vec<&'static str> {
+        PROVIDERS
+            .iter()
+            .copied()
+            .filter(|name| Self::is_available(name, config))
+            .collect()
+    }
+
+    /// Provider `name` running `model`, or its default model. `None` when
+    /// the provider is unknown or unavailable.
+    pub fn build(name: &str, model: Option<&str>, config: &Config, client: &reqwest::Client) -> Option<Self> {
+        if !Self::is_available(name, config) {
+            return None;
+        }
+        let client = client.clone();
+        Some(match name {
+            "anthropic" => Provider::Anthropic(AnthropicProvider::new(
+                client,
+                &config.anthropic_api_key,
+                model.unwrap_or(anthropic::DEFAULT_MODEL),
+            )),
+            "perplexity" => Provider::Perplexity(PerplexityProvider::new(
+                client,
+                &config.perplexity_api_key,
+                model.unwrap_or(perplexity::DEFAULT_MODEL),
+            )),
+            "openai" => Provider::OpenAi(OpenAiProvider::new(
+                client,
+                &config.ai_api_url,
+                &config.ai_api_key,
+                model.unwrap_or(openai::DEFAULT_MODEL),
+            )),
+            _ => Provider::Mock(MockProvider::new(model.unwrap_or(mock::DEFAULT_MODEL))),
+        })
+    }
+
+    /// `AI_PROVIDER`, or when unset the first provider with an API key, in
+    /// the order Anthropic, Perplexity, OpenAI-compatible.
+    pub fn default_name(config: &Config) -> Option<&'static str> {
+        match config.ai_provider.as_str() {
+            "" => ["anthropic", "perplexity", "openai"]
+                .into_iter()
+                .find(|name| Self::is_available(name, config)),
+            name => PROVIDERS.iter().copied().find(|p| *p == name),
+        }
+    }
+
+    /// The server default provider with `AI_MODEL`; `None` when AI is off.
+    pub fn server_default(config: &Config, client: &reqwest::Client) -> Option<Self> {
+        let model = Some(config.ai_model.as_str()).filter(|m| !m.is_empty());
+        Self::build(Self::default_name(config)?, model, config, client)
+    }
+
+    /// The provider for workspace settings. A model without a provider
+    /// applies to the server default; a chosen provider that is no longer
+    /// configured falls back to the server default.
+    pub fn resolve(settings: &AiSettings, config: &Config, client: &reqwest::Client) -> Option<Self> {
+        match settings.ai_provider.as_deref() {
+            Some("none") => None,
+            Some(name) => Self::build(name, settings.ai_model.as_deref(), config, client).or_else(|| {
+                tracing::warn!("AI provider '{}' is not configured; using the server default", name);
+                Self::server_default(config, client)
+            }),
+            None => match settings.ai_model.as_deref() {
+                Some(model) => Self::build(Self::default_name(config)?, Some(model), config, client),
+                None => Self::server_default(config, client),
+            },
+        }
+    }
+
+    pub async fn for_workspace(
+        pool: &PgPool,
+        config: &Config,
+        client: &reqwest::Client,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, AppError> {
+        let settings = sqlx::query_as::<_, AiSettings>(
+            "SELECT ai_provider, ai_model FROM workspaces WHERE id = $1",
+        )
+        .bind(workspace_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Workspace not found".to_string()))?;
+        Ok(Self::resolve(&settings, config, client))
+    }
+}
+
+impl AiProvider for Provider {
+    fn metadata(&self) -> ProviderInfo {
+        match self {
+            Provider::Anthropic(p) => p.metadata(),
+            Provider::Perplexity(p) => p.metadata(),
+            Provider::OpenAi(p) => p.metadata(),
+            Provider::Mock(p) => p.metadata(),
+        }
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: u32) -> Result<String, AppError> {
+        match self {
+            Provider::Anthropic(p) => p.complete(system, prompt, max_tokens).await,
+            Provider::Perplexity(p) => p.complete(system, prompt, max_tokens).await,
+            Provider::OpenAi(p) => p.complete(system, prompt, max_tokens).await,
+            Provider::Mock(p) => p.complete(system, prompt, max_tokens).await,
+        }
+    }
+
+    async fn chat(&self, system: &str, messages: &[ChatMessage], max_tokens: u32) -> Result<String, AppError> {
+        match self {
+            Provider::Anthropic(p) => p.chat(system, messages, max_tokens).await,
+            Provider::Perplexity(p) => p.chat(system, messages, max_tokens).await,
+            Provider::OpenAi(p) => p.chat(system, messages, max_tokens).await,
+            Provider::Mock(p) => p.chat(system, messages, max_tokens).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config with only the given AI settings; `keys` lists the providers
+    /// that get an API key or URL.
+    fn config_with(ai_provider: &str, ai_model: &str, keys: &[&str]) -> Config {
+        let key = |name: &str, value: &str| {
+            if keys.contains(&name) { value.to_string() } else { String::new() }
+        };
+        Config {
+            database_url: String::new(),
+            jwt_secret: String::new(),
+            jwt_expiry_hours: 72,
+            cors_origin: String::new(),
+            port: 8080,
+            mapbox_monthly_cap: 0,
+            lemonsqueezy_webhook_secret: String::new(),
+            lemonsqueezy_api_key: String::new(),
+            perplexity_api_key: key("perplexity", "pplx-test"),
+            anthropic_api_key: key("anthropic", "sk-ant-test"),
+            ai_provider: ai_provider.to_string(),
+            ai_model: ai_model.to_string(),
+            ai_api_url: key("openai", "http://localhost:11434/v1"),
+            ai_api_key: String::new(),
+            trash_purge_interval_secs: 3600,
+            embedding_provider: "hashed".to_string(),
+            embedding_api_url: String::new(),
+            embedding_api_key: String::new(),
+            embedding_model: String::new(),
+            embedding_dimensions: 256,
+        }
+    }
+
+    fn settings(provider: Option<&str>, model: Option<&str>) -> AiSettings {
+        AiSettings {
+            ai_provider: provider.map(str::to_string),
+            ai_model: model.map(str::to_string),
+        }
+    }
+
+    /// Provider name and model `resolve` picks, or `None` when AI is off.
+    fn resolved(settings: &AiSettings, config: &Config) -> Option<(&'static str, String)> {
+        Provider::resolve(settings, config, &reqwest::Client::new()).map(|p| {
+            let info = p.metadata();
+            (info.provider, info.model)
+        })
+    }
+
+    #[test]
+    fn availability_needs_keys_and_mock_needs_server_default() {
+        let config = config_with("", "", &["anthropic", "openai"]);
+        assert_eq!(Provider::available(&config), vec!["anthropic", "openai"]);
+        assert!(!Provider::is_available("mock", &config));
+        assert!(!Provider::is_available("unknown", &config));
+
+        assert_eq!(Provider::available(&config_with("mock", "", &[])), vec!["mock"]);
+    }
+
+    #[test]
+    fn default_name_prefers_setting_then_first_configured() {
+        assert_eq!(Provider::default_name(&config_with("", "", &["openai", "perplexity"])), Some("perplexity"));
+        assert_eq!(Provider::default_name(&config_with("", "", &["openai"])), Some("openai"));
+        assert_eq!(Provider::default_name(&config_with("", "", &[])), None);
+        assert_eq!(Provider::default_name(&config_with("openai", "", &["anthropic", "openai"])), Some("openai"));
+        assert_eq!(Provider::default_name(&config_with("none", "", &["anthropic"])), None);
+        assert_eq!(Provider::default_name(&config_with("unknown", "", &["anthropic"])), None);
+    }
+
+    #[test]
+    fn resolve_none_turns_ai_off() {
+        let config = config_with("", "", &["anthropic"]);
+        assert_eq!(resolved(&settings(Some("none"), None), &config), None);
+        assert_eq!(resolved(&settings(None, None), &config_with("none", "", &["anthropic"])), None);
+    }
+
+    #[test]
+    fn resolve_without_settings_uses_server_default() {
+        assert_eq!(
+            resolved(&settings(None, None), &config_with("", "", &["anthropic"])),
+            Some(("anthropic", anthropic::DEFAULT_MODEL.to_string()))
+        );
+        assert_eq!(
+            resolved(&settings(None, None), &config_with("openai", "qwen2.5", &["anthropic", "openai"])),
+            Some(("openai", "qwen2.5".to_string()))
+        );
+        assert_eq!(resolved(&settings(None, None), &config_with("", "", &[])), None);
+    }
+
+    #[test]
+    fn resolve_chosen_provider_and_model() {
+        let config = config_with("", "", &["anthropic", "perplexity"]);
+        assert_eq!(
+            resolved(&settings(Some("perplexity"), None), &config),
+            Some(("perplexity", perplexity::DEFAULT_MODEL.to_string()))
+        );
+        assert_eq!(
+            resolved(&settings(Some("perplexity"), Some("sonar-pro")), &config),
+            Some(("perplexity", "sonar-pro".to_string()))
+        );
+    }
+
+    #[test]
+    fn resolve_model_without_provider_applies_to_server_default() {
+        assert_eq!(
+            resolved(&settings(None, Some("claude-haiku-4-5")), &config_with("", "other", &["anthropic"])),
+            Some(("anthropic", "claude-haiku-4-5".to_string()))
+        );
+        assert_eq!(resolved(&settings(None, Some("claude-haiku-4-5")), &config_with("", "", &[])), None);
+    }
+
+    #[test]
+    fn resolve_unconfigured_or_unknown_provider_falls_back_to_server_default() {
+        let config = config_with("", "", &["anthropic"]);
+        let fallback = Some(("anthropic", anthropic::DEFAULT_MODEL.to_string()));
+        assert_eq!(resolved(&settings(Some("openai"), Some("llama3.1")), &config), fallback);
+        assert_eq!(resolved(&settings(Some("unknown"), None), &config), fallback);
+        // The mock is not offered unless it is the server default.
+        assert_eq!(resolved(&settings(Some("mock"), None), &config), fallback);
+        assert_eq!(resolved(&settings(Some("openai"), None), &config_with("", "", &[])), None);
+    }
+
+    #[test]
+    fn resolve_mock_when_server_default() {
+        let config = config_with("mock", "", &[]);
+        assert_eq!(
+            resolved(&settings(None, None), &config),
+            Some(("mock", mock::DEFAULT_MODEL.to_string()))
+        );
+        assert_eq!(
+            resolved(&settings(Some("mock"), Some("echo")), &config),
+            Some(("mock", "echo".to_string()))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Any server with an OpenAI-compatible `POST /chat/completions` endpoint:
+//! OpenAI, a local Ollama (`http://localhost:11434/v1`) or llama.cpp
+//! server (`http://localhost:8080/v1`), vLLM, LM Studio, ...
+
+use serde::{Deserialize, Serialize};
+
+use super::{AiProvider, ChatMessage, ProviderInfo};
+use crate::error::AppError;
+
+pub const DEFAULT_MODEL: &str = "llama3.2";
+
+#[derive(Clone)]
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    /// Base URL, e.g. `http://localhost:11434/v1`.
+    url: String,
+    /// Sent as a bearer token when set; local servers usually need none.
+    api_key: String,
+    model: String,
+    /// Service name for error messages.
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+struct CompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<Message<'a>>,
+    max_tokens: u32,
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    content: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(client: reqwest::Client, url: &str, api_key: &str, model: &str) -> Self {
+        OpenAiProvider {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            name: "OpenAI-compatible",
+        }
+    }
+
+    /// Name the service in error messages.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+impl AiProvider for OpenAiProvider {
+    fn metadata(&self) -> ProviderInfo {
+        ProviderInfo {
+            provider: "openai",
+            label: "OpenAI-compatible",
+            model: self.model.clone(),
+        }
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: u32) -> Result<String, AppError> {
+        self.chat(system, &[ChatMessage::user(prompt)], max_tokens).await
+    }
+
+    async fn chat(&self, system: &str, messages: &[ChatMessage], max_tokens: u32) -> Result<String, AppError> {
+        let request = CompletionRequest {
+            model: &self.model,
+            messages: std::iter::once(Message { role: "system", content: system })
+                .chain(messages.iter().map(|m| Message { role: &m.role, content: &m.content }))
+                .collect(),
+            max_tokens,
+            stream: false,
+        };
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.url))
+            .json(&request);
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("{} API error: {}", self.name, e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(AppError::Internal(format!(
+                "{} API returned {}: {}",
+                self.name, status, text
+            )));
+        }
+
+        let parsed: CompletionResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to parse {} response: {}", self.name, e)))?;
+
+        parsed
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .ok_or_else(|| AppError::Internal(format!("Empty response from {}", self.name)))
+    }
+}
@@ -0,0 +1,39 @@
+//! Perplexity's web-search models, through its OpenAI-compatible API.
+
+use super::{AiProvider, ChatMessage, OpenAiProvider, ProviderInfo};
+use crate::error::AppError;
+
+pub const DEFAULT_MODEL: &str = "sonar";
+
+const API_URL: &str = "https://api.perplexity.ai";
+
+#[derive(Clone)]
+pub struct PerplexityProvider {
+    api: OpenAiProvider,
+}
+
+impl PerplexityProvider {
+    pub fn new(client: reqwest::Client, api_key: &str, model: &str) -> Self {
+        PerplexityProvider {
+            api: OpenAiProvider::new(client, API_URL, api_key, model).named("Perplexity"),
+        }
+    }
+}
+
+impl AiProvider for PerplexityProvider {
+    fn metadata(&self) -> ProviderInfo {
+        ProviderInfo {
+            provider: "perplexity",
+            label: "Perplexity",
+            model: self.api.model().to_string(),
+        }
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: u32) -> Result<String, AppError> {
+        self.api.complete(system, prompt, max_tokens).await
+    }
+
+    async fn chat(&self, system: &str, messages: &[ChatMessage], max_tokens: u32) -> Result<String, AppError> {
+        self.api.chat(system, messages, max_tokens).await
+    }
+}
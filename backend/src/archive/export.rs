@@ -0,0 +1,335 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::{export_path, Scope, EXPORT_RETENTION_DAYS, FORMAT, SCHEMA_VERSION, TABLES};
+use crate::error::AppError;
+use crate::routes::media::UPLOADS_DIR;
+use crate::routes::notes::export_filename;
+use crate::tiptap::render::{self, MediaRef, RenderContext};
+
+/// Everything that goes into the archive, gathered from the database up
+/// front so the zip itself can be written on a blocking thread.
+struct ArchiveContents {
+    /// In-memory entries: `(path in zip, bytes)`.
+    entries: Vec<(String, Vec<u8>)>,
+    /// Media files to copy: `(path in zip, path on disk)`.
+    media: Vec<(String, PathBuf)>,
+    manifest: Value,
+}
+
+// ---------------------------------------------------------------------------
+// Job lifecycle
+// ---------------------------------------------------------------------------
+
+/// Run an export job to completion, recording the outcome on its row.
+/// Intended to be spawned; never returns an error.
+pub async fn run_export(pool: PgPool, export_id: Uuid, workspace_id: Uuid) {
+    let update = match build_export(&pool, export_id, workspace_id).await {
+        Ok(size) => sqlx::query(
+            "UPDATE workspace_exports SET status = 'completed', file_size_bytes = $2, \
+             error = NULL, completed_at = now() WHERE id = $1",
+        )
+        .bind(export_id)
+        .bind(size as i64),
+        Err(err) => {
+            if let AppError::Internal(ref msg) = err {
+                tracing::error!("Workspace export {} failed: {}", export_id, msg);
+            }
+            sqlx::query(
+                "UPDATE workspace_exports SET status = 'failed', error = $2, \
+                 completed_at = now() WHERE id = $1",
+            )
+            .bind(export_id)
+            .bind(err.to_string())
+        }
+    };
+
+    if let Err(e) = update.execute(&pool).await {
+        tracing::error!("Failed to record outcome of export {}: {}", export_id, e);
+    }
+}
+
+/// Jobs still pending or running at startup were lost with the previous
+/// process; mark them failed so clients stop polling.
+pub async fn fail_interrupted(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE workspace_exports SET status = 'failed', error = 'Interrupted by server restart', \
+         completed_at = now() WHERE status IN ('pending', 'running')",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete jobs that finished more than [`EXPORT_RETENTION_DAYS`] ago and
+/// their archives. Returns the number of jobs removed.
+pub async fn purge_expired(pool: &PgPool) -> Result<usize, AppError> {
+    let expired: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        "DELETE FROM workspace_exports \
+         WHERE status IN ('completed', 'failed') \
+           AND completed_at <= now() - make_interval(days => $1) \
+         RETURNING id, workspace_id",
+    )
+    .bind(EXPORT_RETENTION_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    for (export_id, workspace_id) in &expired {
+        // Failed jobs may not have left a file.
+        let path = export_path(*workspace_id, *export_id);
+        let _ = tokio::fs::remove_file(&path).await;
+        // Only succeeds once the workspace has no archives left.
+        if let Some(dir) = path.parent() {
+            let _ = tokio::fs::remove_dir(dir).await;
+        }
+    }
+    Ok(expired.len())
+}
+
+async fn build_export(pool: &PgPool, export_id: Uuid, workspace_id: Uuid) -> Result<u64, AppError> {
+    sqlx::query("UPDATE workspace_exports SET status = 'running' WHERE id = $1")
+        .bind(export_id)
+        .execute(pool)
+        .await?;
+
+    let contents = collect(pool, workspace_id, export_id).await?;
+
+    let path = export_path(workspace_id, export_id);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| {
+            AppError::Internal(format!("Failed to create export directory: {e}"))
+        })?;
+    }
+
+    tokio::task::spawn_blocking(move || write_archive(&path, contents))
+        .await
+        .map_err(|e| AppError::Internal(format!("spawn_blocking error: {e}")))?
+}
+
+// ---------------------------------------------------------------------------
+// Collecting rows
+// ---------------------------------------------------------------------------
+
+fn table_query(table: &str, scope: &Scope) -> String {
+    match scope {
+        Scope::Workspace => {
+            format!("SELECT to_jsonb(t) FROM {table} t WHERE t.workspace_id = $1")
+        }
+        Scope::Note(col) => format!(
+            "SELECT to_jsonb(t) FROM {table} t JOIN notes n ON n.id = t.{col} \
+             WHERE n.workspace_id = $1"
+        ),
+        Scope::Conversation => format!(
+            "SELECT to_jsonb(t) FROM {table} t JOIN ai_conversations c ON c.id = t.conversation_id \
+             WHERE c.workspace_id = $1"
+        ),
+    }
+}
+
+async fn collect(pool: &PgPool, workspace_id: Uuid, export_id: Uuid) -> Result<ArchiveContents, AppError> {
+    let workspace_name: String = sqlx::query_scalar("SELECT name FROM workspaces WHERE id = $1")
+        .bind(workspace_id)
+        .fetch_one(pool)
+        .await?;
+
+    let mut entries = Vec::new();
+    let mut counts = Map::new();
+    let mut rows_by_table: HashMap<&str, Vec<Value>> = HashMap::new();
+
+    for (table, scope) in TABLES {
+        let rows: Vec<Value> = sqlx::query_scalar(&table_query(table, scope))
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await?;
+        counts.insert(table.to_string(), json!(rows.len()));
+        entries.push((format!("data/{table}.json"), to_json_bytes(&rows)?));
+        rows_by_table.insert(table, rows);
+    }
+
+    let empty = Vec::new();
+    let rows = |table: &str| rows_by_table.get(table).unwrap_or(&empty);
+
+    // Media files and the archive-relative URLs Markdown images should use.
+    let mut media = Vec::new();
+    let mut seen = HashSet::new();
+    let mut ctx = RenderContext::default();
+    for row in rows("media") {
+        let label = str_field(row, "label").or_else(|| str_field(row, "original_filename"));
+        for key_col in ["s3_key", "thumbnail_s3_key"] {
+            let Some(key) = str_field(row, key_col) else {
+                continue;
+            };
+            let url = if key.starts_with("http://") || key.starts_with("https://") {
+                key.clone()
+            } else {
+                let name = format!("media/{}", key.rsplit('/').next().unwrap_or(&key));
+                if seen.insert(name.clone()) {
+                    media.push((name.clone(), PathBuf::from(UPLOADS_DIR).join(&key)));
+                }
+                format!("../{name}")
+            };
+            if key_col == "s3_key" {
+                if let Some(id) = uuid_field(row, "id") {
+                    ctx.media.insert(id, MediaRef { url, label: label.clone() });
+                }
+            }
+        }
+    }
+
+    // Tag names per note for the Markdown front-matter.
+    let tag_names: HashMap<Uuid, String> = rows("tags")
+        .iter()
+        .filter_map(|t| Some((uuid_field(t, "id")?, str_field(t, "name")?)))
+        .collect();
+    let mut note_tags: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for link in rows("note_tags") {
+        if let (Some(note_id), Some(name)) = (
+            uuid_field(link, "note_id"),
+            uuid_field(link, "tag_id").and_then(|id| tag_names.get(&id)),
+        ) {
+            note_tags.entry(note_id).or_default().push(name.clone());
+        }
+    }
+
+    for note in rows("notes") {
+        if !note["deleted_at"].is_null() {
+            continue;
+        }
+        let Some(id) = uuid_field(note, "id") else {
+            continue;
+        };
+        let title = str_field(note, "title").unwrap_or_default();
+        let short_id = id.simple().to_string();
+        let filename = export_filename(&format!("{title}-{}", &short_id[..8]), "md");
+        let tags = note_tags.get(&id).map(|t| t.as_slice()).unwrap_or(&[]);
+        entries.push((
+            format!("notes/{filename}"),
+            note_markdown(note, tags, &ctx).into_bytes(),
+        ));
+    }
+
+    let manifest = json!({
+        "format": FORMAT,
+        "schema_version": SCHEMA_VERSION,
+        "export_id": export_id,
+        "exported_at": Utc::now(),
+        "workspace": { "id": workspace_id, "name": workspace_name },
+        "counts": counts,
+    });
+
+    Ok(ArchiveContents { entries, media, manifest })
+}
+
+fn str_field(row: &Value, key: &str) -> Option<String> {
+    row.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+fn uuid_field(row: &Value, key: &str) -> Option<Uuid> {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn to_json_bytes(value: &impl serde::Serialize) -> Result<Vec<u8>, AppError> {
+    serde_json::to_vec_pretty(value)
+        .map_err(|e| AppError::Internal(format!("Failed to serialise export: {e}")))
+}
+
+/// Markdown with YAML front-matter using the keys the Markdown importer
+/// understands, so a single note can be round-tripped on its own.
+fn note_markdown(note: &Value, tags: &[String], ctx: &RenderContext) -> String {
+    let mut fm = Map::new();
+    for (key, col) in [
+        ("id", "id"),
+        ("title", "title"),
+        ("type", "note_type"),
+        ("location", "location_name"),
+        ("lat", "location_lat"),
+        ("lng", "location_lng"),
+        ("weather", "weather"),
+        ("temperature_c", "temperature_c"),
+        ("time_start", "time_start"),
+        ("time_end", "time_end"),
+        ("created_at", "created_at"),
+        ("updated_at", "updated_at"),
+    ] {
+        if let Some(value) = note.get(col).filter(|v| !v.is_null()) {
+            fm.insert(key.to_string(), value.clone());
+        }
+    }
+    if !tags.is_empty() {
+        fm.insert("tags".to_string(), json!(tags));
+    }
+
+    let yaml = serde_yaml::to_string(&Value::Object(fm)).unwrap_or_default();
+    let body = render::to_markdown(&note["body"], ctx);
+    format!("---\n{yaml}---\n\n{body}")
+}
+
+// ---------------------------------------------------------------------------
+// Writing the zip
+// ---------------------------------------------------------------------------
+
+fn archive_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Internal(format!("Failed to write export archive: {e}"))
+}
+
+/// Write to a `.part` file and rename on success so a half-written archive
+/// is never served. Returns the final size in bytes.
+fn write_archive(path: &Path, contents: ArchiveContents) -> Result<u64, AppError> {
+    let part = path.with_extension("zip.part");
+    let result = write_zip(&part, contents).and_then(|()| {
+        std::fs::rename(&part, path).map_err(archive_error)?;
+        std::fs::metadata(path).map(|m| m.len()).map_err(archive_error)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&part);
+    }
+    result
+}
+
+fn write_zip(path: &Path, contents: ArchiveContents) -> Result<(), AppError> {
+    let ArchiveContents { entries, media, mut manifest } = contents;
+
+    let mut zip = ZipWriter::new(File::create(path).map_err(archive_error)?);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Photos and audio are already compressed.
+    let stored = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+
+    for (name, bytes) in entries {
+        zip.start_file(name, deflated).map_err(archive_error)?;
+        zip.write_all(&bytes).map_err(archive_error)?;
+    }
+
+    let mut copied = 0usize;
+    let mut missing = Vec::new();
+    for (name, disk_path) in media {
+        match File::open(&disk_path) {
+            Ok(mut file) => {
+                zip.start_file(name, stored).map_err(archive_error)?;
+                std::io::copy(&mut file, &mut zip).map_err(archive_error)?;
+                copied += 1;
+            }
+            Err(_) => missing.push(name),
+        }
+    }
+
+    manifest["media_files"] = json!(copied);
+    manifest["missing_media"] = json!(missing);
+    zip.start_file("manifest.json", deflated).map_err(archive_error)?;
+    zip.write_all(&to_json_bytes(&manifest)?).map_err(archive_error)?;
+
+    zip.finish().map_err(archive_error)?;
+    Ok(())
+}
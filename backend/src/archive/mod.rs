@@ -0,0 +1,74 @@
+//! Workspace export archives: a zip holding every workspace table as JSON,
+//! each note as Markdown, and the media files from `uploads/`.
+//!
+//! Layout (schema version [`SCHEMA_VERSION`]):
+//!
+//! ```text
+//! manifest.json          format, schema version, workspace, row counts
+//! data/{table}.json      one JSON array per entry in [`TABLES`]
+//! notes/{title}-{id}.md  Markdown with YAML front-matter (active notes only)
+//! media/{filename}       files referenced by `media.s3_key`
+//! ```
+
+pub mod export;
+pub mod restore;
+
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+/// Bump when the archive layout or the meaning of a table's columns changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Identifies an ArchiveMind export in `manifest.json`.
+pub const FORMAT: &str = "archivemind-workspace-export";
+
+/// Base directory for generated archives, relative to CWD like `uploads/`.
+pub const EXPORTS_DIR: &str = "exports";
+
+/// Days a finished export's archive is kept before the purge loop deletes
+/// it along with its job.
+pub const EXPORT_RETENTION_DAYS: i32 = 7;
+
+/// How a table's rows are tied to a workspace.
+pub enum Scope {
+    /// The table has its own `workspace_id` column.
+    Workspace,
+    /// Rows belong to a note through the named column.
+    Note(&'static str),
+    /// Rows belong to an AI conversation through `conversation_id`.
+    Conversation,
+}
+
+/// Exported tables, in dependency order (parents before children).
+pub const TABLES: &[(&str, Scope)] = &[
+    ("field_trips", Scope::Workspace),
+    ("entities", Scope::Workspace),
+    ("concepts", Scope::Workspace),
+    ("tags", Scope::Workspace),
+    ("note_templates", Scope::Workspace),
+    ("custom_field_definitions", Scope::Workspace),
+    ("note_types", Scope::Workspace),
+    ("notes", Scope::Workspace),
+    ("note_revisions", Scope::Note("note_id")),
+    ("note_field_trips", Scope::Note("note_id")),
+    ("note_entities", Scope::Note("note_id")),
+    ("note_concepts", Scope::Note("note_id")),
+    ("note_tags", Scope::Note("note_id")),
+    ("note_links", Scope::Note("source_note_id")),
+    ("note_comments", Scope::Note("note_id")),
+    ("media", Scope::Note("note_id")),
+    ("inventory_items", Scope::Workspace),
+    ("routines", Scope::Workspace),
+    ("ai_conversations", Scope::Workspace),
+    ("ai_messages", Scope::Conversation),
+    ("graph_edges", Scope::Workspace),
+    ("saved_searches", Scope::Workspace),
+];
+
+/// Path of a finished export archive on disk.
+pub fn export_path(workspace_id: Uuid, export_id: Uuid) -> PathBuf {
+    PathBuf::from(EXPORTS_DIR)
+        .join(workspace_id.to_string())
+        .join(format!("{export_id}.zip"))
+}
@@ -0,0 +1,467 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use super::{FORMAT, SCHEMA_VERSION, TABLES};
+use crate::error::AppError;
+use crate::routes::media::ensure_upload_dir;
+use crate::routes::notes::recompute_graph_edges;
+use crate::tiptap;
+
+/// Rows per `INSERT … jsonb_populate_recordset` statement.
+const INSERT_BATCH: usize = 500;
+
+/// Bounds on what an archive may unpack to, since every entry read is held
+/// in memory: entries in the zip, bytes per entry, and bytes in total.
+const MAX_ARCHIVE_ENTRIES: usize = 100_000;
+const MAX_ENTRY_BYTES: u64 = 128 * 1024 * 1024;
+const MAX_UNCOMPRESSED_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Derived edge types; these are rebuilt from the link tables rather than
+/// copied from the archive.
+const DERIVED_EDGE_TYPES: &[&str] = &["entity_co_mention", "entity_concept", "note_link"];
+
+/// A parsed export archive.
+pub struct ArchiveData {
+    pub tables: HashMap<String, Vec<Value>>,
+    /// Contents of `media/`, keyed by file name.
+    pub media: HashMap<String, Vec<u8>>,
+}
+
+/// What a restore wrote, per table.
+pub struct RestoreOutcome {
+    pub rows: BTreeMap<String, u64>,
+    pub media_files: usize,
+    pub missing_media: Vec<String>,
+    pub storage_bytes: i64,
+    /// Media files written to `uploads/`, for cleanup if the commit fails.
+    pub written_files: Vec<PathBuf>,
+}
+
+// ---------------------------------------------------------------------------
+// Reading
+// ---------------------------------------------------------------------------
+
+fn invalid(msg: impl std::fmt::Display) -> AppError {
+    AppError::BadRequest(format!("Invalid export archive: {msg}"))
+}
+
+/// Read an entry whole, charging it to `budget`, the uncompressed bytes the
+/// archive may still use. Sizes in the zip headers are not trusted.
+fn read_entry(entry: impl Read, name: &str, budget: &mut u64) -> Result<Vec<u8>, AppError> {
+    let cap = MAX_ENTRY_BYTES.min(*budget);
+    let mut buf = Vec::new();
+    entry.take(cap + 1).read_to_end(&mut buf).map_err(invalid)?;
+    if buf.len() as u64 > cap {
+        return Err(if cap == MAX_ENTRY_BYTES {
+            invalid(format!("{name} is larger than {} MB uncompressed", MAX_ENTRY_BYTES >> 20))
+        } else {
+            invalid(format!("archive is larger than {} MB uncompressed", MAX_UNCOMPRESSED_BYTES >> 20))
+        });
+    }
+    *budget -= buf.len() as u64;
+    Ok(buf)
+}
+
+/// Parse an export zip. CPU-bound; call from a blocking task.
+pub fn read_archive(bytes: Vec<u8>) -> Result<ArchiveData, AppError> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).map_err(invalid)?;
+    if zip.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(invalid(format!("more than {MAX_ARCHIVE_ENTRIES} entries")));
+    }
+    let mut budget = MAX_UNCOMPRESSED_BYTES;
+
+    let manifest: Value = {
+        let entry = zip
+            .by_name("manifest.json")
+            .map_err(|_| invalid("manifest.json is missing"))?;
+        let buf = read_entry(entry, "manifest.json", &mut budget)?;
+        serde_json::from_slice(&buf).map_err(invalid)?
+    };
+    if manifest["format"].as_str() != Some(FORMAT) {
+        return Err(invalid("not an ArchiveMind workspace export"));
+    }
+    let version = manifest["schema_version"].as_u64().unwrap_or(0);
+    if version == 0 || version > SCHEMA_VERSION as u64 {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported export schema version {version} (this server supports up to {SCHEMA_VERSION})"
+        )));
+    }
+
+    let mut tables = HashMap::new();
+    let mut media = HashMap::new();
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i).map_err(invalid)?;
+        let name = entry.name().to_string();
+        if entry.is_dir() {
+            continue;
+        }
+        if let Some(table) = name
+            .strip_prefix("data/")
+            .and_then(|n| n.strip_suffix(".json"))
+        {
+            if TABLES.iter().any(|(t, _)| *t == table) {
+                let buf = read_entry(entry, &name, &mut budget)?;
+                let rows: Vec<Value> = serde_json::from_slice(&buf)
+                    .map_err(|e| invalid(format!("{name}: {e}")))?;
+                tables.insert(table.to_string(), rows);
+            }
+        } else if let Some(file) = name.strip_prefix("media/") {
+            if !file.is_empty() && !file.contains('/') {
+                let buf = read_entry(entry, &name, &mut budget)?;
+                media.insert(file.to_string(), buf);
+            }
+        }
+    }
+
+    Ok(ArchiveData { tables, media })
+}
+
+impl ArchiveData {
+    /// What restoring the archive adds to each plan-limited resource, as
+    /// counted by `plan_guard`. Storage is the larger of the recorded media
+    /// sizes and the files actually present.
+    pub fn usage(&self) -> [(&'static str, i64); 4] {
+        let rows = |table: &str| self.tables.get(table).map(Vec::as_slice).unwrap_or_default();
+        let live_notes = rows("notes")
+            .iter()
+            .filter(|n| n.get("deleted_at").is_none_or(Value::is_null))
+            .count();
+        let recorded: i64 = rows("media")
+            .iter()
+            .filter_map(|m| m.get("file_size_bytes").and_then(Value::as_i64))
+            .sum();
+        let present: i64 = self.media.values().map(|b| b.len() as i64).sum();
+        [
+            ("notes", live_notes as i64),
+            ("entities", rows("entities").len() as i64),
+            ("media_uploads", rows("media").len() as i64),
+            ("storage_bytes", recorded.max(present)),
+        ]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Remapping
+// ---------------------------------------------------------------------------
+
+/// Foreign keys per table, as `(column, required)`. A row whose required key
+/// does not resolve to an imported row is dropped; optional keys are nulled.
+fn foreign_keys(table: &str) -> &'static [(&'static str, bool)] {
+    match table {
+        "note_types" => &[("default_template_id", false)],
+        "notes" => &[("merged_into_id", false)],
+        "note_revisions" => &[("note_id", true)],
+        "note_field_trips" => &[("note_id", true), ("field_trip_id", true)],
+        "note_entities" => &[("note_id", true), ("entity_id", true)],
+        "note_concepts" => &[("note_id", true), ("concept_id", true)],
+        "note_tags" => &[("note_id", true), ("tag_id", true)],
+        "note_links" => &[("source_note_id", true), ("target_note_id", true)],
+        "note_comments" => &[("note_id", true), ("parent_id", false)],
+        "media" => &[("note_id", true)],
+        "routines" => &[("field_trip_id", false)],
+        "ai_conversations" => &[("note_id", false)],
+        "ai_messages" => &[("conversation_id", true)],
+        "graph_edges" => &[("source_id", true), ("target_id", true)],
+        _ => &[],
+    }
+}
+
+fn row_uuid(row: &Map<String, Value>, key: &str) -> Option<Uuid> {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+/// Point a column at the remapped id. Returns `false` if the row must be
+/// dropped because a required reference is unresolved.
+fn remap_column(row: &mut Map<String, Value>, col: &str, required: bool, ids: &HashMap<Uuid, Uuid>) -> bool {
+    match row_uuid(row, col).and_then(|old| ids.get(&old)) {
+        Some(new_id) => {
+            row.insert(col.to_string(), Value::String(new_id.to_string()));
+            true
+        }
+        None if required => false,
+        None => {
+            row.insert(col.to_string(), Value::Null);
+            true
+        }
+    }
+}
+
+/// Give a local `s3_key` a fresh name under the target workspace, queueing
+/// the file copy. External URLs are kept as-is.
+fn remap_file_key(
+    row: &mut Map<String, Value>,
+    col: &str,
+    new_name: String,
+    workspace_id: Uuid,
+    files: &mut Vec<(String, String)>,
+) {
+    let Some(key) = row.get(col).and_then(|v| v.as_str()).map(|s| s.to_string()) else {
+        return;
+    };
+    if key.starts_with("http://") || key.starts_with("https://") {
+        return;
+    }
+    let archive_name = key.rsplit('/').next().unwrap_or(&key).to_string();
+    let ext = archive_name.rsplit_once('.').map(|(_, e)| e).unwrap_or("bin");
+    let new_file = format!("{new_name}.{ext}");
+    row.insert(col.to_string(), Value::String(format!("{workspace_id}/{new_file}")));
+    files.push((archive_name, new_file));
+}
+
+// ---------------------------------------------------------------------------
+// Restore
+// ---------------------------------------------------------------------------
+
+/// Insert rows through `jsonb_populate_recordset` so column types (enums,
+/// JSONB, timestamps) are handled by Postgres. Only columns present both in
+/// the archive and the live table are written; the rest take their defaults.
+async fn insert_rows(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    table: &str,
+    rows: Vec<Value>,
+) -> Result<u64, AppError> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let table_cols: Vec<String> = sqlx::query_scalar(
+        "SELECT column_name::text FROM information_schema.columns \
+         WHERE table_schema = current_schema() AND table_name = $1 ORDER BY ordinal_position",
+    )
+    .bind(table)
+    .fetch_all(&mut **tx)
+    .await?;
+    let cols: Vec<&str> = table_cols
+        .iter()
+        .filter(|c| rows.iter().any(|r| r.get(c.as_str()).is_some()))
+        .map(|c| c.as_str())
+        .collect();
+    let cols = cols.join(", ");
+
+    let sql = format!(
+        "INSERT INTO {table} ({cols}) \
+         SELECT {cols} FROM jsonb_populate_recordset(NULL::{table}, $1) \
+         ON CONFLICT DO NOTHING"
+    );
+    let mut inserted = 0;
+    for chunk in rows.chunks(INSERT_BATCH) {
+        inserted += sqlx::query(&sql)
+            .bind(Value::Array(chunk.to_vec()))
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+    }
+    Ok(inserted)
+}
+
+/// Restore `archive` into `workspace_id` inside `tx`.
+///
+/// Every row receives a new UUID and all references — foreign keys, mention
+/// ids inside Tiptap bodies, media URLs — are rewritten to match. Tags,
+/// concepts and templates whose name already exists in the workspace are
+/// merged into the existing row; references to any other row skipped on a
+/// conflict are nulled, or drop the referring row. Media files are written to `uploads/{workspace_id}/`; on
+/// error, files already written are removed before returning. Derived graph
+/// edges are recomputed; curated ones are copied.
+pub async fn restore(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    archive: ArchiveData,
+) -> Result<RestoreOutcome, AppError> {
+    let ArchiveData { mut tables, media } = archive;
+
+    // Names already taken in the target workspace (unique per workspace).
+    let mut existing: HashMap<&str, HashMap<String, Uuid>> = HashMap::new();
+    for table in ["tags", "concepts", "note_templates"] {
+        let rows: Vec<(Uuid, String)> = sqlx::query_as(&format!(
+            "SELECT id, name FROM {table} WHERE workspace_id = $1"
+        ))
+        .bind(workspace_id)
+        .fetch_all(&mut **tx)
+        .await?;
+        existing.insert(table, rows.into_iter().map(|(id, name)| (name, id)).collect());
+    }
+
+    // Pass 1: allocate a new id for every row so later tables (and note
+    // bodies) can refer to rows that are inserted after them.
+    let mut ids: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut merged: HashSet<Uuid> = HashSet::new();
+    for (table, _) in TABLES {
+        for row in tables.get(*table).into_iter().flatten() {
+            let Some(old_id) = row.as_object().and_then(|r| row_uuid(r, "id")) else {
+                continue;
+            };
+            let existing_id = existing
+                .get(table)
+                .and_then(|names| names.get(row["name"].as_str().unwrap_or_default()));
+            match existing_id {
+                Some(id) => {
+                    ids.insert(old_id, *id);
+                    merged.insert(old_id);
+                }
+                None => {
+                    ids.insert(old_id, Uuid::new_v4());
+                }
+            }
+        }
+    }
+
+    // Pass 2: rewrite and insert, table by table in dependency order.
+    let mut outcome = RestoreOutcome {
+        rows: BTreeMap::new(),
+        media_files: 0,
+        missing_media: Vec::new(),
+        storage_bytes: 0,
+        written_files: Vec::new(),
+    };
+    let mut files: Vec<(String, String)> = Vec::new();
+
+    for (table, _) in TABLES {
+        let rows = tables.remove(*table).unwrap_or_default();
+        let mut out = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let Value::Object(mut row) = row else {
+                continue;
+            };
+
+            if let Some(old_id) = row_uuid(&row, "id") {
+                // Merged into an existing row by name: nothing to insert.
+                if merged.contains(&old_id) {
+                    continue;
+                }
+                if let Some(new_id) = ids.get(&old_id) {
+                    row.insert("id".to_string(), Value::String(new_id.to_string()));
+                }
+            }
+            if row.contains_key("workspace_id") {
+                row.insert("workspace_id".to_string(), Value::String(workspace_id.to_string()));
+            }
+            if !foreign_keys(table)
+                .iter()
+                .all(|(col, required)| remap_column(&mut row, col, *required, &ids))
+            {
+                continue;
+            }
+
+            match *table {
+                "notes" | "note_revisions" => {
+                    if let Some(body) = row.get_mut("body") {
+                        tiptap::remap_ids(body, &ids);
+                    }
+                    // Authors do not exist on the target instance.
+                    if row.contains_key("created_by") {
+                        row.insert("created_by".to_string(), Value::Null);
+                    }
+                }
+                "note_templates" => {
+                    if let Some(body) = row.get_mut("body") {
+                        tiptap::remap_ids(body, &ids);
+                    }
+                    if let Some(Value::Array(concepts)) = row.get_mut("default_concept_ids") {
+                        concepts.retain_mut(|c| {
+                            match c.as_str().and_then(|s| Uuid::parse_str(s).ok()).and_then(|old| ids.get(&old)) {
+                                Some(new_id) => {
+                                    *c = Value::String(new_id.to_string());
+                                    true
+                                }
+                                None => false,
+                            }
+                        });
+                    }
+                }
+                "saved_searches" => {
+                    row.insert("created_by".to_string(), Value::Null);
+                    // Seen notes were archived under their old ids.
+                    row.insert("seen_note_ids".to_string(), Value::Null);
+                    // Ids that did not resolve are kept; they match nothing,
+                    // as the deleted trip or entity would.
+                    if let Some(Value::Object(filters)) = row.get_mut("filters") {
+                        for col in ["field_trip_id", "concept_id", "entity_id"] {
+                            if let Some(new_id) = row_uuid(filters, col).and_then(|old| ids.get(&old)) {
+                                filters.insert(col.to_string(), Value::String(new_id.to_string()));
+                            }
+                        }
+                    }
+                }
+                "note_comments" => {
+                    for col in ["author_id", "resolved_by"] {
+                        row.insert(col.to_string(), Value::Null);
+                    }
+                }
+                "media" => {
+                    let id = row_uuid(&row, "id").unwrap_or_else(Uuid::new_v4);
+                    remap_file_key(&mut row, "s3_key", id.to_string(), workspace_id, &mut files);
+                    remap_file_key(&mut row, "thumbnail_s3_key", format!("{id}_thumb"), workspace_id, &mut files);
+                    outcome.storage_bytes += row.get("file_size_bytes").and_then(|v| v.as_i64()).unwrap_or(0);
+                }
+                "graph_edges" => {
+                    let edge_type = row.get("edge_type").and_then(|v| v.as_str()).unwrap_or_default();
+                    if DERIVED_EDGE_TYPES.contains(&edge_type) {
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            out.push(Value::Object(row));
+        }
+
+        let new_ids: Vec<Uuid> = out
+            .iter()
+            .filter_map(|r| r.as_object().and_then(|r| row_uuid(r, "id")))
+            .collect();
+        let expected = out.len() as u64;
+        let inserted = insert_rows(tx, table, out).await?;
+        outcome.rows.insert(table.to_string(), inserted);
+
+        // Rows skipped by `ON CONFLICT DO NOTHING` must not be referenced
+        // by later tables.
+        if inserted < expected && !new_ids.is_empty() {
+            let present: HashSet<Uuid> = sqlx::query_scalar(&format!(
+                "SELECT id FROM {table} WHERE id = ANY($1)"
+            ))
+            .bind(&new_ids)
+            .fetch_all(&mut **tx)
+            .await?
+            .into_iter()
+            .collect();
+            let skipped: HashSet<Uuid> = new_ids.into_iter().filter(|id| !present.contains(id)).collect();
+            ids.retain(|_, new_id| !skipped.contains(new_id));
+        }
+    }
+
+    recompute_graph_edges(tx, workspace_id).await?;
+
+    // Copy media last so a failed insert never leaves orphaned files.
+    let upload_dir = ensure_upload_dir(&workspace_id).await?;
+    for (archive_name, new_file) in files {
+        let Some(bytes) = media.get(&archive_name) else {
+            outcome.missing_media.push(archive_name);
+            continue;
+        };
+        let path = upload_dir.join(&new_file);
+        if let Err(e) = tokio::fs::write(&path, bytes).await {
+            discard_media(&outcome).await;
+            return Err(AppError::Internal(format!("Failed to write media file: {e}")));
+        }
+        outcome.written_files.push(path);
+        outcome.media_files += 1;
+    }
+
+    Ok(outcome)
+}
+
+/// Delete the media files a restore wrote, for when its transaction fails
+/// to commit afterwards.
+pub async fn discard_media(outcome: &RestoreOutcome) {
+    for path in &outcome.written_files {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+}
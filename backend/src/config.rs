@@ -12,6 +12,26 @@ pub struct Config {
     pub lemonsqueezy_api_key: String,
     pub perplexity_api_key: String,
     pub anthropic_api_key: String,
+    /// Default AI provider for workspaces without their own: `anthropic`,
+    /// `perplexity`, `openai` (any OpenAI-compatible server), `mock` or
+    /// `none`. Empty picks the first provider with an API key.
+    pub ai_provider: String,
+    /// Model of the default provider; empty uses the provider's default.
+    pub ai_model: String,
+    /// Base URL of the OpenAI-compatible server, e.g.
+    /// `http://localhost:11434/v1` for Ollama. Empty disables `openai`.
+    pub ai_api_url: String,
+    pub ai_api_key: String,
+    /// How often expired notes are purged from the trash.
+    pub trash_purge_interval_secs: u64,
+    /// `hashed` (local, no network) or `openai` (any OpenAI-compatible
+    /// `/embeddings` endpoint).
+    pub embedding_provider: String,
+    pub embedding_api_url: String,
+    pub embedding_api_key: String,
+    pub embedding_model: String,
+    /// Vector size of the `hashed` provider.
+    pub embedding_dimensions: usize,
 }
 
 impl Config {
@@ -41,6 +61,30 @@ impl Config {
                 .unwrap_or_else(|_| String::new()),
             anthropic_api_key: env::var("ANTHROPIC_API_KEY")
                 .unwrap_or_else(|_| String::new()),
+            ai_provider: env::var("AI_PROVIDER")
+                .unwrap_or_else(|_| String::new()),
+            ai_model: env::var("AI_MODEL")
+                .unwrap_or_else(|_| String::new()),
+            ai_api_url: env::var("AI_API_URL")
+                .unwrap_or_else(|_| String::new()),
+            ai_api_key: env::var("AI_API_KEY")
+                .unwrap_or_else(|_| String::new()),
+            trash_purge_interval_secs: env::var("TRASH_PURGE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .expect("TRASH_PURGE_INTERVAL_SECS must be a number"),
+            embedding_provider: env::var("EMBEDDING_PROVIDER")
+                .unwrap_or_else(|_| "hashed".to_string()),
+            embedding_api_url: env::var("EMBEDDING_API_URL")
+                .unwrap_or_else(|_| "https://api.openai.com/v1".to_string()),
+            embedding_api_key: env::var("EMBEDDING_API_KEY")
+                .unwrap_or_else(|_| String::new()),
+            embedding_model: env::var("EMBEDDING_MODEL")
+                .unwrap_or_else(|_| "text-embedding-3-small".to_string()),
+            embedding_dimensions: env::var("EMBEDDING_DIMENSIONS")
+                .unwrap_or_else(|_| "256".to_string())
+                .parse()
+                .expect("EMBEDDING_DIMENSIONS must be a number"),
         }
     }
 }
@@ -0,0 +1,130 @@
+//! Local embeddings from hashed word and character n-grams ("feature
+//! hashing"). Deterministic and free, with no network access; texts that
+//! share words or word fragments end up close, but synonyms do not.
+
+use super::{normalize, Embedder};
+use crate::error::AppError;
+
+#[derive(Clone)]
+pub struct HashedEmbedder {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashedEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        HashedEmbedder {
+            dimensions,
+            model: format!("hashed-ngram-{dimensions}"),
+        }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            let hash = fnv1a(feature.as_bytes());
+            // The top bit picks the sign, so collisions tend to cancel out.
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[(hash % self.dimensions as u64) as usize] += sign * weight;
+        };
+
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        for (i, word) in words.iter().enumerate() {
+            add(word, 1.0);
+            if let Some(next) = words.get(i + 1) {
+                add(&format!("{word} {next}"), 0.5);
+            }
+            // Trigrams of the word with boundary markers, so inflections and
+            // misspellings share most features.
+            let chars: Vec<char> = format!("<{word}>").chars().collect();
+            for gram in chars.windows(3) {
+                add(&gram.iter().collect::<String>(), 0.25);
+            }
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+/// 64-bit FNV-1a; stable across builds and platforms, unlike std's hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl Embedder for HashedEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        Ok(texts.iter().map(|t| self.embed_one(t)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    async fn embed(embedder: &HashedEmbedder, texts: &[&str]) -> Vec<Vec<f32>> {
+        let texts: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+        embedder.embed(&texts).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn same_text_gives_same_vector() {
+        let text = "Harvest ritual at the Kandy temple";
+        let first = embed(&HashedEmbedder::new(256), &[text, text]).await;
+        let second = embed(&HashedEmbedder::new(256), &[text]).await;
+        assert_eq!(first[0], first[1]);
+        assert_eq!(first[0], second[0]);
+        assert_eq!(HashedEmbedder::new(256).model(), "hashed-ngram-256");
+    }
+
+    #[tokio::test]
+    async fn vectors_are_unit_length() {
+        let vectors = embed(
+            &HashedEmbedder::new(128),
+            &["rice", "The monsoon arrived early in the hill country this year"],
+        )
+        .await;
+        for vector in vectors {
+            assert_eq!(vector.len(), 128);
+            let length = dot(&vector, &vector).sqrt();
+            assert!((length - 1.0).abs() < 1e-5, "length {length}");
+        }
+    }
+
+    #[tokio::test]
+    async fn similar_texts_score_closer_than_unrelated() {
+        let vectors = embed(
+            &HashedEmbedder::new(256),
+            &[
+                "the rice harvest ceremony in the village",
+                "village rice harvests and their ceremonies",
+                "bus timetable from Colombo to Galle",
+            ],
+        )
+        .await;
+        let similar = dot(&vectors[0], &vectors[1]);
+        let unrelated = dot(&vectors[0], &vectors[2]);
+        assert!(similar > unrelated, "similar {similar}, unrelated {unrelated}");
+    }
+
+    #[tokio::test]
+    async fn empty_text_gives_zero_vector() {
+        let vectors = embed(&HashedEmbedder::new(16), &["  ", ""]).await;
+        assert!(vectors.iter().flatten().all(|x| *x == 0.0));
+    }
+}
@@ -0,0 +1,108 @@
+//! Embeddings from an OpenAI-compatible `POST /embeddings` endpoint (OpenAI,
+//! Azure OpenAI, Ollama, vLLM, LM Studio, ...).
+
+use serde::{Deserialize, Serialize};
+
+use super::{normalize, Embedder};
+use crate::error::AppError;
+
+/// Texts sent per request, below every common provider's input limit.
+const BATCH_SIZE: usize = 64;
+
+#[derive(Clone)]
+pub struct HttpEmbedder {
+    client: reqwest::Client,
+    /// Base URL, e.g. `https://api.openai.com/v1`.
+    url: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl HttpEmbedder {
+    pub fn new(client: reqwest::Client, url: &str, api_key: &str, model: &str) -> Self {
+        HttpEmbedder {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        let mut request = self
+            .client
+            .post(format!("{}/embeddings", self.url))
+            .json(&EmbeddingRequest { model: &self.model, input: texts });
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Embedding API error: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(AppError::Internal(format!(
+                "Embedding API returned {}: {}",
+                status, text
+            )));
+        }
+
+        let mut parsed: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to parse embedding response: {}", e)))?;
+
+        if parsed.data.len() != texts.len() {
+            return Err(AppError::Internal(format!(
+                "Embedding API returned {} vectors for {} inputs",
+                parsed.data.len(),
+                texts.len()
+            )));
+        }
+        parsed.data.sort_by_key(|d| d.index);
+        Ok(parsed
+            .data
+            .into_iter()
+            .map(|d| {
+                let mut vector = d.embedding;
+                normalize(&mut vector);
+                vector
+            })
+            .collect())
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+}
@@ -0,0 +1,296 @@
+//! Note embeddings for semantic search. Notes are split into overlapping
+//! windows of words; each window is embedded together with the note's title
+//! and stored in `note_chunks`. All vectors are unit length, so their dot
+//! product (`embedding_similarity` in SQL) is their cosine similarity.
+
+pub mod hashed;
+pub mod http;
+
+use std::future::Future;
+
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+
+pub use hashed::HashedEmbedder;
+pub use http::HttpEmbedder;
+
+/// Turns texts into vectors in a fixed space named by `model`.
+pub trait Embedder {
+    /// Identifies the vector space; stored with every chunk.
+    fn model(&self) -> &str;
+
+    /// One unit-length vector per text, in order.
+    fn embed(&self, texts: &[String]) -> impl Future<Output = Result<Vec<Vec<f32>>, AppError>> + Send;
+}
+
+/// The embedder selected by `EMBEDDING_PROVIDER`.
+#[derive(Clone)]
+pub enum EmbeddingProvider {
+    Hashed(HashedEmbedder),
+    Http(HttpEmbedder),
+}
+
+impl EmbeddingProvider {
+    pub fn from_config(config: &Config, client: reqwest::Client) -> Self {
+        match config.embedding_provider.as_str() {
+            "openai" => EmbeddingProvider::Http(HttpEmbedder::new(
+                client,
+                &config.embedding_api_url,
+                &config.embedding_api_key,
+                &config.embedding_model,
+            )),
+            "hashed" => EmbeddingProvider::Hashed(HashedEmbedder::new(config.embedding_dimensions)),
+            other => {
+                tracing::warn!("Unknown EMBEDDING_PROVIDER '{}'; using hashed", other);
+                EmbeddingProvider::Hashed(HashedEmbedder::new(config.embedding_dimensions))
+            }
+        }
+    }
+}
+
+impl Embedder for EmbeddingProvider {
+    fn model(&self) -> &str {
+        match self {
+            EmbeddingProvider::Hashed(e) => e.model(),
+            EmbeddingProvider::Http(e) => e.model(),
+        }
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        match self {
+            EmbeddingProvider::Hashed(e) => e.embed(texts).await,
+            EmbeddingProvider::Http(e) => e.embed(texts).await,
+        }
+    }
+}
+
+/// Scale a vector to unit length; zero vectors are left as they are.
+pub fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Chunking
+// ---------------------------------------------------------------------------
+
+/// Words per chunk, and words repeated from the end of the previous chunk
+/// so a passage split across a boundary still matches as a whole.
+const CHUNK_WORDS: usize = 150;
+const CHUNK_OVERLAP: usize = 30;
+
+/// Split body text into chunks. A note with no body gets a single chunk of
+/// its title, so it can still be found.
+fn chunk_text(title: &str, body_text: &str) -> Vec<String> {
+    let words: Vec<&str> = body_text.split_whitespace().collect();
+    if words.is_empty() {
+        let title = title.trim();
+        return if title.is_empty() { Vec::new() } else { vec![title.to_string()] };
+    }
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + CHUNK_WORDS).min(words.len());
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP;
+    }
+    chunks
+}
+
+fn source_hash(model: &str, title: &str, body_text: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [model, title, body_text] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+// ---------------------------------------------------------------------------
+// Storage
+// ---------------------------------------------------------------------------
+
+/// Re-embed a note's chunks unless they are current. Chunks of other models
+/// are replaced too.
+pub async fn embed_note<E: Embedder>(pool: &PgPool, embedder: &E, note_id: Uuid) -> Result<(), AppError> {
+    let Some((title, body_text)) = sqlx::query_as::<_, (String, String)>(
+        "SELECT title, coalesce(body_text, '') FROM notes WHERE id = $1",
+    )
+    .bind(note_id)
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let model = embedder.model().to_string();
+    let hash = source_hash(&model, &title, &body_text);
+    let current: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM note_chunks WHERE note_id = $1 AND model = $2 AND source_hash = $3)",
+    )
+    .bind(note_id)
+    .bind(&model)
+    .bind(&hash)
+    .fetch_one(pool)
+    .await?;
+    if current {
+        return Ok(());
+    }
+
+    let chunks = chunk_text(&title, &body_text);
+    let inputs: Vec<String> = chunks.iter().map(|c| format!("{title}\n\n{c}")).collect();
+    let vectors = embedder.embed(&inputs).await?;
+    if vectors.len() != chunks.len() {
+        return Err(AppError::Internal(format!(
+            "Embedder returned {} vectors for {} chunks",
+            vectors.len(),
+            chunks.len()
+        )));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // The note may have been edited while embedding; that edit's own run
+    // writes its chunks instead.
+    let still_current = sqlx::query_as::<_, (String, String)>(
+        "SELECT title, coalesce(body_text, '') FROM notes WHERE id = $1 FOR SHARE",
+    )
+    .bind(note_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .is_some_and(|(t, b)| source_hash(&model, &t, &b) == hash);
+    if !still_current {
+        return Ok(());
+    }
+
+    sqlx::query("DELETE FROM note_chunks WHERE note_id = $1")
+        .bind(note_id)
+        .execute(&mut *tx)
+        .await?;
+    for (i, (content, vector)) in chunks.iter().zip(&vectors).enumerate() {
+        sqlx::query(
+            "INSERT INTO note_chunks (note_id, chunk_index, content, model, source_hash, embedding) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(note_id)
+        .bind(i as i32)
+        .bind(content)
+        .bind(&model)
+        .bind(&hash)
+        .bind(vector)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(())
+}
+
+/// Embed a note in the background after its text changed, so requests do
+/// not wait on the embedding provider.
+pub fn spawn_embed_note(pool: PgPool, embedder: EmbeddingProvider, note_id: Uuid) {
+    tokio::spawn(async move {
+        if let Err(e) = embed_note(&pool, &embedder, note_id).await {
+            tracing::warn!("Failed to embed note {}: {}", note_id, e);
+        }
+    });
+}
+
+/// Embed live notes that have no chunks for the current model: notes
+/// written before embeddings existed or by imports, and every note after
+/// the provider changes. `None` covers all workspaces.
+pub async fn backfill(pool: PgPool, embedder: EmbeddingProvider, workspace_id: Option<Uuid>) {
+    let ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT n.id FROM notes n \
+         WHERE ($1::uuid IS NULL OR n.workspace_id = $1) AND n.deleted_at IS NULL \
+         AND NOT EXISTS (SELECT 1 FROM note_chunks c WHERE c.note_id = n.id AND c.model = $2) \
+         ORDER BY n.updated_at DESC",
+    )
+    .bind(workspace_id)
+    .bind(embedder.model())
+    .fetch_all(&pool)
+    .await;
+
+    let ids = match ids {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::warn!("Failed to list notes to embed: {}", e);
+            return;
+        }
+    };
+    if !ids.is_empty() {
+        tracing::info!("Embedding {} notes with {}", ids.len(), embedder.model());
+    }
+    for id in ids {
+        if let Err(e) = embed_note(&pool, &embedder, id).await {
+            tracing::warn!("Failed to embed note {}: {}", id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(range: std::ops::Range<usize>) -> String {
+        range.map(|i| format!("w{i}")).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn short_body_is_one_chunk() {
+        assert_eq!(chunk_text("Title", "  one\ntwo   three "), vec!["one two three"]);
+        assert_eq!(chunk_text("Title", &words(0..CHUNK_WORDS)), vec![words(0..CHUNK_WORDS)]);
+    }
+
+    #[test]
+    fn chunks_overlap_at_boundaries() {
+        let chunks = chunk_text("Title", &words(0..300));
+        let step = CHUNK_WORDS - CHUNK_OVERLAP;
+        assert_eq!(
+            chunks,
+            vec![
+                words(0..CHUNK_WORDS),
+                words(step..step + CHUNK_WORDS),
+                words(2 * step..300),
+            ]
+        );
+    }
+
+    #[test]
+    fn chunk_ending_exactly_at_last_word_stops() {
+        let step = CHUNK_WORDS - CHUNK_OVERLAP;
+        let chunks = chunk_text("Title", &words(0..step + CHUNK_WORDS));
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1], words(step..step + CHUNK_WORDS));
+    }
+
+    #[test]
+    fn empty_body_falls_back_to_title() {
+        assert_eq!(chunk_text("  Kandy perahera ", " \n "), vec!["Kandy perahera"]);
+        assert!(chunk_text("   ", "").is_empty());
+    }
+
+    #[test]
+    fn normalize_leaves_zero_vectors() {
+        let mut zero = vec![0.0; 3];
+        normalize(&mut zero);
+        assert_eq!(zero, vec![0.0; 3]);
+        let mut v = vec![3.0, 4.0];
+        normalize(&mut v);
+        assert_eq!(v, vec![0.6, 0.8]);
+    }
+}
@@ -5,10 +5,15 @@ use serde_json::json;
 #[derive(Debug)]
 pub enum AppError {
     BadRequest(String),
+    /// 400 with machine-readable `details`, e.g. where a search query failed
+    /// to parse.
+    BadRequestDetails(String, serde_json::Value),
     Unauthorized(String),
     Forbidden(String),
     NotFound(String),
     Conflict(String),
+    /// 412 — an `If-Match` precondition failed; carries the current server copy.
+    PreconditionFailed(String, serde_json::Value),
     Internal(String),
 }
 
@@ -16,10 +21,18 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::BadRequestDetails(msg, details) => {
+                let body = axum::Json(json!({ "error": msg, "details": details }));
+                return (StatusCode::BAD_REQUEST, body).into_response();
+            }
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::PreconditionFailed(msg, current) => {
+                let body = axum::Json(json!({ "error": msg, "current": current }));
+                return (StatusCode::PRECONDITION_FAILED, body).into_response();
+            }
             AppError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (
@@ -34,6 +47,23 @@ impl IntoResponse for AppError {
     }
 }
 
+/// Client-facing message, as it would appear in the response body. Internal
+/// details are not exposed.
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::BadRequest(msg)
+            | AppError::BadRequestDetails(msg, _)
+            | AppError::Unauthorized(msg)
+            | AppError::Forbidden(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::PreconditionFailed(msg, _) => f.write_str(msg),
+            AppError::Internal(_) => f.write_str("Internal server error"),
+        }
+    }
+}
+
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
         match err {
@@ -1,11 +1,17 @@
+mod archive;
+mod ai;
 mod auth;
 mod config;
+mod embeddings;
 mod error;
 pub mod middleware;
 mod models;
 mod response;
 mod routes;
+mod search;
 mod seed;
+mod tiptap;
+mod trash;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -91,6 +97,15 @@ async fn main() {
 
     tracing::info!("Migrations applied successfully");
 
+    if let Err(e) = archive::export::fail_interrupted(&pool).await {
+        tracing::warn!("Failed to mark interrupted exports: {}", e);
+    }
+
+    tokio::spawn(trash::run_purge_loop(
+        pool.clone(),
+        std::time::Duration::from_secs(config.trash_purge_interval_secs),
+    ));
+
     // Keep a clone for the graceful-shutdown pool drain (PgPool is Arc-backed).
     let pool_shutdown = pool.clone();
 
@@ -100,6 +115,23 @@ async fn main() {
         .build()
         .expect("Failed to create HTTP client");
 
+    let embedder = embeddings::EmbeddingProvider::from_config(&config, http_client.clone());
+    tokio::spawn(embeddings::backfill(pool.clone(), embedder.clone(), None));
+
+    if !config.ai_provider.is_empty()
+        && config.ai_provider != "none"
+        && !ai::PROVIDERS.contains(&config.ai_provider.as_str())
+    {
+        tracing::warn!("Unknown AI_PROVIDER '{}'; AI features are off by default", config.ai_provider);
+    }
+    match ai::Provider::server_default(&config, &http_client) {
+        Some(provider) => {
+            let info = ai::AiProvider::metadata(&provider);
+            tracing::info!("Default AI provider: {} ({})", info.provider, info.model);
+        }
+        None => tracing::info!("No default AI provider configured"),
+    }
+
     // --- 3.4 Rate limiting ---
     // 10 requests/second sustained, burst of 30.
     // Uses PeerIpKeyExtractor by default, which requires connect_info.
@@ -122,7 +154,8 @@ async fn main() {
     let cors = CorsLayer::new()
         .allow_origin(cors_origin)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([header::ETAG]);
 
     // TimeoutLayer's error type (Box<dyn Error>) is not Into<Infallible>.
     // Wrap it with HandleErrorLayer so the Router keeps its Infallible bound.
@@ -135,7 +168,7 @@ async fn main() {
         .layer(TimeoutLayer::new(std::time::Duration::from_secs(30)));
 
     // Layer order (bottom-up evaluation): TraceLayer → timeout → governor → cors → correlation_id → security headers → body limit → router
-    let app = routes::build_router(pool, config.clone(), http_client)
+    let app = routes::build_router(pool, config.clone(), http_client, embedder)
         // --- Body size limit (50 MB) ---
         .layer(DefaultBodyLimit::max(50 * 1024 * 1024))
         // --- Security response headers ---
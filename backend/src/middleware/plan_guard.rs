@@ -118,13 +118,29 @@ async fn ensure_usage_record(
 /// Check whether the user has reached their plan limit for the given resource.
 /// Returns `Ok(())` if under the limit, or `Err(AppError)` with a 403 Forbidden.
 ///
-/// `resource` should be one of: "notes", "entities", "media_uploads", "map_loads", "storage_bytes".
+/// `resource` should be one of: "notes", "entities", "media_uploads", "map_loads", "storage_bytes",
+/// "workspaces".
 pub async fn check_limit(
     pool: &PgPool,
     user_id: Uuid,
     workspace_id: Uuid,
     resource: &str,
 ) -> Result<(), AppError> {
+    check_quota(pool, user_id, workspace_id, resource, 1).await
+}
+
+/// Check that `amount` more of `resource` (e.g. the contents of an import)
+/// fits within the user's plan limit.
+pub async fn check_quota(
+    pool: &PgPool,
+    user_id: Uuid,
+    workspace_id: Uuid,
+    resource: &str,
+    amount: i64,
+) -> Result<(), AppError> {
+    if amount <= 0 {
+        return Ok(());
+    }
     let tier = get_user_tier(pool, user_id).await?;
     let limits = PlanLimits::for_tier(tier);
 
@@ -182,6 +198,13 @@ pub async fn check_limit(
             .await
             .unwrap_or(0)
         }
+        "workspaces" => {
+            sqlx::query_scalar("SELECT COUNT(*) FROM workspaces WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(pool)
+                .await
+                .unwrap_or(0)
+        }
         "map_loads" => {
             let usage = ensure_usage_record(pool, user_id, workspace_id).await?;
             usage.map_loads as i64
@@ -193,11 +216,16 @@ pub async fn check_limit(
         _ => 0,
     };
 
-    if current >= limit {
+    if current + amount > limit {
         let tier_name = serde_json::to_string(&tier)
             .unwrap_or_default()
             .trim_matches('"')
             .to_string();
+        if amount > 1 {
+            return Err(AppError::Forbidden(format!(
+                "Plan limit exceeded for {resource}. Current: {current}, Adding: {amount}, Limit: {limit} ({tier_name} plan). Upgrade your plan for higher limits."
+            )));
+        }
         return Err(AppError::Forbidden(format!(
             "Plan limit reached for {resource}. Current: {current}, Limit: {limit} ({tier_name} plan). Upgrade your plan for higher limits."
         )));
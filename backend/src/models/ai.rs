@@ -30,6 +30,15 @@ pub struct AiMessage {
     pub created_at: DateTime<Utc>,
 }
 
+/// A workspace's choice of AI provider and model; `None` uses the server
+/// default.
+#[derive(Debug, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AiSettings {
+    /// One of `ai::PROVIDERS`, or `none` to turn AI features off.
+    pub ai_provider: Option<String>,
+    pub ai_model: Option<String>,
+}
+
 // ---------------------------------------------------------------------------
 // CRUD
 // ---------------------------------------------------------------------------
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A comment on a note, optionally anchored to a text range of its body.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Comment {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    /// Thread root this comment replies to; `None` for a root.
+    pub parent_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub author_initials: Option<String>,
+    pub body: String,
+    /// ProseMirror positions of the commented range in the note body.
+    pub anchor_from: Option<i32>,
+    pub anchor_to: Option<i32>,
+    /// The text the range covered when the comment was made.
+    pub anchor_text: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A root comment with its replies, oldest first.
+#[derive(Debug, Serialize)]
+pub struct CommentThread {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub replies: Vec<Comment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateComment {
+    pub body: String,
+    /// Reply to this comment's thread. Replies cannot be anchored.
+    pub parent_id: Option<Uuid>,
+    pub anchor_from: Option<i32>,
+    pub anchor_to: Option<i32>,
+    pub anchor_text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateComment {
+    pub body: String,
+}
+
+/// Query string of `GET /api/v1/notes/{id}/comments`.
+#[derive(Debug, Deserialize)]
+pub struct CommentFilters {
+    /// Only resolved (`true`) or open (`false`) threads; all when omitted.
+    pub resolved: Option<bool>,
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A workspace-defined note field. Values live in `notes.custom_fields`
+/// under `key`.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CustomFieldDefinition {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub key: String,
+    pub label: String,
+    /// `text`, `number`, `date`, `enum` or `boolean`
+    pub field_type: String,
+    /// Allowed values of an `enum` field; empty for other types.
+    pub options: Vec<String>,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCustomField {
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// `key` and `field_type` are fixed once created, since notes already store
+/// values under them.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCustomField {
+    pub label: Option<String>,
+    pub options: Option<Vec<String>>,
+    pub sort_order: Option<i32>,
+}
+
+/// One condition of `NoteFilters::fields`, resolved against the workspace's
+/// definitions so `value` has the field's JSON type.
+#[derive(Debug)]
+pub struct FieldFilter {
+    pub key: String,
+    /// SQL comparison: `=`, `<`, `<=`, `>` or `>=`
+    pub op: &'static str,
+    pub value: serde_json::Value,
+}
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A workspace export job. The archive itself lives on disk under
+/// `exports/{workspace_id}/{id}.zip` once `status` is `completed`.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct WorkspaceExport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub requested_by: Option<Uuid>,
+    pub status: String,
+    pub file_size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub failed: usize,
+    pub files: Vec<ImportFileResult>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Imported,
+    Failed,
+}
+
+/// Outcome for a single file of an import archive.
+#[derive(Debug, Serialize)]
+pub struct ImportFileResult {
+    pub path: String,
+    pub status: ImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `[[Wiki links]]` that matched no entity or concept and were kept as text.
+    pub unresolved_links: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of restoring a workspace export archive.
+#[derive(Debug, Serialize)]
+pub struct WorkspaceImportReport {
+    pub workspace_id: Uuid,
+    /// Set when the archive was restored into a newly created workspace: a
+    /// session token scoped to it. `POST /api/v1/auth/switch-workspace`
+    /// issues one later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Rows inserted per table.
+    pub imported: BTreeMap<String, u64>,
+    pub media_files: usize,
+    /// Media referenced by the archive data but absent from `media/`.
+    pub missing_media: Vec<String>,
+}
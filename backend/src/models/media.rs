@@ -16,6 +16,8 @@ pub struct Media {
     pub label: Option<String>,
     pub transcription_status: String,
     pub transcription_text: Option<String>,
+    /// `TranscriptSegment`s, when the transcription has timings.
+    pub transcription_segments: Option<serde_json::Value>,
     pub sort_order: i32,
     pub created_at: DateTime<Utc>,
 }
@@ -53,5 +55,16 @@ pub struct PresignResponse {
 #[derive(Debug, Deserialize)]
 pub struct UpdateTranscription {
     pub status: String,
+    /// Defaults to the segments' text when only `segments` is given.
     pub text: Option<String>,
+    pub segments: Option<Vec<TranscriptSegment>>,
+}
+
+/// A stretch of a transcription, with offsets in seconds from the start of
+/// the recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
 }
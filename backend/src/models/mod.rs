@@ -1,14 +1,24 @@
 pub mod ai;
 pub mod budget;
+pub mod comment;
 pub mod concept;
+pub mod custom_field;
 pub mod entity;
+pub mod export;
 pub mod field_trip;
 pub mod graph;
+pub mod import;
 pub mod inventory;
 pub mod map;
 pub mod media;
 pub mod note;
+pub mod note_revision;
+pub mod note_template;
+pub mod note_type;
 pub mod plan;
 pub mod routine;
+pub mod saved_search;
+pub mod share;
 pub mod tag;
+pub mod trash;
 pub mod user;
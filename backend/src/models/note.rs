@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -19,9 +19,15 @@ pub struct Note {
     pub temperature_c: Option<f32>,
     pub time_start: Option<DateTime<Utc>>,
     pub time_end: Option<DateTime<Utc>>,
+    /// Workspace-defined field values, keyed by custom field key.
+    pub custom_fields: serde_json::Value,
+    /// Incremented on every edit; exposed as the `ETag` for optimistic concurrency.
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
+    /// The note this one was merged into, if it was trashed by a merge.
+    pub merged_into_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -31,13 +37,19 @@ pub struct NoteSummary {
     pub title: String,
     pub body_text: String,
     pub note_type: String,
+    pub note_type_name: String,
+    pub note_type_icon: Option<String>,
     pub is_starred: bool,
     pub location_name: Option<String>,
     pub gps_coords: Option<String>,
     pub weather: Option<String>,
+    pub custom_fields: serde_json::Value,
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Kilometres from the `near` point of the listing, if any.
+    #[sqlx(default)]
+    pub distance_km: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,8 +59,8 @@ pub struct CreateNote {
     pub body: serde_json::Value,
     #[serde(default)]
     pub body_text: String,
-    #[serde(default = "default_note_type")]
-    pub note_type: String,
+    /// `field_note` when omitted, or the template's type when instantiating one.
+    pub note_type: Option<String>,
     pub location_name: Option<String>,
     pub location_lat: Option<f64>,
     pub location_lng: Option<f64>,
@@ -57,6 +69,8 @@ pub struct CreateNote {
     pub temperature_c: Option<f32>,
     pub time_start: Option<DateTime<Utc>>,
     pub time_end: Option<DateTime<Utc>>,
+    /// Values for the workspace's custom fields, validated against their definitions.
+    pub custom_fields: Option<serde_json::Map<String, serde_json::Value>>,
     pub field_trip_ids: Option<Vec<Uuid>>,
     pub tag_names: Option<Vec<String>>,
     pub concept_ids: Option<Vec<Uuid>>,
@@ -67,10 +81,6 @@ fn default_body() -> serde_json::Value {
     serde_json::json!({})
 }
 
-fn default_note_type() -> String {
-    "field_note".to_string()
-}
-
 #[derive(Debug, Deserialize)]
 pub struct UpdateNote {
     pub title: Option<String>,
@@ -85,13 +95,15 @@ pub struct UpdateNote {
     pub temperature_c: Option<f32>,
     pub time_start: Option<DateTime<Utc>>,
     pub time_end: Option<DateTime<Utc>>,
+    /// Merged into the stored values; `null` clears a field.
+    pub custom_fields: Option<serde_json::Map<String, serde_json::Value>>,
     pub field_trip_ids: Option<Vec<Uuid>>,
     pub tag_names: Option<Vec<String>>,
     pub concept_ids: Option<Vec<Uuid>>,
     pub entity_ids: Option<Vec<Uuid>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NoteFilters {
     pub note_type: Option<String>,
     pub field_trip_id: Option<Uuid>,
@@ -101,10 +113,20 @@ pub struct NoteFilters {
     pub entity_type: Option<String>,
     pub starred: Option<bool>,
     pub deleted: Option<bool>,
+    /// Custom field conditions, e.g. `language:Quechua,recorded_on>=2024-05-01`
+    pub fields: Option<String>,
+    /// Place and time filters; see `crate::search::geo`.
+    pub near: Option<String>,
+    pub km: Option<f64>,
+    pub bbox: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
     pub sort: Option<String>,
     pub order: Option<String>,
     pub page: Option<i64>,
     pub per_page: Option<i64>,
+    /// `next_cursor` from a previous page; takes precedence over `page`.
+    pub cursor: Option<String>,
     pub count_only: Option<bool>,
 }
 
@@ -117,9 +139,110 @@ pub struct ConnectedNote {
     pub label: Option<String>,
 }
 
+/// A note that links to another via a `noteLink` node in its body.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Backlink {
+    pub id: Uuid,
+    pub title: String,
+    pub note_type: String,
+    pub link_count: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// `md` (default) or `html`
+    pub format: Option<String>,
+}
+
+/// Body of `POST /api/v1/notes/{id}/duplicate`.
+#[derive(Debug, Default, Deserialize)]
+pub struct DuplicateNote {
+    /// Defaults to the original title with " (copy)" appended.
+    pub title: Option<String>,
+    /// Also copy the note's media files.
+    #[serde(default)]
+    pub include_media: bool,
+}
+
+/// Body of `POST /api/v1/notes/merge`. The first note is kept and receives
+/// the others, which are moved to the trash.
+#[derive(Debug, Deserialize)]
+pub struct MergeNotes {
+    pub note_ids: Vec<Uuid>,
+    /// Defaults to the first note's title.
+    pub title: Option<String>,
+}
+
+/// Body of `POST /api/v1/notes/bulk`: an action applied to either an
+/// explicit list of note ids or every note matching `filters`.
+#[derive(Debug, Deserialize)]
+pub struct BulkNoteRequest {
+    pub ids: Option<Vec<Uuid>>,
+    /// Pagination fields are ignored; `deleted` defaults to `true` for
+    /// `restore` and `permanent_delete`.
+    pub filters: Option<NoteFilters>,
+    #[serde(flatten)]
+    pub action: BulkNoteAction,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkNoteAction {
+    /// Replace the notes' field trips with this one.
+    MoveToFieldTrip { field_trip_id: Uuid },
+    AddTags { tag_names: Vec<String> },
+    RemoveTags { tag_names: Vec<String> },
+    AddConcepts { concept_ids: Vec<Uuid> },
+    RemoveConcepts { concept_ids: Vec<Uuid> },
+    Star,
+    Unstar,
+    Delete,
+    Restore,
+    PermanentDelete,
+}
+
+impl BulkNoteAction {
+    /// Whether the action applies to notes in the trash rather than live ones.
+    pub fn targets_trash(&self) -> bool {
+        matches!(self, Self::Restore | Self::PermanentDelete)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkNoteStatus {
+    Ok,
+    NotFound,
+    Skipped,
+}
+
+/// Outcome for a single note of a bulk request.
+#[derive(Debug, Serialize)]
+pub struct BulkNoteResult {
+    pub id: Uuid,
+    pub status: BulkNoteStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkNoteReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkNoteResult>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct NoteCount {
     pub total: i64,
     pub starred: i64,
     pub deleted: i64,
 }
+
+impl Note {
+    /// Strong entity tag for this note's current version, e.g. `"7"`.
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.version)
+    }
+}
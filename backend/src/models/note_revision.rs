@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A snapshot of a note's content taken just before an update overwrote it.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct NoteRevision {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub revision_number: i32,
+    pub title: String,
+    pub body: serde_json::Value,
+    pub body_text: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Listing row — omits the (potentially large) Tiptap body.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct NoteRevisionSummary {
+    pub id: Uuid,
+    pub revision_number: i32,
+    pub title: String,
+    pub body_text_length: i32,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    /// Revision to compare against; omitted means the note's current content.
+    pub to: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionDiff {
+    pub from: i32,
+    pub to: Option<i32>,
+    pub title_changed: bool,
+    pub from_title: String,
+    pub to_title: String,
+    pub changes: Vec<crate::tiptap::diff::BlockChange>,
+}
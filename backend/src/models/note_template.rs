@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A reusable starting point for new notes. `title` and text inside `body`
+/// may contain `{{variables}}` that are expanded when the template is
+/// instantiated (see `tiptap::template`).
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct NoteTemplate {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub title: String,
+    pub body: serde_json::Value,
+    pub note_type: String,
+    pub default_tags: Vec<String>,
+    pub default_concept_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNoteTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default = "default_body")]
+    pub body: serde_json::Value,
+    pub note_type: Option<String>,
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    #[serde(default)]
+    pub default_concept_ids: Vec<Uuid>,
+}
+
+fn default_body() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNoteTemplate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub title: Option<String>,
+    pub body: Option<serde_json::Value>,
+    pub note_type: Option<String>,
+    pub default_tags: Option<Vec<String>>,
+    pub default_concept_ids: Option<Vec<Uuid>>,
+}
+
+/// Query string of `POST /api/v1/notes`.
+#[derive(Debug, Deserialize)]
+pub struct CreateNoteParams {
+    pub template_id: Option<Uuid>,
+}
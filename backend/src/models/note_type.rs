@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+/// A workspace-defined kind of note. Notes refer to it by `key` through
+/// their `note_type` column.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct NoteType {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub key: String,
+    pub name: String,
+    pub icon: Option<String>,
+    /// Template applied to new notes of this type created without one.
+    pub default_template_id: Option<Uuid>,
+    /// Custom field keys a note of this type must have a value for.
+    pub required_fields: Vec<String>,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNoteType {
+    pub key: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub default_template_id: Option<Uuid>,
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// `key` is fixed once created, since notes refer to the type by it.
+#[derive(Debug, Deserialize)]
+pub struct UpdateNoteType {
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    /// `null` removes the default template; omitted leaves it unchanged.
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub default_template_id: Option<Option<Uuid>>,
+    pub required_fields: Option<Vec<String>>,
+    pub sort_order: Option<i32>,
+}
+
+/// Distinguish an explicit `null` (`Some(None)`) from an absent field (`None`).
+fn explicit_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
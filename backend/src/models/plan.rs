@@ -101,6 +101,7 @@ impl PlanLimits {
             "entities" => self.entities as i64,
             "media_uploads" => self.media_uploads as i64,
             "ai_requests" => self.ai_requests as i64,
+            "workspaces" => self.workspaces as i64,
             _ => 0,
         }
     }
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::note::NoteFilters;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub icon: String,
+    pub query: String,
+    /// The filtering fields of `NoteFilters`.
+    pub filters: serde_json::Value,
+    pub pinned: bool,
+    pub last_viewed_at: DateTime<Utc>,
+    /// Notes that matched when the search was last viewed.
+    #[serde(skip)]
+    pub seen_note_ids: Option<Vec<Uuid>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A saved search with its current note matches.
+#[derive(Debug, Serialize)]
+pub struct SavedSearchWithCounts {
+    #[serde(flatten)]
+    pub search: SavedSearch,
+    pub match_count: i64,
+    /// Matching notes that were not among the matches when the search was
+    /// last viewed.
+    pub new_match_count: i64,
+    /// Why the search could not be run, when its query or filters are no
+    /// longer valid; the counts are then zero.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSavedSearch {
+    pub name: String,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub query: String,
+    /// Paging, sorting and trash fields are ignored.
+    #[serde(default)]
+    pub filters: NoteFilters,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSavedSearch {
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    pub query: Option<String>,
+    /// Replaces the saved filters.
+    pub filters: Option<NoteFilters>,
+    pub pinned: Option<bool>,
+}
+
+/// Query string of `GET /api/v1/saved-searches`.
+#[derive(Debug, Deserialize)]
+pub struct SavedSearchFilters {
+    pub pinned: Option<bool>,
+}
+
+/// Query string of `GET /api/v1/saved-searches/:id/results`.
+#[derive(Debug, Deserialize)]
+pub struct SavedSearchPage {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub cursor: Option<String>,
+    pub fuzzy: Option<bool>,
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A public read-only link to one note or a whole field trip.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ShareLink {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub note_id: Option<Uuid>,
+    pub field_trip_id: Option<Uuid>,
+    pub has_password: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub view_count: i64,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Returned once, on creation; only a hash of `token` is stored.
+#[derive(Debug, Serialize)]
+pub struct CreatedShareLink {
+    #[serde(flatten)]
+    pub link: ShareLink,
+    pub token: String,
+}
+
+/// Exactly one of `note_id` and `field_trip_id` must be given.
+#[derive(Debug, Deserialize)]
+pub struct CreateShareLink {
+    pub note_id: Option<Uuid>,
+    pub field_trip_id: Option<Uuid>,
+    /// Never expires when omitted.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Viewers must send it in the `X-Share-Password` header.
+    pub password: Option<String>,
+}
+
+/// Query string of `GET /api/v1/shares`.
+#[derive(Debug, Deserialize)]
+pub struct ShareLinkFilters {
+    pub note_id: Option<Uuid>,
+    pub field_trip_id: Option<Uuid>,
+}
+
+/// What an unauthenticated viewer of a share link sees.
+#[derive(Debug, Serialize)]
+pub struct PublicShare {
+    /// `note` or `field_trip`
+    pub kind: &'static str,
+    pub title: String,
+    pub icon: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub notes: Vec<PublicNote>,
+}
+
+/// A shared note with its body rendered to HTML. Media URLs in `html` are
+/// scoped to the share token.
+#[derive(Debug, Serialize)]
+pub struct PublicNote {
+    pub id: Uuid,
+    pub title: String,
+    pub note_type_name: String,
+    pub note_type_icon: Option<String>,
+    pub location_name: Option<String>,
+    pub time_start: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub html: String,
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A soft-deleted note and when the background purge will remove it.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TrashedNote {
+    pub id: Uuid,
+    pub title: String,
+    pub note_type: String,
+    pub deleted_at: DateTime<Utc>,
+    /// `None` when the workspace keeps trashed notes indefinitely.
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TrashSettings {
+    /// Days a note stays in the trash; `null` disables automatic purging.
+    pub trash_retention_days: Option<i32>,
+}
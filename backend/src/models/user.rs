@@ -33,6 +33,22 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// A workspace the user owns; sessions are scoped to one at a time.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct WorkspaceSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Whether the requesting session is scoped to it.
+    #[sqlx(default)]
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SwitchWorkspaceRequest {
+    pub workspace_id: Uuid,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub token: String,
@@ -1,5 +1,11 @@
 use axum::Json;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AppError;
 
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T: Serialize> {
@@ -15,6 +21,9 @@ pub struct Meta {
     pub page: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub per_page: Option<i64>,
+    /// Pass back as `cursor` to fetch the next page; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl<T: Serialize> ApiResponse<T> {
@@ -29,6 +38,27 @@ impl<T: Serialize> ApiResponse<T> {
                 total,
                 page: Some(page),
                 per_page: Some(per_page),
+                next_cursor: None,
+            }),
+        })
+    }
+
+    /// A page of a keyset-paginated list. `page` is `None` when the request
+    /// was made with a cursor rather than a page number.
+    pub fn cursor_list(
+        data: T,
+        total: i64,
+        page: Option<i64>,
+        per_page: i64,
+        next_cursor: Option<String>,
+    ) -> Json<ApiResponse<T>> {
+        Json(ApiResponse {
+            data,
+            meta: Some(Meta {
+                total,
+                page,
+                per_page: Some(per_page),
+                next_cursor,
             }),
         })
     }
@@ -39,6 +69,7 @@ impl<T: Serialize> ApiResponse<T> {
 pub struct PaginationParams {
     pub page: Option<i64>,
     pub per_page: Option<i64>,
+    pub cursor: Option<String>,
 }
 
 impl PaginationParams {
@@ -53,4 +84,54 @@ impl PaginationParams {
     pub fn offset(&self) -> i64 {
         (self.page() - 1) * self.per_page()
     }
+
+    /// Whether the client asked for a page at all. Endpoints that predate
+    /// pagination return every row when it did not.
+    pub fn requested(&self) -> bool {
+        self.page.is_some() || self.per_page.is_some() || self.cursor.is_some()
+    }
+}
+
+/// Opaque keyset-pagination cursor: the sort key and id of the last row on a
+/// page, as base64url-encoded JSON. The sort name is kept so a cursor cannot
+/// be replayed against a different ordering.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cursor {
+    #[serde(rename = "s")]
+    sort: String,
+    #[serde(rename = "k")]
+    key: serde_json::Value,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(sort: &str, key: impl Serialize, id: Uuid) -> Self {
+        Self {
+            sort: sort.to_string(),
+            key: serde_json::to_value(key).unwrap_or_default(),
+            id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Decode a cursor issued for the given sort order.
+    pub fn decode(s: &str, sort: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+        let bytes = URL_SAFE_NO_PAD.decode(s).map_err(|_| invalid())?;
+        let cursor: Cursor = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+        if cursor.sort != sort {
+            return Err(AppError::BadRequest(
+                "Cursor was issued for a different sort order".to_string(),
+            ));
+        }
+        Ok(cursor)
+    }
+
+    pub fn key<K: DeserializeOwned>(&self) -> Result<K, AppError> {
+        serde_json::from_value(self.key.clone())
+            .map_err(|_| AppError::BadRequest("Invalid cursor".to_string()))
+    }
 }
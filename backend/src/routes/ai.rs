@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::ai::{AiProvider, ChatMessage, Provider, ProviderInfo, PROVIDERS};
 use crate::auth::middleware::AuthUser;
 use crate::config::Config;
 use crate::error::AppError;
@@ -72,9 +73,21 @@ pub struct ConversationWithMessages {
 pub struct AiStatusResponse {
     pub enabled: bool,
     pub provider: String,
+    pub label: Option<String>,
+    pub model: Option<String>,
     pub message: String,
 }
 
+#[derive(Serialize)]
+pub struct AiSettingsResponse {
+    #[serde(flatten)]
+    pub settings: ai::AiSettings,
+    /// Providers configured on this server.
+    pub available_providers: Vec<&'static str>,
+    /// What the settings resolve to; `None` when AI features are off.
+    pub active: Option<ProviderInfo>,
+}
+
 #[derive(Serialize)]
 pub struct SuggestedTag {
     pub id: Uuid,
@@ -89,6 +102,8 @@ pub struct RelatedNote {
     pub id: Uuid,
     pub title: String,
     pub note_type: String,
+    pub note_type_name: String,
+    pub note_type_icon: Option<String>,
     pub body_text: String,
     pub shared_entities: i64,
     pub shared_concepts: i64,
@@ -101,6 +116,8 @@ pub struct TimelineEntry {
     pub id: Uuid,
     pub title: String,
     pub note_type: String,
+    pub note_type_name: String,
+    pub note_type_icon: Option<String>,
     pub location_name: Option<String>,
     pub location_lat: Option<f64>,
     pub location_lng: Option<f64>,
@@ -136,6 +153,7 @@ pub fn routes() -> Router<PgPool> {
         .route("/api/v1/ai/conversations", get(list_conversations))
         .route("/api/v1/ai/conversations/{id}", get(get_conversation))
         .route("/api/v1/ai/conversations/{id}", delete(delete_conversation))
+        .route("/api/v1/settings/ai", get(get_ai_settings).put(update_ai_settings))
 }
 
 // ---------------------------------------------------------------------------
@@ -148,36 +166,33 @@ struct NoteContent {
     body_text: String,
 }
 
-/// Determine which AI provider to use. Returns ("claude", key) or ("perplexity", key) or ("none", "").
-fn resolve_provider(config: &Config) -> (&'static str, &str) {
-    if !config.anthropic_api_key.is_empty() {
-        ("claude", &config.anthropic_api_key)
-    } else if !config.perplexity_api_key.is_empty() {
-        ("perplexity", &config.perplexity_api_key)
-    } else {
-        ("none", "")
-    }
-}
-
 // ---------------------------------------------------------------------------
 // Handlers
 // ---------------------------------------------------------------------------
 
 async fn ai_status(
-    _auth: AuthUser,
+    auth: AuthUser,
+    State(pool): State<PgPool>,
     axum::Extension(config): axum::Extension<Config>,
-) -> Json<AiStatusResponse> {
-    let (provider, _) = resolve_provider(&config);
-    let enabled = provider != "none";
-    Json(AiStatusResponse {
-        enabled,
-        provider: provider.to_string(),
-        message: match provider {
-            "claude" => "AI features powered by Claude are available.".to_string(),
-            "perplexity" => "AI features powered by Perplexity are available.".to_string(),
-            _ => "AI features are coming soon. Stay tuned!".to_string(),
+    axum::Extension(http_client): axum::Extension<reqwest::Client>,
+) -> Result<Json<AiStatusResponse>, AppError> {
+    let provider = Provider::for_workspace(&pool, &config, &http_client, auth.workspace_id).await?;
+    Ok(Json(match provider.map(|p| p.metadata()) {
+        Some(info) => AiStatusResponse {
+            enabled: true,
+            provider: info.provider.to_string(),
+            label: Some(info.label.to_string()),
+            model: Some(info.model),
+            message: format!("AI features powered by {} are available.", info.label),
+        },
+        None => AiStatusResponse {
+            enabled: false,
+            provider: "none".to_string(),
+            label: None,
+            model: None,
+            message: "AI features are coming soon. Stay tuned!".to_string(),
         },
-    })
+    }))
 }
 
 async fn summarize(
@@ -189,15 +204,13 @@ async fn summarize(
 ) -> Result<Json<ApiResponse<SummarizeResponse>>, AppError> {
     plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "ai_requests").await?;
 
-    let (provider, api_key) = resolve_provider(&config);
-
-    if provider == "none" {
+    let Some(provider) = Provider::for_workspace(&pool, &config, &http_client, auth.workspace_id).await? else {
         return Ok(ApiResponse::ok(SummarizeResponse {
             summary: "AI-powered summarization is coming soon! This feature will use AI to generate concise summaries of your field notes, highlighting key entities, locations, and concepts.".to_string(),
             coming_soon: true,
             model: "none".to_string(),
         }));
-    }
+    };
 
     let note = sqlx::query_as::<_, NoteContent>(
         "SELECT title, body_text FROM notes WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL",
@@ -212,23 +225,14 @@ async fn summarize(
     let note_content = format!("Title: {}\n\n{}", note.title, note.body_text);
     let truncated = truncate_text(&note_content, 12000);
 
-    let (summary, model) = match provider {
-        "claude" => {
-            let text = call_claude(&http_client, api_key, system_prompt, &truncated, 1024).await?;
-            (text, "claude-sonnet-4-6".to_string())
-        }
-        _ => {
-            let text = call_perplexity(&http_client, api_key, "sonar", system_prompt, &truncated).await?;
-            (text, "sonar".to_string())
-        }
-    };
+    let summary = provider.complete(system_prompt, &truncated, 1024).await?;
 
     plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "ai_requests", 1).await?;
 
     Ok(ApiResponse::ok(SummarizeResponse {
         summary,
         coming_soon: false,
-        model,
+        model: provider.metadata().model,
     }))
 }
 
@@ -254,9 +258,7 @@ async fn chat(
 
     ai::insert_message(&pool, conversation.id, "user", &body.message, None, None).await?;
 
-    let (provider, api_key) = resolve_provider(&config);
-
-    if provider == "none" {
+    let Some(provider) = Provider::for_workspace(&pool, &config, &http_client, auth.workspace_id).await? else {
         let assistant_msg = ai::insert_message(
             &pool,
             conversation.id,
@@ -272,7 +274,7 @@ async fn chat(
             message: assistant_msg,
             coming_soon: true,
         }));
-    }
+    };
 
     let history = ai::get_messages(&pool, conversation.id).await?;
 
@@ -289,17 +291,22 @@ async fn chat(
     };
 
     let system_prompt = "You are a field research assistant for ArchiveMind. Help the researcher analyze their notes, discover connections, and find related academic sources. When citing web sources, include URLs.";
-
-    let (response_text, model_name) = match provider {
-        "claude" => {
-            let text = call_claude_chat(&http_client, api_key, system_prompt, &history, note_context.as_ref()).await?;
-            (text, "claude-sonnet-4-6")
-        }
-        _ => {
-            let text = call_perplexity_chat(&http_client, api_key, system_prompt, &history, note_context.as_ref()).await?;
-            (text, "sonar-pro")
-        }
+    let system = match note_context {
+        Some(note) => format!(
+            "{}\n\nThe researcher is working on a note titled \"{}\". Here is the content:\n\n{}",
+            system_prompt,
+            note.title,
+            truncate_text(&note.body_text, 8000)
+        ),
+        None => system_prompt.to_string(),
     };
+    let messages: Vec<ChatMessage> = history
+        .into_iter()
+        .filter(|msg| msg.role != "system")
+        .map(|msg| ChatMessage { role: msg.role, content: msg.content })
+        .collect();
+
+    let response_text = provider.chat(&system, &messages, 2048).await?;
 
     let assistant_msg = ai::insert_message(
         &pool,
@@ -307,7 +314,7 @@ async fn chat(
         "assistant",
         &response_text,
         None,
-        Some(model_name),
+        Some(&provider.metadata().model),
     )
     .await?;
 
@@ -329,14 +336,12 @@ async fn complete(
 ) -> Result<Json<ApiResponse<CompleteResponse>>, AppError> {
     plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "ai_requests").await?;
 
-    let (provider, api_key) = resolve_provider(&config);
-
-    if provider == "none" {
+    let Some(provider) = Provider::for_workspace(&pool, &config, &http_client, auth.workspace_id).await? else {
         return Ok(ApiResponse::ok(CompleteResponse {
             completion: String::new(),
             coming_soon: true,
         }));
-    }
+    };
 
     // Optionally fetch note context for better completions
     let note_context = if let Some(note_id) = body.note_id {
@@ -368,10 +373,7 @@ async fn complete(
 
     let text_to_complete = truncate_text(&body.text, 4000);
 
-    let completion = match provider {
-        "claude" => call_claude(&http_client, api_key, &system_prompt, &text_to_complete, 200).await?,
-        _ => call_perplexity(&http_client, api_key, "sonar", &system_prompt, &text_to_complete).await?,
-    };
+    let completion = provider.complete(&system_prompt, &text_to_complete, 200).await?;
 
     plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "ai_requests", 1).await?;
 
@@ -381,6 +383,81 @@ async fn complete(
     }))
 }
 
+// ---------------------------------------------------------------------------
+// GET/PUT /api/v1/settings/ai — workspace AI provider and model
+// ---------------------------------------------------------------------------
+
+/// Longest accepted model name, matching the column's CHECK.
+const MAX_MODEL_LEN: usize = 200;
+
+fn settings_response(settings: ai::AiSettings, config: &Config, http_client: &reqwest::Client) -> AiSettingsResponse {
+    let active = Provider::resolve(&settings, config, http_client).map(|p| p.metadata());
+    AiSettingsResponse {
+        settings,
+        available_providers: Provider::available(config),
+        active,
+    }
+}
+
+async fn get_ai_settings(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    axum::Extension(config): axum::Extension<Config>,
+    axum::Extension(http_client): axum::Extension<reqwest::Client>,
+) -> Result<Json<ApiResponse<AiSettingsResponse>>, AppError> {
+    let settings = sqlx::query_as::<_, ai::AiSettings>(
+        "SELECT ai_provider, ai_model FROM workspaces WHERE id = $1",
+    )
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Workspace not found".to_string()))?;
+
+    Ok(ApiResponse::ok(settings_response(settings, &config, &http_client)))
+}
+
+/// Replace both settings; `null` restores the server default.
+async fn update_ai_settings(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    axum::Extension(config): axum::Extension<Config>,
+    axum::Extension(http_client): axum::Extension<reqwest::Client>,
+    Json(body): Json<ai::AiSettings>,
+) -> Result<Json<ApiResponse<AiSettingsResponse>>, AppError> {
+    if let Some(name) = body.ai_provider.as_deref() {
+        if name != "none" && !PROVIDERS.contains(&name) {
+            return Err(AppError::BadRequest(format!(
+                "ai_provider must be one of {}, none, or null for the server default",
+                PROVIDERS.join(", ")
+            )));
+        }
+        if name != "none" && !Provider::is_available(name, &config) {
+            return Err(AppError::BadRequest(format!(
+                "AI provider '{name}' is not configured on this server"
+            )));
+        }
+    }
+    let model = body.ai_model.as_deref().map(str::trim);
+    if model.is_some_and(|m| m.is_empty() || m.len() > MAX_MODEL_LEN) {
+        return Err(AppError::BadRequest(format!(
+            "ai_model must be 1 to {MAX_MODEL_LEN} characters, or null for the provider's default"
+        )));
+    }
+
+    let settings = sqlx::query_as::<_, ai::AiSettings>(
+        "UPDATE workspaces SET ai_provider = $2, ai_model = $3, updated_at = now() \
+         WHERE id = $1 RETURNING ai_provider, ai_model",
+    )
+    .bind(auth.workspace_id)
+    .bind(&body.ai_provider)
+    .bind(model)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Workspace not found".to_string()))?;
+
+    Ok(ApiResponse::ok(settings_response(settings, &config, &http_client)))
+}
+
 // ---------------------------------------------------------------------------
 // Local intelligence (no AI, pure DB)
 // ---------------------------------------------------------------------------
@@ -484,20 +561,23 @@ async fn related_notes(
          shared AS (
             SELECT n.id,
                    n.title,
-                   n.note_type::text as note_type,
+                   n.note_type,
+                   ty.name AS note_type_name,
+                   ty.icon AS note_type_icon,
                    SUBSTRING(n.body_text FROM 1 FOR 200) as body_text,
                    n.created_at,
                    COUNT(DISTINCT ne.entity_id) FILTER (WHERE ne.entity_id IN (SELECT entity_id FROM note_ents)) as shared_entities,
                    COUNT(DISTINCT nc.concept_id) FILTER (WHERE nc.concept_id IN (SELECT concept_id FROM note_cons)) as shared_concepts
             FROM notes n
+            JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type
             LEFT JOIN note_entities ne ON ne.note_id = n.id
             LEFT JOIN note_concepts nc ON nc.note_id = n.id
             WHERE n.workspace_id = $2
               AND n.id != $1
               AND n.deleted_at IS NULL
-            GROUP BY n.id, n.title, n.note_type, n.body_text, n.created_at
+            GROUP BY n.id, ty.id
          )
-         SELECT id, title, note_type, body_text, created_at, shared_entities, shared_concepts,
+         SELECT id, title, note_type, note_type_name, note_type_icon, body_text, created_at, shared_entities, shared_concepts,
                 (shared_entities * 3 + shared_concepts * 2)::FLOAT8 as relevance_score
          FROM shared
          WHERE shared_entities > 0 OR shared_concepts > 0
@@ -515,6 +595,8 @@ async fn related_notes(
             id: r.id,
             title: r.title,
             note_type: r.note_type,
+            note_type_name: r.note_type_name,
+            note_type_icon: r.note_type_icon,
             body_text: r.body_text,
             shared_entities: r.shared_entities,
             shared_concepts: r.shared_concepts,
@@ -535,11 +617,12 @@ async fn timeline(
 
     let entries = if let Some(ft_id) = params.field_trip_id {
         sqlx::query_as::<_, TimelineRow>(
-            "SELECT n.id, n.title, n.note_type::text as note_type, \
+            "SELECT n.id, n.title, n.note_type, ty.name AS note_type_name, ty.icon AS note_type_icon, \
                     n.location_name, n.location_lat, n.location_lng, n.created_at, \
                     (SELECT COUNT(*) FROM note_entities ne WHERE ne.note_id = n.id) as entity_count, \
                     (SELECT COUNT(*) FROM note_concepts nc WHERE nc.note_id = n.id) as concept_count \
              FROM notes n \
+             JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type \
              JOIN note_field_trips nft ON nft.note_id = n.id AND nft.field_trip_id = $3 \
              WHERE n.workspace_id = $1 AND n.deleted_at IS NULL \
              ORDER BY n.created_at ASC \
@@ -552,11 +635,12 @@ async fn timeline(
         .await?
     } else {
         sqlx::query_as::<_, TimelineRow>(
-            "SELECT n.id, n.title, n.note_type::text as note_type, \
+            "SELECT n.id, n.title, n.note_type, ty.name AS note_type_name, ty.icon AS note_type_icon, \
                     n.location_name, n.location_lat, n.location_lng, n.created_at, \
                     (SELECT COUNT(*) FROM note_entities ne WHERE ne.note_id = n.id) as entity_count, \
                     (SELECT COUNT(*) FROM note_concepts nc WHERE nc.note_id = n.id) as concept_count \
              FROM notes n \
+             JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type \
              WHERE n.workspace_id = $1 AND n.deleted_at IS NULL \
              ORDER BY n.created_at ASC \
              LIMIT $2",
@@ -573,6 +657,8 @@ async fn timeline(
             id: e.id,
             title: e.title,
             note_type: e.note_type,
+            note_type_name: e.note_type_name,
+            note_type_icon: e.note_type_icon,
             location_name: e.location_name,
             location_lat: e.location_lat,
             location_lng: e.location_lng,
@@ -638,6 +724,8 @@ struct RelatedNoteRow {
     id: Uuid,
     title: String,
     note_type: String,
+    note_type_name: String,
+    note_type_icon: Option<String>,
     body_text: String,
     shared_entities: i64,
     shared_concepts: i64,
@@ -650,6 +738,8 @@ struct TimelineRow {
     id: Uuid,
     title: String,
     note_type: String,
+    note_type_name: String,
+    note_type_icon: Option<String>,
     location_name: Option<String>,
     location_lat: Option<f64>,
     location_lng: Option<f64>,
@@ -658,305 +748,6 @@ struct TimelineRow {
     created_at: chrono::DateTime<chrono::Utc>,
 }
 
-// ---------------------------------------------------------------------------
-// Claude (Anthropic Messages API)
-// ---------------------------------------------------------------------------
-
-#[derive(Serialize)]
-struct ClaudeRequest {
-    model: String,
-    max_tokens: u32,
-    system: String,
-    messages: Vec<ClaudeMessage>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct ClaudeMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct ClaudeResponse {
-    content: Vec<ClaudeContent>,
-}
-
-#[derive(Deserialize)]
-struct ClaudeContent {
-    text: String,
-}
-
-async fn call_claude(
-    client: &reqwest::Client,
-    api_key: &str,
-    system: &str,
-    user_message: &str,
-    max_tokens: u32,
-) -> Result<String, AppError> {
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-6".to_string(),
-        max_tokens,
-        system: system.to_string(),
-        messages: vec![ClaudeMessage {
-            role: "user".to_string(),
-            content: user_message.to_string(),
-        }],
-    };
-
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| AppError::Internal(format!("Claude API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(AppError::Internal(format!(
-            "Claude API returned {}: {}",
-            status, text
-        )));
-    }
-
-    let parsed: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to parse Claude response: {}", e)))?;
-
-    parsed
-        .content
-        .first()
-        .map(|c| c.text.clone())
-        .ok_or_else(|| AppError::Internal("Empty response from Claude".to_string()))
-}
-
-async fn call_claude_chat(
-    client: &reqwest::Client,
-    api_key: &str,
-    system_prompt: &str,
-    history: &[ai::AiMessage],
-    note_context: Option<&NoteContent>,
-) -> Result<String, AppError> {
-
-    let system = if let Some(note) = note_context {
-        format!(
-            "{}\n\nThe researcher is working on a note titled \"{}\". Here is the content:\n\n{}",
-            system_prompt,
-            note.title,
-            truncate_text(&note.body_text, 8000)
-        )
-    } else {
-        system_prompt.to_string()
-    };
-
-    let mut messages: Vec<ClaudeMessage> = Vec::new();
-    for msg in history {
-        if msg.role == "system" {
-            continue;
-        }
-        messages.push(ClaudeMessage {
-            role: msg.role.clone(),
-            content: msg.content.clone(),
-        });
-    }
-
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-6".to_string(),
-        max_tokens: 2048,
-        system,
-        messages,
-    };
-
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| AppError::Internal(format!("Claude API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(AppError::Internal(format!(
-            "Claude API returned {}: {}",
-            status, text
-        )));
-    }
-
-    let parsed: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to parse Claude response: {}", e)))?;
-
-    parsed
-        .content
-        .first()
-        .map(|c| c.text.clone())
-        .ok_or_else(|| AppError::Internal("Empty response from Claude".to_string()))
-}
-
-// ---------------------------------------------------------------------------
-// Perplexity API (fallback / web search)
-// ---------------------------------------------------------------------------
-
-#[derive(Serialize)]
-struct PerplexityRequest {
-    model: String,
-    messages: Vec<PerplexityMessage>,
-    stream: bool,
-}
-
-#[derive(Serialize, Deserialize)]
-struct PerplexityMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct PerplexityResponse {
-    choices: Vec<PerplexityChoice>,
-}
-
-#[derive(Deserialize)]
-struct PerplexityChoice {
-    message: PerplexityChoiceMessage,
-}
-
-#[derive(Deserialize)]
-struct PerplexityChoiceMessage {
-    content: String,
-}
-
-async fn call_perplexity(
-    client: &reqwest::Client,
-    api_key: &str,
-    model: &str,
-    system: &str,
-    user_message: &str,
-) -> Result<String, AppError> {
-
-    let request = PerplexityRequest {
-        model: model.to_string(),
-        messages: vec![
-            PerplexityMessage {
-                role: "system".to_string(),
-                content: system.to_string(),
-            },
-            PerplexityMessage {
-                role: "user".to_string(),
-                content: user_message.to_string(),
-            },
-        ],
-        stream: false,
-    };
-
-    let response = client
-        .post("https://api.perplexity.ai/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| AppError::Internal(format!("Perplexity API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(AppError::Internal(format!(
-            "Perplexity API returned {}: {}",
-            status, text
-        )));
-    }
-
-    let parsed: PerplexityResponse = response
-        .json()
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to parse Perplexity response: {}", e)))?;
-
-    parsed
-        .choices
-        .first()
-        .map(|c| c.message.content.clone())
-        .ok_or_else(|| AppError::Internal("Empty response from Perplexity".to_string()))
-}
-
-async fn call_perplexity_chat(
-    client: &reqwest::Client,
-    api_key: &str,
-    system_prompt: &str,
-    history: &[ai::AiMessage],
-    note_context: Option<&NoteContent>,
-) -> Result<String, AppError> {
-
-    let mut messages = vec![PerplexityMessage {
-        role: "system".to_string(),
-        content: system_prompt.to_string(),
-    }];
-
-    if let Some(note) = note_context {
-        messages.push(PerplexityMessage {
-            role: "system".to_string(),
-            content: format!(
-                "The researcher is working on a note titled \"{}\". Here is the content:\n\n{}",
-                note.title,
-                truncate_text(&note.body_text, 8000)
-            ),
-        });
-    }
-
-    for msg in history {
-        if msg.role == "system" {
-            continue;
-        }
-        messages.push(PerplexityMessage {
-            role: msg.role.clone(),
-            content: msg.content.clone(),
-        });
-    }
-
-    let request = PerplexityRequest {
-        model: "sonar-pro".to_string(),
-        messages,
-        stream: false,
-    };
-
-    let response = client
-        .post("https://api.perplexity.ai/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| AppError::Internal(format!("Perplexity API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(AppError::Internal(format!(
-            "Perplexity API returned {}: {}",
-            status, text
-        )));
-    }
-
-    let parsed: PerplexityResponse = response
-        .json()
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to parse Perplexity response: {}", e)))?;
-
-    parsed
-        .choices
-        .first()
-        .map(|c| c.message.content.clone())
-        .ok_or_else(|| AppError::Internal("Empty response from Perplexity".to_string()))
-}
-
 // ---------------------------------------------------------------------------
 // Utility
 // ---------------------------------------------------------------------------
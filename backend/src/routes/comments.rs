@@ -0,0 +1,255 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::comment::*;
+use crate::response::ApiResponse;
+use crate::routes::notes::ensure_note_exists;
+
+/// Comments joined with their author, scoped to the workspace through the
+/// note they belong to.
+const COMMENT_SELECT: &str = "SELECT c.id, c.note_id, c.parent_id, c.author_id, \
+     u.display_name AS author_name, u.avatar_initials AS author_initials, c.body, \
+     c.anchor_from, c.anchor_to, c.anchor_text, c.resolved_at, c.resolved_by, \
+     c.created_at, c.updated_at \
+     FROM note_comments c \
+     JOIN notes n ON n.id = c.note_id \
+     LEFT JOIN users u ON u.id = c.author_id";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route(
+            "/api/v1/notes/{id}/comments",
+            get(list_comments).post(create_comment),
+        )
+        .route(
+            "/api/v1/comments/{id}",
+            put(update_comment).delete(delete_comment),
+        )
+        .route("/api/v1/comments/{id}/resolve", post(resolve_comment))
+        .route("/api/v1/comments/{id}/unresolve", post(unresolve_comment))
+}
+
+async fn fetch_comment(pool: &PgPool, workspace_id: Uuid, id: Uuid) -> Result<Comment, AppError> {
+    sqlx::query_as::<_, Comment>(&format!(
+        "{COMMENT_SELECT} WHERE c.id = $1 AND n.workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))
+}
+
+/// Only a comment's author may edit or delete it.
+fn ensure_author(auth: &AuthUser, comment: &Comment) -> Result<(), AppError> {
+    if comment.author_id != Some(auth.user_id) {
+        return Err(AppError::Forbidden("Only the author can change this comment".to_string()));
+    }
+    Ok(())
+}
+
+fn comment_body(body: &str) -> Result<&str, AppError> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(AppError::BadRequest("Comment body is required".to_string()));
+    }
+    Ok(body)
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/v1/notes/:id/comments — threads with their replies, oldest first
+// ---------------------------------------------------------------------------
+async fn list_comments(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(note_id): Path<Uuid>,
+    Query(filters): Query<CommentFilters>,
+) -> Result<Json<ApiResponse<Vec<CommentThread>>>, AppError> {
+    ensure_note_exists(&pool, auth.workspace_id, note_id).await?;
+
+    let comments = sqlx::query_as::<_, Comment>(&format!(
+        "{COMMENT_SELECT} WHERE c.note_id = $1 AND n.workspace_id = $2 \
+         ORDER BY c.created_at, c.id"
+    ))
+    .bind(note_id)
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let (roots, replies): (Vec<Comment>, Vec<Comment>) =
+        comments.into_iter().partition(|c| c.parent_id.is_none());
+    let mut threads: Vec<CommentThread> = roots
+        .into_iter()
+        .filter(|c| filters.resolved.is_none_or(|r| r == c.resolved_at.is_some()))
+        .map(|comment| CommentThread { comment, replies: Vec::new() })
+        .collect();
+    for reply in replies {
+        if let Some(thread) = threads.iter_mut().find(|t| Some(t.comment.id) == reply.parent_id) {
+            thread.replies.push(reply);
+        }
+    }
+
+    let total = threads.len() as i64;
+    Ok(ApiResponse::list(threads, total, 1, total.max(1)))
+}
+
+// ---------------------------------------------------------------------------
+// POST /api/v1/notes/:id/comments — start a thread or reply to one
+// ---------------------------------------------------------------------------
+async fn create_comment(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(note_id): Path<Uuid>,
+    Json(body): Json<CreateComment>,
+) -> Result<Json<ApiResponse<Comment>>, AppError> {
+    let text = comment_body(&body.body)?;
+
+    let live: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM notes WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)",
+    )
+    .bind(note_id)
+    .bind(auth.workspace_id)
+    .fetch_one(&pool)
+    .await?;
+    if !live {
+        return Err(AppError::NotFound("Note not found".to_string()));
+    }
+
+    let anchored = body.anchor_from.is_some() || body.anchor_to.is_some();
+    match (body.anchor_from, body.anchor_to) {
+        (None, None) => {}
+        (Some(from), Some(to)) if from >= 0 && from <= to => {}
+        _ => {
+            return Err(AppError::BadRequest(
+                "anchor_from and anchor_to must be given together, with 0 <= anchor_from <= anchor_to"
+                    .to_string(),
+            ))
+        }
+    }
+
+    // Replying to a reply joins the same thread.
+    let parent_id = match body.parent_id {
+        Some(parent_id) => {
+            if anchored {
+                return Err(AppError::BadRequest("Replies cannot be anchored".to_string()));
+            }
+            let parent = fetch_comment(&pool, auth.workspace_id, parent_id).await?;
+            if parent.note_id != note_id {
+                return Err(AppError::BadRequest(
+                    "parent_id belongs to a different note".to_string(),
+                ));
+            }
+            Some(parent.parent_id.unwrap_or(parent.id))
+        }
+        None => None,
+    };
+
+    let id: Uuid = sqlx::query_scalar(
+        "INSERT INTO note_comments (note_id, parent_id, author_id, body, anchor_from, anchor_to, anchor_text) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
+    )
+    .bind(note_id)
+    .bind(parent_id)
+    .bind(auth.user_id)
+    .bind(text)
+    .bind(body.anchor_from)
+    .bind(body.anchor_to)
+    .bind(body.anchor_text.as_deref().filter(|_| anchored))
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(ApiResponse::ok(fetch_comment(&pool, auth.workspace_id, id).await?))
+}
+
+// ---------------------------------------------------------------------------
+// PUT/DELETE /api/v1/comments/:id — author only
+// ---------------------------------------------------------------------------
+async fn update_comment(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateComment>,
+) -> Result<Json<ApiResponse<Comment>>, AppError> {
+    let text = comment_body(&body.body)?;
+    let comment = fetch_comment(&pool, auth.workspace_id, id).await?;
+    ensure_author(&auth, &comment)?;
+
+    sqlx::query("UPDATE note_comments SET body = $2, updated_at = now() WHERE id = $1")
+        .bind(id)
+        .bind(text)
+        .execute(&pool)
+        .await?;
+
+    Ok(ApiResponse::ok(fetch_comment(&pool, auth.workspace_id, id).await?))
+}
+
+/// Deleting a thread root deletes its replies.
+async fn delete_comment(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let comment = fetch_comment(&pool, auth.workspace_id, id).await?;
+    ensure_author(&auth, &comment)?;
+
+    sqlx::query("DELETE FROM note_comments WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({ "deleted": true })))
+}
+
+// ---------------------------------------------------------------------------
+// POST /api/v1/comments/:id/resolve, /unresolve — whole threads
+// ---------------------------------------------------------------------------
+async fn set_resolved(
+    auth: &AuthUser,
+    pool: &PgPool,
+    id: Uuid,
+    resolved: bool,
+) -> Result<Comment, AppError> {
+    let comment = fetch_comment(pool, auth.workspace_id, id).await?;
+    if comment.parent_id.is_some() {
+        return Err(AppError::BadRequest(
+            "Only a thread's first comment can be resolved".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE note_comments SET \
+         resolved_at = CASE WHEN $2 THEN COALESCE(resolved_at, now()) END, \
+         resolved_by = CASE WHEN $2 THEN COALESCE(resolved_by, $3) END \
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(resolved)
+    .bind(auth.user_id)
+    .execute(pool)
+    .await?;
+
+    fetch_comment(pool, auth.workspace_id, id).await
+}
+
+async fn resolve_comment(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Comment>>, AppError> {
+    Ok(ApiResponse::ok(set_resolved(&auth, &pool, id, true).await?))
+}
+
+async fn unresolve_comment(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Comment>>, AppError> {
+    Ok(ApiResponse::ok(set_resolved(&auth, &pool, id, false).await?))
+}
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::custom_field::*;
+use crate::response::ApiResponse;
+
+const FIELD_COLS: &str =
+    "id, workspace_id, key, label, field_type, options, sort_order, created_at, updated_at";
+
+const FIELD_TYPES: &[&str] = &["text", "number", "date", "enum", "boolean"];
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route(
+            "/api/v1/custom-fields",
+            get(list_fields).post(create_field),
+        )
+        .route(
+            "/api/v1/custom-fields/{id}",
+            get(get_field).put(update_field).delete(delete_field),
+        )
+}
+
+async fn definitions<'e, E: PgExecutor<'e>>(
+    executor: E,
+    workspace_id: Uuid,
+) -> Result<HashMap<String, CustomFieldDefinition>, AppError> {
+    let defs = sqlx::query_as::<_, CustomFieldDefinition>(&format!(
+        "SELECT {FIELD_COLS} FROM custom_field_definitions WHERE workspace_id = $1"
+    ))
+    .bind(workspace_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(defs.into_iter().map(|d| (d.key.clone(), d)).collect())
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+// ---------------------------------------------------------------------------
+// Note values
+// ---------------------------------------------------------------------------
+
+/// Check a value against its definition and return it in stored form
+/// (dates as `YYYY-MM-DD` strings).
+fn coerce_value(def: &CustomFieldDefinition, value: &Value) -> Result<Value, AppError> {
+    let invalid = || {
+        AppError::BadRequest(format!(
+            "Custom field '{}' expects a {} value",
+            def.key, def.field_type
+        ))
+    };
+    match def.field_type.as_str() {
+        "text" => value.as_str().map(|s| Value::String(s.to_string())).ok_or_else(invalid),
+        "number" => value.is_number().then(|| value.clone()).ok_or_else(invalid),
+        "boolean" => value.is_boolean().then(|| value.clone()).ok_or_else(invalid),
+        "date" => value
+            .as_str()
+            .and_then(parse_date)
+            .map(|d| Value::String(d.format("%Y-%m-%d").to_string()))
+            .ok_or_else(invalid),
+        "enum" => match value.as_str() {
+            Some(s) if def.options.iter().any(|o| o == s) => Ok(Value::String(s.to_string())),
+            _ => Err(AppError::BadRequest(format!(
+                "Custom field '{}' must be one of: {}",
+                def.key,
+                def.options.join(", ")
+            ))),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+/// Merge `changes` into a note's current `custom_fields`, validating each
+/// value against the workspace's definitions. A `null` value clears the
+/// field.
+pub(crate) async fn apply_custom_fields<'e, E: PgExecutor<'e>>(
+    executor: E,
+    workspace_id: Uuid,
+    current: &Value,
+    changes: &Map<String, Value>,
+) -> Result<Value, AppError> {
+    let mut merged = current.as_object().cloned().unwrap_or_default();
+    if changes.is_empty() {
+        return Ok(Value::Object(merged));
+    }
+
+    let defs = definitions(executor, workspace_id).await?;
+    for (key, value) in changes {
+        let def = defs
+            .get(key)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown custom field '{key}'")))?;
+        if value.is_null() {
+            merged.remove(key);
+        } else {
+            merged.insert(key.clone(), coerce_value(def, value)?);
+        }
+    }
+    Ok(Value::Object(merged))
+}
+
+// ---------------------------------------------------------------------------
+// Filtering
+// ---------------------------------------------------------------------------
+
+/// Parse `NoteFilters::fields`: comma-separated `key:value` terms, plus
+/// `key>value`, `key>=value`, `key<value` and `key<=value` for number and
+/// date fields, e.g. `language:Quechua,recorded_on>=2024-05-01`.
+pub(crate) async fn field_filters<'e, E: PgExecutor<'e>>(
+    executor: E,
+    workspace_id: Uuid,
+    spec: Option<&str>,
+) -> Result<Vec<FieldFilter>, AppError> {
+    let Some(spec) = spec.filter(|s| !s.trim().is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let defs = definitions(executor, workspace_id).await?;
+    filter_terms(spec).map(|term| field_filter(term, &defs)).collect()
+}
+
+/// Field filters stored with a saved search. Terms whose field has since
+/// been deleted, or no longer accepts the value, are skipped.
+pub(crate) async fn stored_field_filters<'e, E: PgExecutor<'e>>(
+    executor: E,
+    workspace_id: Uuid,
+    spec: Option<&str>,
+) -> Result<Vec<FieldFilter>, AppError> {
+    let Some(spec) = spec.filter(|s| !s.trim().is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let defs = definitions(executor, workspace_id).await?;
+    Ok(filter_terms(spec)
+        .filter_map(|term| field_filter(term, &defs).ok())
+        .collect())
+}
+
+fn filter_terms(spec: &str) -> impl Iterator<Item = &str> {
+    spec.split(',').map(str::trim).filter(|t| !t.is_empty())
+}
+
+fn field_filter(term: &str, defs: &HashMap<String, CustomFieldDefinition>) -> Result<FieldFilter, AppError> {
+    let malformed = || AppError::BadRequest(format!("Malformed field filter '{term}'"));
+    let pos = term.find([':', '<', '>']).ok_or_else(malformed)?;
+    let (key, rest) = (term[..pos].trim(), &term[pos..]);
+    let (op, raw) = match rest {
+        r if r.starts_with(">=") => (">=", &r[2..]),
+        r if r.starts_with("<=") => ("<=", &r[2..]),
+        r if r.starts_with('>') => (">", &r[1..]),
+        r if r.starts_with('<') => ("<", &r[1..]),
+        r => ("=", &r[1..]),
+    };
+    let raw = raw.trim();
+
+    let def = defs
+        .get(key)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown custom field '{key}'")))?;
+    if op != "=" && !matches!(def.field_type.as_str(), "number" | "date") {
+        return Err(AppError::BadRequest(format!(
+            "Custom field '{key}' only supports equality filters"
+        )));
+    }
+
+    let invalid = || {
+        AppError::BadRequest(format!(
+            "Custom field '{key}' expects a {} value",
+            def.field_type
+        ))
+    };
+    let value = match def.field_type.as_str() {
+        "number" => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(invalid)?,
+        "boolean" => Value::Bool(raw.parse::<bool>().map_err(|_| invalid())?),
+        _ => coerce_value(def, &Value::String(raw.to_string()))?,
+    };
+
+    Ok(FieldFilter {
+        key: key.to_string(),
+        op,
+        value,
+    })
+}
+
+/// SQL condition on `notes n` for one filter. Binds the key at `$idx` and
+/// the JSON value at `$idx + 1`.
+pub(crate) fn field_filter_sql(filter: &FieldFilter, idx: u32) -> String {
+    if filter.op == "=" {
+        // Containment, so the GIN index on custom_fields applies.
+        format!(
+            "n.custom_fields @> jsonb_build_object(${}::text, ${}::jsonb)",
+            idx,
+            idx + 1
+        )
+    } else {
+        // jsonb orders numbers numerically and ISO dates as strings.
+        format!(
+            "n.custom_fields -> ${}::text {} ${}::jsonb",
+            idx,
+            filter.op,
+            idx + 1
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Definitions CRUD
+// ---------------------------------------------------------------------------
+
+/// Enum fields need a non-empty list of distinct options; other types none.
+fn check_options(field_type: &str, options: &[String]) -> Result<(), AppError> {
+    if field_type != "enum" {
+        if !options.is_empty() {
+            return Err(AppError::BadRequest(
+                "Only enum fields take options".to_string(),
+            ));
+        }
+        return Ok(());
+    }
+    if options.is_empty() || options.iter().any(|o| o.trim().is_empty()) {
+        return Err(AppError::BadRequest(
+            "Enum fields need at least one non-empty option".to_string(),
+        ));
+    }
+    let mut unique = options.to_vec();
+    unique.sort();
+    unique.dedup();
+    if unique.len() != options.len() {
+        return Err(AppError::BadRequest("Enum options must be distinct".to_string()));
+    }
+    Ok(())
+}
+
+async fn list_fields(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<CustomFieldDefinition>>>, AppError> {
+    let fields = sqlx::query_as::<_, CustomFieldDefinition>(&format!(
+        "SELECT {FIELD_COLS} FROM custom_field_definitions WHERE workspace_id = $1 \
+         ORDER BY sort_order, label"
+    ))
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let total = fields.len() as i64;
+    Ok(ApiResponse::list(fields, total, 1, total.max(1)))
+}
+
+async fn get_field(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<CustomFieldDefinition>>, AppError> {
+    let field = sqlx::query_as::<_, CustomFieldDefinition>(&format!(
+        "SELECT {FIELD_COLS} FROM custom_field_definitions WHERE id = $1 AND workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Custom field not found".to_string()))?;
+
+    Ok(ApiResponse::ok(field))
+}
+
+async fn create_field(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(body): Json<CreateCustomField>,
+) -> Result<Json<ApiResponse<CustomFieldDefinition>>, AppError> {
+    let key = body.key.trim();
+    let valid_key = key.len() <= 63
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid_key {
+        return Err(AppError::BadRequest(
+            "Field key must be lowercase letters, digits and underscores, starting with a letter"
+                .to_string(),
+        ));
+    }
+    if body.label.trim().is_empty() {
+        return Err(AppError::BadRequest("Field label is required".to_string()));
+    }
+    if !FIELD_TYPES.contains(&body.field_type.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "Unknown field type: {}",
+            body.field_type
+        )));
+    }
+    check_options(&body.field_type, &body.options)?;
+
+    let field = sqlx::query_as::<_, CustomFieldDefinition>(&format!(
+        "INSERT INTO custom_field_definitions \
+         (workspace_id, key, label, field_type, options, sort_order) \
+         VALUES ($1, $2, $3, $4, $5, $6) \
+         ON CONFLICT (workspace_id, key) DO NOTHING \
+         RETURNING {FIELD_COLS}"
+    ))
+    .bind(auth.workspace_id)
+    .bind(key)
+    .bind(body.label.trim())
+    .bind(&body.field_type)
+    .bind(&body.options)
+    .bind(body.sort_order)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict(format!("A custom field with key '{key}' already exists")))?;
+
+    Ok(ApiResponse::ok(field))
+}
+
+async fn update_field(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateCustomField>,
+) -> Result<Json<ApiResponse<CustomFieldDefinition>>, AppError> {
+    if body.label.as_deref().is_some_and(|l| l.trim().is_empty()) {
+        return Err(AppError::BadRequest("Field label is required".to_string()));
+    }
+    if let Some(ref options) = body.options {
+        let field_type: String = sqlx::query_scalar(
+            "SELECT field_type FROM custom_field_definitions WHERE id = $1 AND workspace_id = $2",
+        )
+        .bind(id)
+        .bind(auth.workspace_id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Custom field not found".to_string()))?;
+        check_options(&field_type, options)?;
+    }
+
+    // Notes keep values of removed enum options until they are next edited.
+    let field = sqlx::query_as::<_, CustomFieldDefinition>(&format!(
+        "UPDATE custom_field_definitions SET \
+         label = COALESCE($3, label), \
+         options = COALESCE($4, options), \
+         sort_order = COALESCE($5, sort_order), \
+         updated_at = now() \
+         WHERE id = $1 AND workspace_id = $2 \
+         RETURNING {FIELD_COLS}"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .bind(body.label.as_deref().map(str::trim))
+    .bind(&body.options)
+    .bind(body.sort_order)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Custom field not found".to_string()))?;
+
+    Ok(ApiResponse::ok(field))
+}
+
+/// Deleting a definition also removes its values from every note and drops
+/// it from note types that require it.
+async fn delete_field(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let key: String = sqlx::query_scalar(
+        "DELETE FROM custom_field_definitions WHERE id = $1 AND workspace_id = $2 RETURNING key",
+    )
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Custom field not found".to_string()))?;
+
+    sqlx::query(
+        "UPDATE notes SET custom_fields = custom_fields - $2 \
+         WHERE workspace_id = $1 AND custom_fields ? $2",
+    )
+    .bind(auth.workspace_id)
+    .bind(&key)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE note_types SET required_fields = array_remove(required_fields, $2), updated_at = now() \
+         WHERE workspace_id = $1 AND $2 = ANY(required_fields)",
+    )
+    .bind(auth.workspace_id)
+    .bind(&key)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(ApiResponse::ok(serde_json::json!({ "deleted": true })))
+}
@@ -3,6 +3,7 @@ use axum::{
     routing::get,
     Json, Router,
 };
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -11,7 +12,7 @@ use crate::error::AppError;
 use crate::middleware::plan_guard;
 use crate::models::entity::*;
 use crate::models::note::NoteSummary;
-use crate::response::ApiResponse;
+use crate::response::{ApiResponse, Cursor, PaginationParams};
 
 async fn list_entities(
     auth: AuthUser,
@@ -164,27 +165,78 @@ async fn entity_notes(
     auth: AuthUser,
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
+    Query(pagination): Query<PaginationParams>,
 ) -> Result<Json<ApiResponse<Vec<NoteSummary>>>, AppError> {
-    let notes = sqlx::query_as::<_, NoteSummary>(
-        "SELECT n.id, n.workspace_id, n.title, n.body_text, n.note_type::text AS note_type, n.is_starred, \
-         n.location_name, n.gps_coords, n.weather, \
+    const SORT: &str = "created_at_desc";
+    let cursor = pagination
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c, SORT))
+        .transpose()?;
+    let (after_created, after_id) = match cursor {
+        Some(ref c) => (Some(c.key::<DateTime<Utc>>()?), Some(c.id)),
+        None => (None, None),
+    };
+    let paged = pagination.requested();
+    let per_page = pagination.per_page();
+    // LIMIT NULL means no limit; one extra row tells whether there is a next page.
+    let limit = paged.then_some(per_page + 1);
+    let offset = if paged && cursor.is_none() { pagination.offset() } else { 0 };
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notes n JOIN note_entities ne ON ne.note_id = n.id \
+         WHERE ne.entity_id = $1 AND n.workspace_id = $2 AND n.deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_one(&pool)
+    .await?;
+
+    let mut notes = sqlx::query_as::<_, NoteSummary>(
+        "SELECT n.id, n.workspace_id, n.title, n.body_text, n.note_type, ty.name AS note_type_name, \
+         ty.icon AS note_type_icon, n.is_starred, n.location_name, n.gps_coords, n.weather, n.custom_fields, \
          COALESCE(ARRAY_AGG(t.name ORDER BY t.name) FILTER (WHERE t.name IS NOT NULL), ARRAY[]::TEXT[]) AS tags, \
          n.created_at, n.updated_at \
          FROM notes n \
          JOIN note_entities ne ON ne.note_id = n.id \
+         JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type \
          LEFT JOIN note_tags nt ON nt.note_id = n.id \
          LEFT JOIN tags t ON t.id = nt.tag_id \
          WHERE ne.entity_id = $1 AND n.workspace_id = $2 AND n.deleted_at IS NULL \
-         GROUP BY n.id \
-         ORDER BY n.created_at DESC",
+           AND ($3::timestamptz IS NULL OR (n.created_at, n.id) < ($3, $4)) \
+         GROUP BY n.id, ty.id \
+         ORDER BY n.created_at DESC, n.id DESC \
+         LIMIT $5 OFFSET $6",
     )
     .bind(id)
     .bind(auth.workspace_id)
+    .bind(after_created)
+    .bind(after_id)
+    .bind(limit)
+    .bind(offset)
     .fetch_all(&pool)
     .await?;
 
-    let total = notes.len() as i64;
-    Ok(ApiResponse::list(notes, total, 1, total.max(1)))
+    if !paged {
+        return Ok(ApiResponse::list(notes, total, 1, total.max(1)));
+    }
+
+    let next_cursor = if notes.len() as i64 > per_page {
+        notes.truncate(per_page as usize);
+        notes
+            .last()
+            .map(|n| Cursor::new(SORT, n.created_at, n.id).encode())
+    } else {
+        None
+    };
+
+    Ok(ApiResponse::cursor_list(
+        notes,
+        total,
+        cursor.is_none().then(|| pagination.page()),
+        per_page,
+        next_cursor,
+    ))
 }
 
 async fn entity_topics(
@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::archive::{self, export};
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::export::WorkspaceExport;
+use crate::response::ApiResponse;
+
+const EXPORT_COLS: &str =
+    "id, workspace_id, requested_by, status, file_size_bytes, error, created_at, completed_at";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/api/v1/exports", get(list_exports).post(create_export))
+        .route("/api/v1/exports/{id}", get(get_export))
+}
+
+// ---------------------------------------------------------------------------
+// POST /api/v1/exports — start a full workspace export
+// ---------------------------------------------------------------------------
+async fn create_export(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<WorkspaceExport>>, AppError> {
+    // `idx_workspace_exports_active` allows one unfinished job per workspace.
+    let job = sqlx::query_as::<_, WorkspaceExport>(&format!(
+        "INSERT INTO workspace_exports (workspace_id, requested_by) VALUES ($1, $2) \
+         ON CONFLICT (workspace_id) WHERE status IN ('pending', 'running') DO NOTHING \
+         RETURNING {EXPORT_COLS}"
+    ))
+    .bind(auth.workspace_id)
+    .bind(auth.user_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| {
+        AppError::Conflict("An export is already in progress for this workspace".to_string())
+    })?;
+
+    tokio::spawn(export::run_export(pool.clone(), job.id, auth.workspace_id));
+
+    Ok(ApiResponse::ok(job))
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/v1/exports — list export jobs, newest first
+// ---------------------------------------------------------------------------
+async fn list_exports(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<WorkspaceExport>>>, AppError> {
+    let jobs = sqlx::query_as::<_, WorkspaceExport>(&format!(
+        "SELECT {EXPORT_COLS} FROM workspace_exports WHERE workspace_id = $1 \
+         ORDER BY created_at DESC"
+    ))
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(ApiResponse::ok(jobs))
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/v1/exports/{id} — download the archive once completed; until then
+// returns the job status as JSON so clients can poll the same URL.
+// ---------------------------------------------------------------------------
+async fn get_export(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let job = sqlx::query_as::<_, WorkspaceExport>(&format!(
+        "SELECT {EXPORT_COLS} FROM workspace_exports WHERE id = $1 AND workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Export not found".to_string()))?;
+
+    if job.status != "completed" {
+        return Ok(ApiResponse::ok(job).into_response());
+    }
+
+    let bytes = tokio::fs::read(archive::export_path(job.workspace_id, job.id))
+        .await
+        .map_err(|_| AppError::NotFound("Export archive is no longer available".to_string()))?;
+
+    let filename = format!(
+        "archivemind-export-{}.zip",
+        job.completed_at.unwrap_or(job.created_at).format("%Y-%m-%d")
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/zip"));
+    if let Ok(v) = HeaderValue::from_str(&format!("attachment; filename=\"{filename}\"")) {
+        headers.insert(header::CONTENT_DISPOSITION, v);
+    }
+
+    Ok((headers, bytes).into_response())
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use axum::{
+    extract::{Multipart, State},
+    routing::post,
+    Json, Router,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::archive::restore;
+use crate::auth::jwt::create_token;
+use crate::auth::middleware::AuthUser;
+use crate::config::Config;
+use crate::embeddings::{self, EmbeddingProvider};
+use crate::error::AppError;
+use crate::middleware::plan_guard;
+use crate::models::import::*;
+use crate::models::note::{CreateNote, Note};
+use crate::response::ApiResponse;
+use crate::routes::note_types::seed_builtin_types;
+use crate::routes::notes::insert_note;
+use crate::tiptap::{self, markdown};
+
+/// Largest single Markdown file accepted from an archive (uncompressed).
+const MAX_MARKDOWN_BYTES: u64 = 5 * 1024 * 1024;
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/api/v1/import/markdown", post(import_markdown))
+        .route("/api/v1/imports", post(import_workspace))
+}
+
+// ---------------------------------------------------------------------------
+// Archive reading
+// ---------------------------------------------------------------------------
+
+/// A Markdown file from the uploaded archive; `contents` is an error message
+/// if the entry could not be read.
+struct MarkdownFile {
+    path: String,
+    contents: Result<String, String>,
+}
+
+/// Skip directories, non-Markdown files and hidden/OS metadata folders such
+/// as `.obsidian/` or `__MACOSX/`.
+fn is_markdown_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    (lower.ends_with(".md") || lower.ends_with(".markdown"))
+        && !path
+            .split('/')
+            .any(|part| part.starts_with('.') || part == "__MACOSX")
+}
+
+fn read_markdown_archive(bytes: Vec<u8>) -> Result<Vec<MarkdownFile>, AppError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| AppError::BadRequest(format!("Invalid zip archive: {e}")))?;
+
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| AppError::BadRequest(format!("Invalid zip archive: {e}")))?;
+        let path = entry.name().to_string();
+        if entry.is_dir() || !is_markdown_path(&path) {
+            continue;
+        }
+
+        let contents = if entry.size() > MAX_MARKDOWN_BYTES {
+            Err("File is too large".to_string())
+        } else {
+            let mut buf = Vec::new();
+            entry
+                .take(MAX_MARKDOWN_BYTES + 1)
+                .read_to_end(&mut buf)
+                .map_err(|e| format!("Failed to read file: {e}"))
+                .and_then(|_| {
+                    String::from_utf8(buf).map_err(|_| "File is not valid UTF-8".to_string())
+                })
+        };
+        files.push(MarkdownFile { path, contents });
+    }
+    Ok(files)
+}
+
+// ---------------------------------------------------------------------------
+// Front-matter
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct FrontMatter {
+    title: Option<String>,
+    note_type: Option<String>,
+    location_name: Option<String>,
+    location_lat: Option<f64>,
+    location_lng: Option<f64>,
+    weather: Option<String>,
+    time_start: Option<DateTime<Utc>>,
+    time_end: Option<DateTime<Utc>>,
+    tags: Vec<String>,
+}
+
+fn yaml_string(value: &serde_yaml::Value) -> Option<String> {
+    let s = match value {
+        serde_yaml::Value::String(s) => s.trim().to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    (!s.is_empty()).then_some(s)
+}
+
+/// Accept RFC 3339, `YYYY-MM-DD HH:MM[:SS]` (UTC) or a bare date (midnight UTC).
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Some(dt.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+fn parse_front_matter(src: &str) -> Result<FrontMatter, String> {
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_str(src).map_err(|e| format!("Invalid front-matter: {e}"))?;
+    let Some(map) = yaml.as_mapping() else {
+        return Ok(FrontMatter::default());
+    };
+    let get = |key: &str| map.get(key).and_then(yaml_string);
+    let time = |key: &str| -> Result<Option<DateTime<Utc>>, String> {
+        match get(key) {
+            Some(s) => parse_datetime(&s)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid {key} in front-matter: {s}")),
+            None => Ok(None),
+        }
+    };
+    let number = |key: &str| map.get(key).and_then(|v| v.as_f64());
+
+    let tags = match map.get("tags") {
+        Some(serde_yaml::Value::Sequence(items)) => items.iter().filter_map(yaml_string).collect(),
+        Some(value) => yaml_string(value)
+            .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let tags = tags
+        .into_iter()
+        .map(|t: String| t.trim_start_matches('#').to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    Ok(FrontMatter {
+        title: get("title"),
+        note_type: get("note_type").or_else(|| get("type")),
+        location_name: get("location").or_else(|| get("location_name")),
+        location_lat: number("lat").or_else(|| number("location_lat")),
+        location_lng: number("lng").or_else(|| number("location_lng")),
+        weather: get("weather"),
+        time_start: match time("time_start")? {
+            Some(t) => Some(t),
+            None => time("date")?,
+        },
+        time_end: time("time_end")?,
+        tags,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Wiki-link resolution
+// ---------------------------------------------------------------------------
+
+/// Lower-cased entity and concept names mapped to the Tiptap node type and id
+/// a `[[Name]]` link should become. Entities win over concepts on a clash.
+async fn load_link_targets(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> Result<HashMap<String, (&'static str, Uuid)>, AppError> {
+    let mut targets = HashMap::new();
+
+    let concepts: Vec<(Uuid, String)> =
+        sqlx::query_as("SELECT id, name FROM concepts WHERE workspace_id = $1")
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await?;
+    for (id, name) in concepts {
+        targets.insert(name.to_lowercase(), ("conceptTag", id));
+    }
+
+    let entities: Vec<(Uuid, String)> =
+        sqlx::query_as("SELECT id, name FROM entities WHERE workspace_id = $1")
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await?;
+    for (id, name) in entities {
+        targets.insert(name.to_lowercase(), ("entityMention", id));
+    }
+
+    Ok(targets)
+}
+
+/// Obsidian-style targets may carry a folder prefix or a `#heading` suffix;
+/// only the page name is matched.
+fn link_key(target: &str) -> String {
+    let name = target.split('#').next().unwrap_or(target);
+    let name = name.rsplit('/').next().unwrap_or(name);
+    name.trim().to_lowercase()
+}
+
+// ---------------------------------------------------------------------------
+// POST /api/v1/import/markdown — import a zip of Markdown files as notes
+// ---------------------------------------------------------------------------
+
+async fn import_markdown(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    axum::Extension(embedder): axum::Extension<EmbeddingProvider>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<ImportReport>>, AppError> {
+    let mut archive: Option<Vec<u8>> = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        AppError::BadRequest(format!("Multipart error: {e}"))
+    })? {
+        if field.name() == Some("file") {
+            archive = Some(field.bytes().await.map_err(|e| {
+                AppError::BadRequest(format!("Failed to read file bytes: {e}"))
+            })?.to_vec());
+        } else {
+            // Drain unknown fields
+            let _ = field.bytes().await;
+        }
+    }
+    let archive = archive.ok_or_else(|| AppError::BadRequest("No file provided".to_string()))?;
+
+    // Decompression is CPU-bound; keep it off the async runtime.
+    let files = tokio::task::spawn_blocking(move || read_markdown_archive(archive))
+        .await
+        .map_err(|e| AppError::Internal(format!("spawn_blocking error: {e}")))??;
+    if files.is_empty() {
+        return Err(AppError::BadRequest(
+            "Archive contains no Markdown files".to_string(),
+        ));
+    }
+
+    let targets = load_link_targets(&pool, auth.workspace_id).await?;
+
+    let mut report = ImportReport {
+        imported: 0,
+        failed: 0,
+        files: Vec::with_capacity(files.len()),
+    };
+
+    for file in files {
+        let outcome = match file.contents {
+            Ok(src) => import_markdown_file(&pool, &auth, &targets, &file.path, &src).await,
+            Err(msg) => Err(AppError::BadRequest(msg)),
+        };
+        match outcome {
+            Ok((note, unresolved_links)) => {
+                report.imported += 1;
+                report.files.push(ImportFileResult {
+                    path: file.path,
+                    status: ImportStatus::Imported,
+                    note_id: Some(note.id),
+                    title: Some(note.title),
+                    unresolved_links,
+                    error: None,
+                });
+            }
+            Err(err) => {
+                if let AppError::Internal(ref msg) = err {
+                    tracing::error!("Markdown import of {} failed: {}", file.path, msg);
+                }
+                report.failed += 1;
+                report.files.push(ImportFileResult {
+                    path: file.path,
+                    status: ImportStatus::Failed,
+                    note_id: None,
+                    title: None,
+                    unresolved_links: Vec::new(),
+                    error: Some(err.to_string()),
+                });
+            }
+        }
+    }
+
+    if report.imported > 0 {
+        tokio::spawn(embeddings::backfill(pool, embedder, Some(auth.workspace_id)));
+    }
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// Convert and insert one file in its own transaction so a bad file does not
+/// roll back the rest of the archive.
+async fn import_markdown_file(
+    pool: &PgPool,
+    auth: &AuthUser,
+    targets: &HashMap<String, (&'static str, Uuid)>,
+    path: &str,
+    src: &str,
+) -> Result<(Note, Vec<String>), AppError> {
+    plan_guard::check_limit(pool, auth.user_id, auth.workspace_id, "notes").await?;
+
+    let parsed = markdown::from_markdown(src, &mut |target, label| {
+        targets.get(&link_key(target)).map(|(node_type, id)| {
+            json!({ "type": node_type, "attrs": { "id": id, "label": label } })
+        })
+    });
+    let fm = match parsed.front_matter.as_deref() {
+        Some(yaml) => parse_front_matter(yaml).map_err(AppError::BadRequest)?,
+        None => FrontMatter::default(),
+    };
+
+    let file_stem = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.').map(|(stem, _)| stem))
+        .unwrap_or(path);
+
+    let body_text = tiptap::plain_text(&parsed.doc);
+    let create = CreateNote {
+        title: fm.title.unwrap_or_else(|| file_stem.to_string()),
+        body: parsed.doc,
+        body_text,
+        note_type: fm.note_type,
+        location_name: fm.location_name,
+        location_lat: fm.location_lat,
+        location_lng: fm.location_lng,
+        gps_coords: None,
+        weather: fm.weather,
+        temperature_c: None,
+        time_start: fm.time_start,
+        time_end: fm.time_end,
+        custom_fields: None,
+        field_trip_ids: None,
+        tag_names: (!fm.tags.is_empty()).then_some(fm.tags),
+        concept_ids: None,
+        entity_ids: None,
+    };
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let note = insert_note(&mut tx, auth.workspace_id, &create).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Increment usage counter (best-effort)
+    let _ = plan_guard::increment_usage(pool, auth.user_id, auth.workspace_id, "notes_count", 1).await;
+
+    Ok((note, parsed.unresolved_links))
+}
+
+// ---------------------------------------------------------------------------
+// POST /api/v1/imports — restore a workspace export archive
+//
+// Multipart fields: `file` (the export zip) and optional `workspace_name`.
+// With a name, the archive is restored into a new workspace owned by the
+// caller and a token for it is returned; otherwise it is merged into the
+// current workspace.
+// ---------------------------------------------------------------------------
+async fn import_workspace(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    axum::Extension(config): axum::Extension<Config>,
+    axum::Extension(embedder): axum::Extension<EmbeddingProvider>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<WorkspaceImportReport>>, AppError> {
+    let mut archive: Option<Vec<u8>> = None;
+    let mut workspace_name: Option<String> = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        AppError::BadRequest(format!("Multipart error: {e}"))
+    })? {
+        match field.name().unwrap_or("") {
+            "file" => {
+                archive = Some(field.bytes().await.map_err(|e| {
+                    AppError::BadRequest(format!("Failed to read file bytes: {e}"))
+                })?.to_vec());
+            }
+            "workspace_name" => {
+                let name = field.text().await.map_err(|e| {
+                    AppError::BadRequest(format!("Failed to read workspace_name: {e}"))
+                })?;
+                workspace_name = Some(name.trim().to_string()).filter(|n| !n.is_empty());
+            }
+            _ => {
+                // Drain unknown fields
+                let _ = field.bytes().await;
+            }
+        }
+    }
+    let archive = archive.ok_or_else(|| AppError::BadRequest("No file provided".to_string()))?;
+
+    let data = tokio::task::spawn_blocking(move || restore::read_archive(archive))
+        .await
+        .map_err(|e| AppError::Internal(format!("spawn_blocking error: {e}")))??;
+
+    // A new workspace gets its id up front; with no rows yet, the check
+    // counts the archive alone against the plan.
+    let workspace_id = match workspace_name {
+        Some(_) => {
+            plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "workspaces").await?;
+            Uuid::new_v4()
+        }
+        None => auth.workspace_id,
+    };
+    for (resource, amount) in data.usage() {
+        plan_guard::check_quota(&pool, auth.user_id, workspace_id, resource, amount).await?;
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if let Some(ref name) = workspace_name {
+        sqlx::query("INSERT INTO workspaces (id, user_id, name) VALUES ($1, $2, $3)")
+            .bind(workspace_id)
+            .bind(auth.user_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        seed_builtin_types(&mut tx, workspace_id).await?;
+    }
+
+    let outcome = restore::restore(&mut tx, workspace_id, data).await?;
+
+    if let Err(e) = tx.commit().await {
+        restore::discard_media(&outcome).await;
+        return Err(AppError::Internal(e.to_string()));
+    }
+
+    // Increment usage counters (best-effort)
+    let count = |table: &str| outcome.rows.get(table).copied().unwrap_or(0) as i64;
+    for (resource, amount) in [
+        ("notes_count", count("notes")),
+        ("entities_count", count("entities")),
+        ("media_uploads", count("media")),
+        ("storage_bytes", outcome.storage_bytes),
+    ] {
+        if amount > 0 {
+            let _ = plan_guard::increment_usage(&pool, auth.user_id, workspace_id, resource, amount).await;
+        }
+    }
+
+    tokio::spawn(embeddings::backfill(pool, embedder, Some(workspace_id)));
+
+    let token = match workspace_name {
+        Some(_) => Some(create_token(
+            auth.user_id,
+            workspace_id,
+            &config.jwt_secret,
+            config.jwt_expiry_hours,
+        )?),
+        None => None,
+    };
+
+    Ok(ApiResponse::ok(WorkspaceImportReport {
+        workspace_id,
+        token,
+        imported: outcome.rows,
+        media_files: outcome.media_files,
+        missing_media: outcome.missing_media,
+    }))
+}
@@ -127,7 +127,7 @@ async fn map_locations(
 
     // ── 1. Notes with GPS data ──────────────────────────────────────────────
     let note_rows = sqlx::query_as::<_, NoteRow>(
-        "SELECT id, title, note_type::text, location_name, location_lat, location_lng, gps_coords \
+        "SELECT id, title, note_type, location_name, location_lat, location_lng, gps_coords \
          FROM notes \
          WHERE workspace_id = $1 \
            AND deleted_at IS NULL \
@@ -5,6 +5,7 @@ use axum::{
     routing::{get, post, put},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use sqlx::PgPool;
 use std::path::PathBuf;
@@ -15,14 +16,14 @@ use crate::auth::middleware::AuthUser;
 use crate::error::AppError;
 use crate::middleware::plan_guard;
 use crate::models::media::*;
-use crate::response::ApiResponse;
+use crate::response::{ApiResponse, Cursor, PaginationParams};
 
 /// Base directory for stored files, relative to CWD (the binary is run from
 /// the `backend/` workspace root in development).
-const UPLOADS_DIR: &str = "uploads";
+pub(crate) const UPLOADS_DIR: &str = "uploads";
 
 /// Ensure `uploads/{workspace_id}/` directory exists and return its path.
-async fn ensure_upload_dir(workspace_id: &Uuid) -> Result<PathBuf, AppError> {
+pub(crate) async fn ensure_upload_dir(workspace_id: &Uuid) -> Result<PathBuf, AppError> {
     let dir = PathBuf::from(UPLOADS_DIR).join(workspace_id.to_string());
     fs::create_dir_all(&dir).await.map_err(|e| {
         AppError::Internal(format!("Failed to create upload directory: {e}"))
@@ -88,7 +89,7 @@ async fn upload_media(
     let ext = original_filename
         .as_deref()
         .and_then(|f| f.rsplit('.').next())
-        .unwrap_or_else(|| match media_type_str.as_str() {
+        .unwrap_or(match media_type_str.as_str() {
             "audio" => "webm",
             _ => "jpg",
         })
@@ -129,7 +130,7 @@ async fn upload_media(
          VALUES ($1, $2::media_type, $3, $4, $5, $6, 0) \
          RETURNING id, note_id, media_type::text, s3_key, original_filename, mime_type, \
          file_size_bytes, duration_seconds, thumbnail_s3_key, label, \
-         transcription_status::text, transcription_text, sort_order, created_at",
+         transcription_status::text, transcription_text, transcription_segments, sort_order, created_at",
     )
     .bind(note_id)
     .bind(&media_type_str)
@@ -159,7 +160,7 @@ async fn serve_file(
     let record = sqlx::query_as::<_, Media>(
         "SELECT m.id, m.note_id, m.media_type::text, m.s3_key, m.original_filename, m.mime_type, \
          m.file_size_bytes, m.duration_seconds, m.thumbnail_s3_key, m.label, \
-         m.transcription_status::text, m.transcription_text, m.sort_order, m.created_at \
+         m.transcription_status::text, m.transcription_text, m.transcription_segments, m.sort_order, m.created_at \
          FROM media m JOIN notes n ON n.id = m.note_id \
          WHERE m.id = $1 AND n.workspace_id = $2",
     )
@@ -169,6 +170,15 @@ async fn serve_file(
     .await?
     .ok_or_else(|| AppError::NotFound("Media not found".to_string()))?;
 
+    media_file_response(&record, &headers).await
+}
+
+/// Stream a media row's file, honouring `Range`. Access checks are the
+/// caller's; shared with the public share-link variant of `serve_file`.
+pub(crate) async fn media_file_response(
+    record: &Media,
+    headers: &HeaderMap,
+) -> Result<(StatusCode, HeaderMap, Body), AppError> {
     // If s3_key is an external URL (e.g. seed placeholder images), redirect to it
     if record.s3_key.starts_with("http://") || record.s3_key.starts_with("https://") {
         let mut redirect_headers = HeaderMap::new();
@@ -262,6 +272,9 @@ struct ListMediaQuery {
     note_id: Option<Uuid>,
     #[serde(rename = "type")]
     media_type: Option<String>,
+    page: Option<i64>,
+    per_page: Option<i64>,
+    cursor: Option<String>,
 }
 
 async fn list_media(
@@ -281,33 +294,94 @@ async fn list_media(
     }
     if params.media_type.is_some() {
         conditions.push(format!("m.media_type::text = ${bind_idx}"));
-        // bind_idx += 1; -- would be used if more conditions follow
-        let _ = bind_idx; // suppress unused warning
+        bind_idx += 1;
+    }
+
+    const SORT: &str = "sort_order_asc";
+    let pagination = PaginationParams {
+        page: params.page,
+        per_page: params.per_page,
+        cursor: params.cursor.clone(),
+    };
+    let cursor = pagination
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c, SORT))
+        .transpose()?;
+    let count_where = format!("WHERE {}", conditions.join(" AND "));
+    if cursor.is_some() {
+        conditions.push(format!(
+            "(m.sort_order, m.created_at, m.id) > (${}, ${}, ${})",
+            bind_idx,
+            bind_idx + 1,
+            bind_idx + 2
+        ));
+        bind_idx += 3;
     }
 
     let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
+    // LIMIT NULL returns everything, as this endpoint did before paging.
     let sql = format!(
         "SELECT m.id, m.note_id, m.media_type::text, m.s3_key, m.original_filename, m.mime_type, \
          m.file_size_bytes, m.duration_seconds, m.thumbnail_s3_key, m.label, \
-         m.transcription_status::text, m.transcription_text, m.sort_order, m.created_at \
+         m.transcription_status::text, m.transcription_text, m.transcription_segments, m.sort_order, m.created_at \
          FROM media m JOIN notes n ON n.id = m.note_id \
-         {where_clause} ORDER BY m.sort_order ASC, m.created_at ASC"
+         {where_clause} ORDER BY m.sort_order ASC, m.created_at ASC, m.id ASC \
+         LIMIT ${} OFFSET ${}",
+        bind_idx,
+        bind_idx + 1
+    );
+    let count_sql = format!(
+        "SELECT COUNT(*) FROM media m JOIN notes n ON n.id = m.note_id {count_where}"
     );
 
     let mut query = sqlx::query_as::<_, Media>(&sql);
+    let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
     query = query.bind(auth.workspace_id);
+    count_query = count_query.bind(auth.workspace_id);
     if let Some(nid) = params.note_id {
         query = query.bind(nid);
+        count_query = count_query.bind(nid);
     }
     if let Some(mt) = &params.media_type {
         query = query.bind(mt);
+        count_query = count_query.bind(mt);
     }
+    if let Some(ref c) = cursor {
+        let (sort_order, created_at) = c.key::<(i32, DateTime<Utc>)>()?;
+        query = query.bind(sort_order).bind(created_at).bind(c.id);
+    }
+
+    let paged = pagination.requested();
+    let per_page = pagination.per_page();
+    let offset = if paged && cursor.is_none() { pagination.offset() } else { 0 };
+    query = query.bind(paged.then_some(per_page + 1)).bind(offset);
 
-    let items = query.fetch_all(&pool).await?;
-    let total = items.len() as i64;
+    let mut items = query.fetch_all(&pool).await?;
 
-    Ok(ApiResponse::list(items, total, 1, 200))
+    if !paged {
+        let total = items.len() as i64;
+        return Ok(ApiResponse::list(items, total, 1, 200));
+    }
+
+    let total = count_query.fetch_one(&pool).await?;
+    let next_cursor = if items.len() as i64 > per_page {
+        items.truncate(per_page as usize);
+        items
+            .last()
+            .map(|m| Cursor::new(SORT, (m.sort_order, m.created_at), m.id).encode())
+    } else {
+        None
+    };
+
+    Ok(ApiResponse::cursor_list(
+        items,
+        total,
+        cursor.is_none().then(|| pagination.page()),
+        per_page,
+        next_cursor,
+    ))
 }
 
 // ---------------------------------------------------------------------------
@@ -321,7 +395,7 @@ async fn get_media(
     let media = sqlx::query_as::<_, Media>(
         "SELECT m.id, m.note_id, m.media_type::text, m.s3_key, m.original_filename, m.mime_type, \
          m.file_size_bytes, m.duration_seconds, m.thumbnail_s3_key, m.label, \
-         m.transcription_status::text, m.transcription_text, m.sort_order, m.created_at \
+         m.transcription_status::text, m.transcription_text, m.transcription_segments, m.sort_order, m.created_at \
          FROM media m JOIN notes n ON n.id = m.note_id \
          WHERE m.id = $1 AND n.workspace_id = $2",
     )
@@ -346,7 +420,7 @@ async fn delete_media(
     let record = sqlx::query_as::<_, Media>(
         "SELECT m.id, m.note_id, m.media_type::text, m.s3_key, m.original_filename, m.mime_type, \
          m.file_size_bytes, m.duration_seconds, m.thumbnail_s3_key, m.label, \
-         m.transcription_status::text, m.transcription_text, m.sort_order, m.created_at \
+         m.transcription_status::text, m.transcription_text, m.transcription_segments, m.sort_order, m.created_at \
          FROM media m JOIN notes n ON n.id = m.note_id \
          WHERE m.id = $1 AND n.workspace_id = $2",
     )
@@ -394,18 +468,38 @@ async fn update_transcription(
         return Err(AppError::NotFound("Media not found".to_string()));
     }
 
+    if let Some(ref segments) = body.segments {
+        if segments.iter().any(|s| !(s.start >= 0.0 && s.end >= s.start)) {
+            return Err(AppError::BadRequest(
+                "Transcript segments need 0 <= start <= end".to_string(),
+            ));
+        }
+    }
+    let text = body.text.clone().or_else(|| {
+        body.segments.as_ref().map(|segments| {
+            segments.iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" ")
+        })
+    });
+    let segments = body
+        .segments
+        .as_ref()
+        .map(|s| serde_json::to_value(s).map_err(|e| AppError::Internal(e.to_string())))
+        .transpose()?;
+
     let media = sqlx::query_as::<_, Media>(
         "UPDATE media SET \
          transcription_status = $2::transcription_status, \
-         transcription_text = COALESCE($3, transcription_text) \
+         transcription_text = COALESCE($3, transcription_text), \
+         transcription_segments = COALESCE($4, transcription_segments) \
          WHERE id = $1 \
          RETURNING id, note_id, media_type::text, s3_key, original_filename, mime_type, \
          file_size_bytes, duration_seconds, thumbnail_s3_key, label, \
-         transcription_status::text, transcription_text, sort_order, created_at",
+         transcription_status::text, transcription_text, transcription_segments, sort_order, created_at",
     )
     .bind(id)
     .bind(&body.status)
-    .bind(&body.text)
+    .bind(&text)
+    .bind(&segments)
     .fetch_optional(&pool)
     .await?
     .ok_or_else(|| AppError::NotFound("Media not found".to_string()))?;
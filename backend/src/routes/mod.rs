@@ -1,18 +1,27 @@
 pub mod ai;
 pub mod billing;
+pub mod comments;
 pub mod concepts;
+pub mod custom_fields;
 pub mod entities;
+pub mod exports;
 pub mod field_trips;
 pub mod graph;
 pub mod health;
+pub mod import;
 pub mod inventory;
 pub mod map;
 pub mod media;
+pub mod note_templates;
+pub mod note_types;
 pub mod notes;
 pub mod routines;
+pub mod saved_searches;
 pub mod search;
 pub mod settings;
+pub mod shares;
 pub mod tags;
+pub mod trash;
 pub mod usage;
 pub mod users;
 
@@ -20,17 +29,29 @@ use axum::Router;
 use sqlx::PgPool;
 
 use crate::config::Config;
+use crate::embeddings::EmbeddingProvider;
 
-pub fn build_router(pool: PgPool, config: Config, http_client: reqwest::Client) -> Router {
+pub fn build_router(
+    pool: PgPool,
+    config: Config,
+    http_client: reqwest::Client,
+    embedder: EmbeddingProvider,
+) -> Router {
     Router::new()
         .merge(health::routes())
         .merge(users::routes())
         .merge(notes::routes())
+        .merge(comments::routes())
+        .merge(note_templates::routes())
+        .merge(note_types::routes())
         .merge(entities::routes())
         .merge(concepts::routes())
+        .merge(custom_fields::routes())
         .merge(field_trips::routes())
         .merge(tags::routes())
+        .merge(trash::routes())
         .merge(search::routes())
+        .merge(saved_searches::routes())
         .merge(media::routes())
         .merge(inventory::routes())
         .merge(routines::routes())
@@ -40,7 +61,11 @@ pub fn build_router(pool: PgPool, config: Config, http_client: reqwest::Client)
         .merge(settings::routes())
         .merge(ai::routes())
         .merge(billing::routes())
+        .merge(import::routes())
+        .merge(exports::routes())
+        .merge(shares::routes())
         .with_state(pool)
         .layer(axum::Extension(config))
         .layer(axum::Extension(http_client))
+        .layer(axum::Extension(embedder))
 }
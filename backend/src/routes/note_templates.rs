@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::note::CreateNote;
+use crate::models::note_template::*;
+use crate::response::ApiResponse;
+use crate::routes::note_types::{resolve_type, DEFAULT_NOTE_TYPE};
+use crate::tiptap::{self, template};
+
+const TEMPLATE_COLS: &str = "id, workspace_id, name, description, title, body, \
+     note_type, default_tags, default_concept_ids, created_at, updated_at";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route(
+            "/api/v1/note-templates",
+            get(list_templates).post(create_template),
+        )
+        .route(
+            "/api/v1/note-templates/{id}",
+            get(get_template).put(update_template).delete(delete_template),
+        )
+}
+
+/// Reject concept ids that do not belong to the workspace.
+async fn check_concepts(pool: &PgPool, workspace_id: Uuid, ids: &[Uuid]) -> Result<(), AppError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let found: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT id) FROM concepts WHERE workspace_id = $1 AND id = ANY($2)",
+    )
+    .bind(workspace_id)
+    .bind(ids)
+    .fetch_one(pool)
+    .await?;
+
+    let mut unique = ids.to_vec();
+    unique.sort();
+    unique.dedup();
+    if found != unique.len() as i64 {
+        return Err(AppError::BadRequest("Unknown concept in default_concept_ids".to_string()));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Instantiation
+// ---------------------------------------------------------------------------
+
+/// Fill a `CreateNote` from a template before it is inserted.
+///
+/// Variables: `{{date}}` / `{{time}}` (from `time_start`, else now, UTC),
+/// `{{field_trip}}` (first of `field_trip_ids`), `{{location}}` and
+/// `{{weather}}`; unset values expand to an empty string. The template's
+/// title and body are used only where the request left them blank, its
+/// `note_type` only if none was given, and its tags/concepts are added to
+/// the request's.
+pub(crate) async fn apply_template(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    template_id: Uuid,
+    note: &mut CreateNote,
+) -> Result<(), AppError> {
+    let tpl = fetch_template(pool, workspace_id, template_id).await?;
+
+    let field_trip: Option<String> = match note.field_trip_ids.as_deref().and_then(|ids| ids.first()) {
+        Some(ft_id) => {
+            sqlx::query_scalar("SELECT name FROM field_trips WHERE id = $1 AND workspace_id = $2")
+                .bind(ft_id)
+                .bind(workspace_id)
+                .fetch_optional(pool)
+                .await?
+        }
+        None => None,
+    };
+
+    let when = note.time_start.unwrap_or_else(Utc::now);
+    let vars: HashMap<&str, String> = HashMap::from([
+        ("date", when.format("%Y-%m-%d").to_string()),
+        ("time", when.format("%H:%M").to_string()),
+        ("field_trip", field_trip.unwrap_or_default()),
+        ("location", note.location_name.clone().unwrap_or_default()),
+        ("weather", note.weather.clone().unwrap_or_default()),
+    ]);
+
+    if note.title.trim().is_empty() && !tpl.title.is_empty() {
+        note.title = template::expand_str(&tpl.title, &vars);
+    }
+    if template::is_blank(&note.body) {
+        let mut body = tpl.body;
+        template::expand_document(&mut body, &vars);
+        note.body_text = tiptap::plain_text(&body);
+        note.body = body;
+    }
+    if note.note_type.is_none() {
+        note.note_type = Some(tpl.note_type);
+    }
+
+    let tags = note.tag_names.get_or_insert_with(Vec::new);
+    for tag in tpl.default_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    let concepts = note.concept_ids.get_or_insert_with(Vec::new);
+    for concept_id in tpl.default_concept_ids {
+        if !concepts.contains(&concept_id) {
+            concepts.push(concept_id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_template(pool: &PgPool, workspace_id: Uuid, id: Uuid) -> Result<NoteTemplate, AppError> {
+    sqlx::query_as::<_, NoteTemplate>(&format!(
+        "SELECT {TEMPLATE_COLS} FROM note_templates WHERE id = $1 AND workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Template not found".to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// CRUD
+// ---------------------------------------------------------------------------
+
+async fn list_templates(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<NoteTemplate>>>, AppError> {
+    let templates = sqlx::query_as::<_, NoteTemplate>(&format!(
+        "SELECT {TEMPLATE_COLS} FROM note_templates WHERE workspace_id = $1 ORDER BY name"
+    ))
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let total = templates.len() as i64;
+    Ok(ApiResponse::list(templates, total, 1, total.max(1)))
+}
+
+async fn get_template(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<NoteTemplate>>, AppError> {
+    Ok(ApiResponse::ok(fetch_template(&pool, auth.workspace_id, id).await?))
+}
+
+async fn create_template(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(body): Json<CreateNoteTemplate>,
+) -> Result<Json<ApiResponse<NoteTemplate>>, AppError> {
+    if body.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Template name is required".to_string()));
+    }
+    check_concepts(&pool, auth.workspace_id, &body.default_concept_ids).await?;
+    let note_type = body.note_type.as_deref().unwrap_or(DEFAULT_NOTE_TYPE);
+    resolve_type(&pool, auth.workspace_id, note_type).await?;
+
+    let template = sqlx::query_as::<_, NoteTemplate>(&format!(
+        "INSERT INTO note_templates \
+         (workspace_id, name, description, title, body, note_type, default_tags, default_concept_ids) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+         RETURNING {TEMPLATE_COLS}"
+    ))
+    .bind(auth.workspace_id)
+    .bind(body.name.trim())
+    .bind(&body.description)
+    .bind(&body.title)
+    .bind(&body.body)
+    .bind(note_type)
+    .bind(&body.default_tags)
+    .bind(&body.default_concept_ids)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(ApiResponse::ok(template))
+}
+
+async fn update_template(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateNoteTemplate>,
+) -> Result<Json<ApiResponse<NoteTemplate>>, AppError> {
+    if let Some(ref concept_ids) = body.default_concept_ids {
+        check_concepts(&pool, auth.workspace_id, concept_ids).await?;
+    }
+    if let Some(ref note_type) = body.note_type {
+        resolve_type(&pool, auth.workspace_id, note_type).await?;
+    }
+
+    let template = sqlx::query_as::<_, NoteTemplate>(&format!(
+        "UPDATE note_templates SET \
+         name = COALESCE($3, name), \
+         description = COALESCE($4, description), \
+         title = COALESCE($5, title), \
+         body = COALESCE($6, body), \
+         note_type = COALESCE($7, note_type), \
+         default_tags = COALESCE($8, default_tags), \
+         default_concept_ids = COALESCE($9, default_concept_ids), \
+         updated_at = now() \
+         WHERE id = $1 AND workspace_id = $2 \
+         RETURNING {TEMPLATE_COLS}"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .bind(body.name.as_deref().map(str::trim))
+    .bind(&body.description)
+    .bind(&body.title)
+    .bind(&body.body)
+    .bind(&body.note_type)
+    .bind(&body.default_tags)
+    .bind(&body.default_concept_ids)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Template not found".to_string()))?;
+
+    Ok(ApiResponse::ok(template))
+}
+
+async fn delete_template(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let result = sqlx::query("DELETE FROM note_templates WHERE id = $1 AND workspace_id = $2")
+        .bind(id)
+        .bind(auth.workspace_id)
+        .execute(&pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Template not found".to_string()));
+    }
+
+    Ok(ApiResponse::ok(serde_json::json!({ "deleted": true })))
+}
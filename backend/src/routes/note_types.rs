@@ -0,0 +1,321 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use serde_json::Value;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::note_type::*;
+use crate::response::ApiResponse;
+
+const TYPE_COLS: &str = "id, workspace_id, key, name, icon, default_template_id, required_fields, \
+     sort_order, created_at, updated_at";
+
+/// Type given to notes and templates created without one. It cannot be
+/// deleted.
+pub(crate) const DEFAULT_NOTE_TYPE: &str = "field_note";
+
+/// Types every new workspace starts with, as `(key, name, icon)`.
+const BUILTIN_TYPES: &[(&str, &str, &str)] = &[
+    ("interview", "Interview", "🎙"),
+    ("field_note", "Field Note", "📋"),
+    ("voice_memo", "Voice Memo", "🎤"),
+    ("photo", "Photo", "📷"),
+];
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/api/v1/note-types", get(list_types).post(create_type))
+        .route(
+            "/api/v1/note-types/{id}",
+            get(get_type).put(update_type).delete(delete_type),
+        )
+}
+
+/// Create the built-in note types in a new workspace.
+pub(crate) async fn seed_builtin_types(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+) -> Result<(), AppError> {
+    for (sort_order, (key, name, icon)) in BUILTIN_TYPES.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO note_types (workspace_id, key, name, icon, sort_order) \
+             VALUES ($1, $2, $3, $4, $5) ON CONFLICT (workspace_id, key) DO NOTHING",
+        )
+        .bind(workspace_id)
+        .bind(key)
+        .bind(name)
+        .bind(icon)
+        .bind(sort_order as i32)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Look up a note type by key, rejecting keys the workspace has not defined.
+pub(crate) async fn resolve_type<'e, E: PgExecutor<'e>>(
+    executor: E,
+    workspace_id: Uuid,
+    key: &str,
+) -> Result<NoteType, AppError> {
+    sqlx::query_as::<_, NoteType>(&format!(
+        "SELECT {TYPE_COLS} FROM note_types WHERE workspace_id = $1 AND key = $2"
+    ))
+    .bind(workspace_id)
+    .bind(key)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::BadRequest(format!("Unknown note type: {key}")))
+}
+
+/// Reject a note whose `custom_fields` lack a value its type requires.
+/// Empty strings count as missing.
+pub(crate) fn check_required_fields(note_type: &NoteType, custom_fields: &Value) -> Result<(), AppError> {
+    let missing: Vec<&str> = note_type
+        .required_fields
+        .iter()
+        .filter(|key| match custom_fields.get(key.as_str()) {
+            None | Some(Value::Null) => true,
+            Some(Value::String(s)) => s.trim().is_empty(),
+            Some(_) => false,
+        })
+        .map(String::as_str)
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::BadRequest(format!(
+        "{} notes require custom fields: {}",
+        note_type.name,
+        missing.join(", ")
+    )))
+}
+
+/// Check that a default template and required fields belong to the workspace.
+async fn check_references(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    default_template_id: Option<Uuid>,
+    required_fields: Option<&[String]>,
+) -> Result<(), AppError> {
+    if let Some(template_id) = default_template_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM note_templates WHERE id = $1 AND workspace_id = $2)",
+        )
+        .bind(template_id)
+        .bind(workspace_id)
+        .fetch_one(pool)
+        .await?;
+        if !exists {
+            return Err(AppError::BadRequest("Unknown default_template_id".to_string()));
+        }
+    }
+
+    if let Some(keys) = required_fields {
+        let known: Vec<String> = sqlx::query_scalar(
+            "SELECT key FROM custom_field_definitions WHERE workspace_id = $1 AND key = ANY($2)",
+        )
+        .bind(workspace_id)
+        .bind(keys)
+        .fetch_all(pool)
+        .await?;
+        if let Some(unknown) = keys.iter().find(|k| !known.contains(k)) {
+            return Err(AppError::BadRequest(format!(
+                "Unknown custom field '{unknown}' in required_fields"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Trim and dedupe required field keys, keeping their order.
+fn normalize_fields(keys: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(keys.len());
+    for key in keys.iter().map(|k| k.trim()) {
+        if !out.iter().any(|k| k == key) {
+            out.push(key.to_string());
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// CRUD
+// ---------------------------------------------------------------------------
+
+async fn list_types(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<NoteType>>>, AppError> {
+    let types = sqlx::query_as::<_, NoteType>(&format!(
+        "SELECT {TYPE_COLS} FROM note_types WHERE workspace_id = $1 ORDER BY sort_order, name"
+    ))
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let total = types.len() as i64;
+    Ok(ApiResponse::list(types, total, 1, total.max(1)))
+}
+
+async fn get_type(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<NoteType>>, AppError> {
+    let note_type = sqlx::query_as::<_, NoteType>(&format!(
+        "SELECT {TYPE_COLS} FROM note_types WHERE id = $1 AND workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Note type not found".to_string()))?;
+
+    Ok(ApiResponse::ok(note_type))
+}
+
+async fn create_type(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(body): Json<CreateNoteType>,
+) -> Result<Json<ApiResponse<NoteType>>, AppError> {
+    let key = body.key.trim();
+    let valid_key = key.len() <= 63
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid_key {
+        return Err(AppError::BadRequest(
+            "Note type key must be lowercase letters, digits and underscores, starting with a letter"
+                .to_string(),
+        ));
+    }
+    if body.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Note type name is required".to_string()));
+    }
+    let required_fields = normalize_fields(&body.required_fields);
+    check_references(&pool, auth.workspace_id, body.default_template_id, Some(&required_fields)).await?;
+
+    let note_type = sqlx::query_as::<_, NoteType>(&format!(
+        "INSERT INTO note_types \
+         (workspace_id, key, name, icon, default_template_id, required_fields, sort_order) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+         ON CONFLICT (workspace_id, key) DO NOTHING \
+         RETURNING {TYPE_COLS}"
+    ))
+    .bind(auth.workspace_id)
+    .bind(key)
+    .bind(body.name.trim())
+    .bind(&body.icon)
+    .bind(body.default_template_id)
+    .bind(&required_fields)
+    .bind(body.sort_order)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict(format!("A note type with key '{key}' already exists")))?;
+
+    Ok(ApiResponse::ok(note_type))
+}
+
+/// Existing notes that lack a newly required field are only checked when
+/// they are next edited.
+async fn update_type(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateNoteType>,
+) -> Result<Json<ApiResponse<NoteType>>, AppError> {
+    if body.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::BadRequest("Note type name is required".to_string()));
+    }
+    let required_fields = body.required_fields.as_deref().map(normalize_fields);
+    check_references(
+        &pool,
+        auth.workspace_id,
+        body.default_template_id.flatten(),
+        required_fields.as_deref(),
+    )
+    .await?;
+
+    let note_type = sqlx::query_as::<_, NoteType>(&format!(
+        "UPDATE note_types SET \
+         name = COALESCE($3, name), \
+         icon = COALESCE($4, icon), \
+         default_template_id = CASE WHEN $5 THEN $6 ELSE default_template_id END, \
+         required_fields = COALESCE($7, required_fields), \
+         sort_order = COALESCE($8, sort_order), \
+         updated_at = now() \
+         WHERE id = $1 AND workspace_id = $2 \
+         RETURNING {TYPE_COLS}"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .bind(body.name.as_deref().map(str::trim))
+    .bind(&body.icon)
+    .bind(body.default_template_id.is_some())
+    .bind(body.default_template_id.flatten())
+    .bind(&required_fields)
+    .bind(body.sort_order)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Note type not found".to_string()))?;
+
+    Ok(ApiResponse::ok(note_type))
+}
+
+/// Only unused types can be deleted; notes in the trash count as using
+/// their type.
+async fn delete_type(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let key: String = sqlx::query_scalar(
+        "SELECT key FROM note_types WHERE id = $1 AND workspace_id = $2 FOR UPDATE",
+    )
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Note type not found".to_string()))?;
+
+    if key == DEFAULT_NOTE_TYPE {
+        return Err(AppError::Conflict("The default note type cannot be deleted".to_string()));
+    }
+
+    let (notes, templates): (i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND note_type = $2), \
+                (SELECT COUNT(*) FROM note_templates WHERE workspace_id = $1 AND note_type = $2)",
+    )
+    .bind(auth.workspace_id)
+    .bind(&key)
+    .fetch_one(&mut *tx)
+    .await?;
+    if notes > 0 || templates > 0 {
+        return Err(AppError::Conflict(format!(
+            "Note type '{key}' is used by {notes} notes and {templates} templates"
+        )));
+    }
+
+    sqlx::query("DELETE FROM note_types WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(ApiResponse::ok(serde_json::json!({ "deleted": true })))
+}
@@ -1,24 +1,49 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
 use axum::{
     extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::auth::middleware::AuthUser;
+use crate::embeddings::{self, EmbeddingProvider};
 use crate::error::AppError;
 use crate::middleware::plan_guard;
 use crate::models::entity::Entity;
+use crate::models::custom_field::FieldFilter;
 use crate::models::note::*;
-use crate::response::ApiResponse;
+use crate::models::note_revision::*;
+use crate::models::note_template::CreateNoteParams;
+use crate::response::{ApiResponse, Cursor, PaginationParams};
+use crate::routes::custom_fields::{apply_custom_fields, field_filter_sql, field_filters};
+use crate::routes::media::UPLOADS_DIR;
+use crate::routes::note_templates::apply_template;
+use crate::routes::note_types::{check_required_fields, resolve_type, DEFAULT_NOTE_TYPE};
+use crate::search::geo::{GeoFilter, GeoValue};
+use crate::tiptap;
+use crate::tiptap::diff::diff_documents;
+use crate::tiptap::render::{self, MediaRef, RenderContext};
+use crate::trash;
+
+const NOTE_COLS: &str = "id, workspace_id, title, body, body_text, note_type, \
+     is_starred, location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
+     time_start, time_end, custom_fields, version, created_at, updated_at, deleted_at, \
+     merged_into_id";
 
 // ---------------------------------------------------------------------------
 // Tiptap JSON helpers
 // ---------------------------------------------------------------------------
 
 /// Recursively walks a Tiptap JSON document and collects all nodes of the
-/// given `node_type`, returning their `attrs` objects.
+/// given `node_type`, returning their `attrs` objects. Marks of the same type
+/// on text nodes are collected too, so a `noteLink` may be either an inline
+/// atom or a link mark over ordinary text.
 fn collect_nodes<'a>(
     value: &'a serde_json::Value,
     node_type: &str,
@@ -32,6 +57,15 @@ fn collect_nodes<'a>(
             return; // atom node — no children to recurse into
         }
     }
+    if let Some(marks) = value.get("marks").and_then(|v| v.as_array()) {
+        for mark in marks {
+            if mark.get("type").and_then(|v| v.as_str()) == Some(node_type) {
+                if let Some(attrs) = mark.get("attrs") {
+                    out.push(attrs);
+                }
+            }
+        }
+    }
     // Recurse into `content` array
     if let Some(content) = value.get("content").and_then(|v| v.as_array()) {
         for child in content {
@@ -49,20 +83,33 @@ fn parse_uuid_attr(attrs: &serde_json::Value, key: &str) -> Option<Uuid> {
         .and_then(|s| Uuid::parse_str(s).ok())
 }
 
-/// Extract entity ids → mention counts and concept ids from a Tiptap JSON body.
-/// Returns `(entity_counts, concept_ids)`.
-fn extract_mentions(body: &serde_json::Value) -> (Vec<(Uuid, i32)>, Vec<Uuid>) {
-    // --- entity mentions ---
-    let mut entity_attrs: Vec<&serde_json::Value> = Vec::new();
-    collect_nodes(body, "entityMention", &mut entity_attrs);
+/// Ids referenced from a Tiptap JSON body.
+struct Mentions {
+    /// Entity id → number of `entityMention` nodes pointing at it.
+    entity_counts: Vec<(Uuid, i32)>,
+    concept_ids: Vec<Uuid>,
+    /// Linked note id → number of `noteLink` nodes/marks pointing at it.
+    note_link_counts: Vec<(Uuid, i32)>,
+}
 
-    let mut entity_counts: std::collections::HashMap<Uuid, i32> = std::collections::HashMap::new();
-    for attrs in &entity_attrs {
+/// Count occurrences of the `id` attr across all `node_type` nodes.
+fn count_ids(body: &serde_json::Value, node_type: &str) -> Vec<(Uuid, i32)> {
+    let mut attrs_list: Vec<&serde_json::Value> = Vec::new();
+    collect_nodes(body, node_type, &mut attrs_list);
+
+    let mut counts: std::collections::HashMap<Uuid, i32> = std::collections::HashMap::new();
+    for attrs in &attrs_list {
         if let Some(id) = parse_uuid_attr(attrs, "id") {
-            *entity_counts.entry(id).or_insert(0) += 1;
+            *counts.entry(id).or_insert(0) += 1;
         }
     }
-    let entity_counts: Vec<(Uuid, i32)> = entity_counts.into_iter().collect();
+    counts.into_iter().collect()
+}
+
+/// Extract entity mentions, concept tags and note links from a Tiptap JSON body.
+fn extract_mentions(body: &serde_json::Value) -> Mentions {
+    // --- entity mentions ---
+    let entity_counts = count_ids(body, "entityMention");
 
     // --- concept tags ---
     let mut concept_attrs: Vec<&serde_json::Value> = Vec::new();
@@ -78,18 +125,29 @@ fn extract_mentions(body: &serde_json::Value) -> (Vec<(Uuid, i32)>, Vec<Uuid>) {
         }
     }
 
-    (entity_counts, concept_ids)
+    // --- note-to-note links ---
+    let note_link_counts = count_ids(body, "noteLink");
+
+    Mentions {
+        entity_counts,
+        concept_ids,
+        note_link_counts,
+    }
 }
 
-/// Re-link entities and concepts for a note based on its Tiptap JSON body,
-/// then regenerate graph edges. Runs inside the caller's transaction.
+/// Re-link entities, concepts and linked notes for a note based on its Tiptap
+/// JSON body, then regenerate graph edges. Runs inside the caller's transaction.
 async fn sync_note_links(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     workspace_id: Uuid,
     note_id: Uuid,
     body: &serde_json::Value,
 ) -> Result<(), AppError> {
-    let (entity_counts, concept_ids) = extract_mentions(body);
+    let Mentions {
+        entity_counts,
+        concept_ids,
+        note_link_counts,
+    } = extract_mentions(body);
 
     // ------------------------------------------------------------------
     // 1. Rebuild note_entities
@@ -246,50 +304,218 @@ async fn sync_note_links(
         }
     }
 
-    Ok(())
-}
+    // ------------------------------------------------------------------
+    // 4. Rebuild outgoing note_links and their note_link graph edges.
+    //    Targets outside the workspace (or self-links) are ignored.
+    // ------------------------------------------------------------------
+    sqlx::query("DELETE FROM note_links WHERE source_note_id = $1")
+        .bind(note_id)
+        .execute(&mut **tx)
+        .await?;
 
-async fn list_notes(
-    auth: AuthUser,
-    State(pool): State<PgPool>,
-    Query(filters): Query<NoteFilters>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
-    let page = filters.page.unwrap_or(1).max(1);
-    let per_page = filters.per_page.unwrap_or(20).clamp(1, 100);
-    let offset = (page - 1) * per_page;
-    let show_deleted = filters.deleted.unwrap_or(false);
+    sqlx::query(
+        "DELETE FROM graph_edges \
+         WHERE workspace_id = $1 AND edge_type = 'note_link' \
+           AND source_type = 'note' AND source_id = $2",
+    )
+    .bind(workspace_id)
+    .bind(note_id)
+    .execute(&mut **tx)
+    .await?;
 
-    if filters.count_only.unwrap_or(false) {
-        let total: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NULL",
+    for (target_id, count) in &note_link_counts {
+        let inserted = sqlx::query(
+            "INSERT INTO note_links (source_note_id, target_note_id, link_count) \
+             SELECT $1, n.id, $3 FROM notes n \
+             WHERE n.id = $2 AND n.workspace_id = $4 AND n.id <> $1 \
+             ON CONFLICT (source_note_id, target_note_id) DO UPDATE SET link_count = EXCLUDED.link_count",
         )
-        .bind(auth.workspace_id)
-        .fetch_one(&pool)
+        .bind(note_id)
+        .bind(target_id)
+        .bind(count)
+        .bind(workspace_id)
+        .execute(&mut **tx)
         .await?;
 
-        let starred: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NULL AND is_starred = true"
-        )
-        .bind(auth.workspace_id)
-        .fetch_one(&pool)
-        .await?;
+        if inserted.rows_affected() == 0 {
+            continue;
+        }
 
-        let deleted: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NOT NULL",
+        let weight = (*count as f32).min(10.0) / 10.0;
+        sqlx::query(
+            "INSERT INTO graph_edges \
+             (workspace_id, source_type, source_id, target_type, target_id, edge_type, strength) \
+             VALUES ($1, 'note', $2, 'note', $3, 'note_link', $4) \
+             ON CONFLICT (workspace_id, source_type, source_id, target_type, target_id, edge_type) \
+             DO UPDATE SET strength = EXCLUDED.strength, updated_at = now()",
         )
-        .bind(auth.workspace_id)
-        .fetch_one(&pool)
+        .bind(workspace_id)
+        .bind(note_id)
+        .bind(target_id)
+        .bind(weight)
+        .execute(&mut **tx)
         .await?;
+    }
 
-        let counts = NoteCount {
-            total,
-            starred,
-            deleted,
-        };
-        return Ok(ApiResponse::ok(serde_json::to_value(counts).unwrap()));
+    Ok(())
+}
+
+/// Recompute every derived graph edge (`entity_co_mention`, `entity_concept`,
+/// `note_link`) for a workspace from its link tables in one pass, using the
+/// same strength formula as `sync_note_links`. Derived edges that are no
+/// longer backed by any note are removed; curated edge types are untouched.
+/// Used after operations that touch many notes at once.
+pub(crate) async fn recompute_graph_edges(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+) -> Result<(), AppError> {
+    // The DELETE sees the table as it was before the INSERT, so rows the
+    // upsert just created are never candidates and updated rows are kept
+    // via their RETURNING ids.
+    sqlx::query(
+        "WITH derived AS ( \
+             SELECT 'entity' AS source_type, ne1.entity_id AS source_id, \
+                    'entity' AS target_type, ne2.entity_id AS target_id, \
+                    'entity_co_mention'::edge_type AS edge_type, COUNT(DISTINCT ne1.note_id) AS shared \
+             FROM note_entities ne1 \
+             JOIN note_entities ne2 ON ne1.note_id = ne2.note_id AND ne1.entity_id < ne2.entity_id \
+             JOIN notes n ON n.id = ne1.note_id \
+             WHERE n.workspace_id = $1 \
+             GROUP BY ne1.entity_id, ne2.entity_id \
+             UNION ALL \
+             SELECT 'entity', ne.entity_id, 'concept', nc.concept_id, \
+                    'entity_concept'::edge_type, COUNT(DISTINCT ne.note_id) \
+             FROM note_entities ne \
+             JOIN note_concepts nc ON nc.note_id = ne.note_id \
+             JOIN notes n ON n.id = ne.note_id \
+             WHERE n.workspace_id = $1 \
+             GROUP BY ne.entity_id, nc.concept_id \
+             UNION ALL \
+             SELECT 'note', nl.source_note_id, 'note', nl.target_note_id, \
+                    'note_link'::edge_type, nl.link_count::BIGINT \
+             FROM note_links nl \
+             JOIN notes n ON n.id = nl.source_note_id \
+             WHERE n.workspace_id = $1 \
+         ), upserted AS ( \
+             INSERT INTO graph_edges \
+             (workspace_id, source_type, source_id, target_type, target_id, edge_type, strength) \
+             SELECT $1, source_type, source_id, target_type, target_id, edge_type, \
+                    LEAST(shared, 10)::REAL / 10.0 \
+             FROM derived \
+             ON CONFLICT (workspace_id, source_type, source_id, target_type, target_id, edge_type) \
+             DO UPDATE SET strength = EXCLUDED.strength, updated_at = now() \
+             RETURNING id \
+         ) \
+         DELETE FROM graph_edges \
+         WHERE workspace_id = $1 \
+           AND edge_type IN ('entity_co_mention', 'entity_concept', 'note_link') \
+           AND id NOT IN (SELECT id FROM upserted)",
+    )
+    .bind(workspace_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Lock a live note row for the rest of the transaction and return it.
+async fn lock_note(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    note_id: Uuid,
+) -> Result<Note, AppError> {
+    sqlx::query_as::<_, Note>(&format!(
+        "SELECT {NOTE_COLS} FROM notes \
+         WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL \
+         FOR UPDATE"
+    ))
+    .bind(note_id)
+    .bind(workspace_id)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Note not found".to_string()))
+}
+
+/// Append the note's current content to its revision history with the next
+/// revision number. The caller must hold the note row lock (see `lock_note`).
+async fn record_revision(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    note: &Note,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO note_revisions (note_id, revision_number, title, body, body_text, created_by) \
+         VALUES ($1, \
+                 (SELECT COALESCE(MAX(revision_number), 0) + 1 FROM note_revisions WHERE note_id = $1), \
+                 $2, $3, $4, $5)",
+    )
+    .bind(note.id)
+    .bind(&note.title)
+    .bind(&note.body)
+    .bind(&note.body_text)
+    .bind(user_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Parse an `If-Match` header into the note versions the client will accept.
+/// Returns `None` when there is no precondition (header absent or `*`).
+fn parse_if_match(headers: &HeaderMap) -> Result<Option<Vec<i32>>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| AppError::BadRequest("Invalid If-Match header".to_string()))?;
+    if value.trim() == "*" {
+        return Ok(None);
+    }
+
+    value
+        .split(',')
+        .map(|tag| {
+            let tag = tag.trim();
+            let tag = tag.strip_prefix("W/").unwrap_or(tag);
+            tag.trim_matches('"')
+                .parse::<i32>()
+                .map_err(|_| AppError::BadRequest(format!("Invalid entity tag in If-Match: {tag}")))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+fn etag_header(note: &Note) -> [(header::HeaderName, String); 1] {
+    [(header::ETAG, note.etag())]
+}
+
+pub(crate) async fn ensure_note_exists(pool: &PgPool, workspace_id: Uuid, note_id: Uuid) -> Result<(), AppError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM notes WHERE id = $1 AND workspace_id = $2)",
+    )
+    .bind(note_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await?;
+
+    if !exists {
+        return Err(AppError::NotFound("Note not found".into()));
     }
+    Ok(())
+}
 
-    // Build dynamic query
+/// JOINs, WHERE clause, distance expression and next free parameter index
+/// for a `NoteFilters` query over `notes n`. `$1` is the workspace id;
+/// filter values follow in field order: field trip, concept, entity, entity
+/// type, note type, then a key and value per custom field filter, then
+/// `geo.values()`.
+fn note_filter_sql(
+    filters: &NoteFilters,
+    field_filters: &[FieldFilter],
+    geo: &GeoFilter,
+) -> (String, String, String, u32) {
+    let show_deleted = filters.deleted.unwrap_or(false);
     let mut conditions = vec!["n.workspace_id = $1".to_string()];
     let mut param_idx = 2u32;
 
@@ -299,9 +525,6 @@ async fn list_notes(
         conditions.push("n.deleted_at IS NULL".to_string());
     }
 
-    // We'll use a simpler approach: build the full query string
-    // and bind parameters positionally.
-    // Tag JOIN is always included for ARRAY_AGG aggregation.
     let mut filter_joins = String::new();
 
     if filters.field_trip_id.is_some() {
@@ -332,7 +555,7 @@ async fn list_notes(
     }
 
     if filters.note_type.is_some() {
-        conditions.push(format!("n.note_type::text = ${}", param_idx));
+        conditions.push(format!("n.note_type = ${}", param_idx));
         param_idx += 1;
     }
 
@@ -340,15 +563,88 @@ async fn list_notes(
         conditions.push("n.is_starred = true".to_string());
     }
 
+    for filter in field_filters {
+        conditions.push(field_filter_sql(filter, param_idx));
+        param_idx += 2;
+    }
+
+    for fragment in geo.conditions() {
+        conditions.push(fragment.numbered(&mut param_idx));
+    }
+    let distance = geo.distance().numbered(&mut param_idx);
+
     let where_clause = format!(" WHERE {}", conditions.join(" AND "));
-    let order = match (filters.sort.as_deref(), filters.order.as_deref()) {
-        (Some("updated_at"), Some("asc")) => " ORDER BY n.updated_at ASC",
-        (Some("updated_at"), _) => " ORDER BY n.updated_at DESC",
-        (Some("title"), Some("asc")) => " ORDER BY n.title ASC",
-        (Some("title"), _) => " ORDER BY n.title DESC",
-        (_, Some("asc")) => " ORDER BY n.created_at ASC",
-        _ => " ORDER BY n.created_at DESC",
+    (filter_joins, where_clause, distance, param_idx)
+}
+
+async fn list_notes(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Query(filters): Query<NoteFilters>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let page = filters.page.unwrap_or(1).max(1);
+    let per_page = filters.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    if filters.count_only.unwrap_or(false) {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(auth.workspace_id)
+        .fetch_one(&pool)
+        .await?;
+
+        let starred: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NULL AND is_starred = true"
+        )
+        .bind(auth.workspace_id)
+        .fetch_one(&pool)
+        .await?;
+
+        let deleted: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(auth.workspace_id)
+        .fetch_one(&pool)
+        .await?;
+
+        let counts = NoteCount {
+            total,
+            starred,
+            deleted,
+        };
+        return Ok(ApiResponse::ok(serde_json::to_value(counts).unwrap()));
+    }
+
+    let field_filters = field_filters(&pool, auth.workspace_id, filters.fields.as_deref()).await?;
+    let geo = GeoFilter::from_filters(&filters)?;
+    let (filter_joins, where_clause, distance, mut param_idx) =
+        note_filter_sql(&filters, &field_filters, &geo);
+    if filters.sort.as_deref() == Some("distance") && geo.near.is_none() {
+        return Err(AppError::BadRequest("Sorting by distance requires 'near'".to_string()));
+    }
+    let (sort_col, dir) = match (filters.sort.as_deref(), filters.order.as_deref()) {
+        (Some("distance"), Some("desc")) => ("distance", "DESC"),
+        (Some("distance"), _) => ("distance", "ASC"),
+        (Some("updated_at"), Some("asc")) => ("updated_at", "ASC"),
+        (Some("updated_at"), _) => ("updated_at", "DESC"),
+        (Some("title"), Some("asc")) => ("title", "ASC"),
+        (Some("title"), _) => ("title", "DESC"),
+        (_, Some("asc")) => ("created_at", "ASC"),
+        _ => ("created_at", "DESC"),
     };
+    let sort_expr = match sort_col {
+        "distance" => distance.clone(),
+        col => format!("n.{col}"),
+    };
+    // n.id breaks ties so keyset pages neither skip nor repeat rows.
+    let order = format!(" ORDER BY {sort_expr} {dir}, n.id {dir}");
+    let sort_name = format!("{sort_col}_{}", dir.to_lowercase());
+    let cursor = filters
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c, &sort_name))
+        .transpose()?;
 
     // Count query does not need tag aggregation
     let count_str = format!(
@@ -356,18 +652,32 @@ async fn list_notes(
         filter_joins, where_clause
     );
 
-    // Data query: filter joins first, then LEFT JOIN for tags + GROUP BY for aggregation
+    // With a cursor, continue after the last row of the previous page.
+    let mut data_where = where_clause.clone();
+    if cursor.is_some() {
+        let cmp = if dir == "ASC" { ">" } else { "<" };
+        data_where.push_str(&format!(
+            " AND ({sort_expr}, n.id) {cmp} (${}, ${})",
+            param_idx,
+            param_idx + 1
+        ));
+        param_idx += 2;
+    }
+
+    // Data query: filter joins first, then LEFT JOIN for tags + GROUP BY for aggregation.
+    // One extra row tells whether there is a next page.
     let query_str = format!(
-        "SELECT n.id, n.workspace_id, n.title, n.body_text, n.note_type::text AS note_type, n.is_starred, \
-         n.location_name, n.gps_coords, n.weather, \
+        "SELECT n.id, n.workspace_id, n.title, n.body_text, n.note_type, ty.name AS note_type_name, \
+         ty.icon AS note_type_icon, n.is_starred, n.location_name, n.gps_coords, n.weather, n.custom_fields, \
          COALESCE(ARRAY_AGG(t.name ORDER BY t.name) FILTER (WHERE t.name IS NOT NULL), ARRAY[]::TEXT[]) AS tags, \
-         n.created_at, n.updated_at \
+         n.created_at, n.updated_at, {} AS distance_km \
          FROM notes n{} \
+         JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type \
          LEFT JOIN note_tags nt ON nt.note_id = n.id \
          LEFT JOIN tags t ON t.id = nt.tag_id{} \
-         GROUP BY n.id{} \
+         GROUP BY n.id, ty.id{} \
          LIMIT ${} OFFSET ${}",
-        filter_joins, where_clause, order, param_idx, param_idx + 1
+        distance, filter_joins, data_where, order, param_idx, param_idx + 1
     );
 
     // Build and execute count query
@@ -387,6 +697,15 @@ async fn list_notes(
     if let Some(ref nt) = filters.note_type {
         count_q = count_q.bind(nt);
     }
+    for f in &field_filters {
+        count_q = count_q.bind(&f.key).bind(&f.value);
+    }
+    for v in geo.values() {
+        count_q = match v {
+            GeoValue::Number(x) => count_q.bind(x),
+            GeoValue::Date(d) => count_q.bind(d),
+        };
+    }
     let total = count_q.fetch_one(&pool).await?;
 
     // Build and execute data query
@@ -406,14 +725,48 @@ async fn list_notes(
     if let Some(ref nt) = filters.note_type {
         data_q = data_q.bind(nt);
     }
-    data_q = data_q.bind(per_page).bind(offset);
-    let notes = data_q.fetch_all(&pool).await?;
+    for f in &field_filters {
+        data_q = data_q.bind(&f.key).bind(&f.value);
+    }
+    for v in geo.values() {
+        data_q = match v {
+            GeoValue::Number(x) => data_q.bind(x),
+            GeoValue::Date(d) => data_q.bind(d),
+        };
+    }
+    if let Some(ref c) = cursor {
+        data_q = match sort_col {
+            "title" => data_q.bind(c.key::<String>()?),
+            "distance" => data_q.bind(c.key::<f64>()?),
+            _ => data_q.bind(c.key::<DateTime<Utc>>()?),
+        }
+        .bind(c.id);
+    }
+    let offset = if cursor.is_some() { 0 } else { offset };
+    data_q = data_q.bind(per_page + 1).bind(offset);
+    let mut notes = data_q.fetch_all(&pool).await?;
+
+    let next_cursor = if notes.len() as i64 > per_page {
+        notes.truncate(per_page as usize);
+        notes.last().map(|n| {
+            let key = match sort_col {
+                "title" => serde_json::json!(n.title),
+                "updated_at" => serde_json::json!(n.updated_at),
+                "distance" => serde_json::json!(n.distance_km),
+                _ => serde_json::json!(n.created_at),
+            };
+            Cursor::new(&sort_name, key, n.id).encode()
+        })
+    } else {
+        None
+    };
 
-    Ok(ApiResponse::list(
+    Ok(ApiResponse::cursor_list(
         serde_json::to_value(notes).unwrap(),
         total,
-        page,
+        cursor.is_none().then_some(page),
         per_page,
+        next_cursor,
     ))
 }
 
@@ -421,51 +774,95 @@ async fn get_note(
     auth: AuthUser,
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Note>>, AppError> {
-    let note = sqlx::query_as::<_, Note>(
-        "SELECT id, workspace_id, title, body, body_text, note_type::text, is_starred, \
-         location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
-         time_start, time_end, created_at, updated_at, deleted_at \
-         FROM notes WHERE id = $1 AND workspace_id = $2",
-    )
+) -> Result<([(header::HeaderName, String); 1], Json<ApiResponse<Note>>), AppError> {
+    let note = sqlx::query_as::<_, Note>(&format!(
+        "SELECT {NOTE_COLS} FROM notes WHERE id = $1 AND workspace_id = $2"
+    ))
     .bind(id)
     .bind(auth.workspace_id)
     .fetch_optional(&pool)
     .await?
     .ok_or_else(|| AppError::NotFound("Note not found".to_string()))?;
 
-    Ok(ApiResponse::ok(note))
+    Ok((etag_header(&note), ApiResponse::ok(note)))
 }
 
 async fn create_note(
     auth: AuthUser,
     State(pool): State<PgPool>,
-    Json(body): Json<CreateNote>,
+    Extension(embedder): Extension<EmbeddingProvider>,
+    Query(params): Query<CreateNoteParams>,
+    Json(mut body): Json<CreateNote>,
 ) -> Result<Json<ApiResponse<Note>>, AppError> {
     // Check plan limit
     plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "notes").await?;
 
-    let title = if body.title.is_empty() { "Untitled".to_string() } else { body.title.clone() };
+    // An explicit template wins over the note type's default one.
+    let template_id = match params.template_id {
+        Some(id) => Some(id),
+        None => {
+            let key = body.note_type.as_deref().unwrap_or(DEFAULT_NOTE_TYPE);
+            resolve_type(&pool, auth.workspace_id, key).await?.default_template_id
+        }
+    };
+    if let Some(template_id) = template_id {
+        apply_template(&pool, auth.workspace_id, template_id, &mut body).await?;
+    }
 
     let mut tx = pool
         .begin()
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let note = sqlx::query_as::<_, Note>(
+    let note = insert_note(&mut tx, auth.workspace_id, &body).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Increment usage counter (best-effort)
+    let _ = plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "notes_count", 1).await;
+
+    embeddings::spawn_embed_note(pool, embedder, note.id);
+
+    Ok(ApiResponse::ok(note))
+}
+
+/// Insert a note with its field trip, tag, concept and entity associations,
+/// then extract mentions from the body. Shared by `create_note` and the
+/// importers; the caller owns the transaction and the plan-limit check.
+pub(crate) async fn insert_note(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    body: &CreateNote,
+) -> Result<Note, AppError> {
+    let title = if body.title.is_empty() { "Untitled".to_string() } else { body.title.clone() };
+    let custom_fields = match body.custom_fields {
+        Some(ref values) => {
+            apply_custom_fields(&mut **tx, workspace_id, &serde_json::json!({}), values).await?
+        }
+        None => serde_json::json!({}),
+    };
+    let note_type = resolve_type(
+        &mut **tx,
+        workspace_id,
+        body.note_type.as_deref().unwrap_or(DEFAULT_NOTE_TYPE),
+    )
+    .await?;
+    check_required_fields(&note_type, &custom_fields)?;
+
+    let note = sqlx::query_as::<_, Note>(&format!(
         "INSERT INTO notes (workspace_id, title, body, body_text, note_type, \
          location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
-         time_start, time_end) \
-         VALUES ($1, $2, $3, $4, $5::note_type, $6, $7, $8, $9, $10, $11, $12, $13) \
-         RETURNING id, workspace_id, title, body, body_text, note_type::text, is_starred, \
-         location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
-         time_start, time_end, created_at, updated_at, deleted_at",
-    )
-    .bind(auth.workspace_id)
+         time_start, time_end, custom_fields) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) \
+         RETURNING {NOTE_COLS}"
+    ))
+    .bind(workspace_id)
     .bind(&title)
     .bind(&body.body)
     .bind(&body.body_text)
-    .bind(&body.note_type)
+    .bind(&note_type.key)
     .bind(&body.location_name)
     .bind(body.location_lat)
     .bind(body.location_lng)
@@ -474,16 +871,25 @@ async fn create_note(
     .bind(body.temperature_c)
     .bind(body.time_start)
     .bind(body.time_end)
-    .fetch_one(&mut *tx)
+    .bind(&custom_fields)
+    .fetch_one(&mut **tx)
     .await?;
 
+    // --- Auto-extract entities/concepts from Tiptap body and regenerate graph edges ---
+    // Runs before the explicit associations below, since it rebuilds
+    // note_entities/note_concepts from the body and would drop them.
+    // Only parse if the body is a non-trivial object (not the default empty `{}`)
+    if body.body.is_object() && !body.body.as_object().map(|o| o.is_empty()).unwrap_or(true) {
+        sync_note_links(tx, workspace_id, note.id, &body.body).await?;
+    }
+
     // Associate field trips
     if let Some(ref ft_ids) = body.field_trip_ids {
         for ft_id in ft_ids {
             sqlx::query("INSERT INTO note_field_trips (note_id, field_trip_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
                 .bind(note.id)
                 .bind(ft_id)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await?;
         }
     }
@@ -496,9 +902,9 @@ async fn create_note(
                  ON CONFLICT (workspace_id, name) DO UPDATE SET name = EXCLUDED.name \
                  RETURNING id",
             )
-            .bind(auth.workspace_id)
+            .bind(workspace_id)
             .bind(name)
-            .fetch_one(&mut *tx)
+            .fetch_one(&mut **tx)
             .await?;
 
             sqlx::query(
@@ -506,7 +912,7 @@ async fn create_note(
             )
             .bind(note.id)
             .bind(tag_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         }
     }
@@ -517,7 +923,7 @@ async fn create_note(
             sqlx::query("INSERT INTO note_concepts (note_id, concept_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
                 .bind(note.id)
                 .bind(c_id)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await?;
         }
     }
@@ -531,44 +937,69 @@ async fn create_note(
             )
             .bind(note.id)
             .bind(e_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         }
     }
 
-    // --- Auto-extract entities/concepts from Tiptap body and regenerate graph edges ---
-    // Only parse if the body is a non-trivial object (not the default empty `{}`)
-    if body.body.is_object() && !body.body.as_object().map(|o| o.is_empty()).unwrap_or(true) {
-        sync_note_links(&mut tx, auth.workspace_id, note.id, &body.body).await?;
-    }
-
-    tx.commit()
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
-
-    // Increment usage counter (best-effort)
-    let _ = plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "notes_count", 1).await;
-
-    Ok(ApiResponse::ok(note))
+    Ok(note)
 }
 
 async fn update_note(
     auth: AuthUser,
     State(pool): State<PgPool>,
+    Extension(embedder): Extension<EmbeddingProvider>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(body): Json<UpdateNote>,
-) -> Result<Json<ApiResponse<Note>>, AppError> {
+) -> Result<([(header::HeaderName, String); 1], Json<ApiResponse<Note>>), AppError> {
+    let expected_versions = parse_if_match(&headers)?;
+
     let mut tx = pool
         .begin()
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let note = sqlx::query_as::<_, Note>(
+    let current = lock_note(&mut tx, auth.workspace_id, id).await?;
+
+    // Reject edits based on a stale copy, handing back what the server has now.
+    if let Some(expected) = expected_versions {
+        if !expected.contains(&current.version) {
+            return Err(AppError::PreconditionFailed(
+                "Note has been modified since it was loaded".to_string(),
+                serde_json::to_value(&current).unwrap_or_default(),
+            ));
+        }
+    }
+
+    // Snapshot the previous content before it is overwritten.
+    let content_changed = body.title.as_ref().is_some_and(|t| *t != current.title)
+        || body.body.as_ref().is_some_and(|b| *b != current.body)
+        || body.body_text.as_ref().is_some_and(|t| *t != current.body_text);
+    if content_changed {
+        record_revision(&mut tx, auth.user_id, &current).await?;
+    }
+
+    let custom_fields = match body.custom_fields {
+        Some(ref values) => Some(
+            apply_custom_fields(&mut *tx, auth.workspace_id, &current.custom_fields, values).await?,
+        ),
+        None => None,
+    };
+    // Only checked when the type or the fields change, so notes predating a
+    // newly required field stay editable.
+    if body.note_type.is_some() || custom_fields.is_some() {
+        let key = body.note_type.as_deref().unwrap_or(&current.note_type);
+        let note_type = resolve_type(&mut *tx, auth.workspace_id, key).await?;
+        check_required_fields(&note_type, custom_fields.as_ref().unwrap_or(&current.custom_fields))?;
+    }
+
+    let note = sqlx::query_as::<_, Note>(&format!(
         "UPDATE notes SET \
          title = COALESCE($3, title), \
          body = COALESCE($4, body), \
          body_text = COALESCE($5, body_text), \
-         note_type = COALESCE($6::note_type, note_type), \
+         note_type = COALESCE($6, note_type), \
          location_name = COALESCE($7, location_name), \
          location_lat = COALESCE($8, location_lat), \
          location_lng = COALESCE($9, location_lng), \
@@ -577,12 +1008,12 @@ async fn update_note(
          temperature_c = COALESCE($12, temperature_c), \
          time_start = COALESCE($13, time_start), \
          time_end = COALESCE($14, time_end), \
+         custom_fields = COALESCE($15, custom_fields), \
+         version = version + 1, \
          updated_at = now() \
          WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL \
-         RETURNING id, workspace_id, title, body, body_text, note_type::text, is_starred, \
-         location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
-         time_start, time_end, created_at, updated_at, deleted_at",
-    )
+         RETURNING {NOTE_COLS}"
+    ))
     .bind(id)
     .bind(auth.workspace_id)
     .bind(&body.title)
@@ -597,6 +1028,7 @@ async fn update_note(
     .bind(body.temperature_c)
     .bind(body.time_start)
     .bind(body.time_end)
+    .bind(&custom_fields)
     .fetch_optional(&mut *tx)
     .await?
     .ok_or_else(|| AppError::NotFound("Note not found".to_string()))?;
@@ -676,7 +1108,11 @@ async fn update_note(
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    Ok(ApiResponse::ok(note))
+    if content_changed {
+        embeddings::spawn_embed_note(pool, embedder, id);
+    }
+
+    Ok((etag_header(&note), ApiResponse::ok(note)))
 }
 
 async fn delete_note(
@@ -764,7 +1200,7 @@ async fn note_connections(
     }
 
     let connected = sqlx::query_as::<_, ConnectedNote>(
-        "SELECT n.id, n.title, n.note_type::text, ge.strength, ge.label \
+        "SELECT n.id, n.title, n.note_type, ge.strength, ge.label \
          FROM graph_edges ge \
          JOIN notes n ON (\
              (ge.target_type = 'note' AND ge.target_id = n.id) \
@@ -789,7 +1225,7 @@ async fn restore_note(
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
     let result = sqlx::query(
-        "UPDATE notes SET deleted_at = NULL, updated_at = now() \
+        "UPDATE notes SET deleted_at = NULL, merged_into_id = NULL, updated_at = now() \
          WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NOT NULL",
     )
     .bind(id)
@@ -809,24 +1245,43 @@ async fn permanent_delete_note(
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
-    // Only allow hard-deleting notes that are already in the trash
-    let result = sqlx::query(
-        "DELETE FROM notes WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NOT NULL",
-    )
-    .bind(id)
-    .bind(auth.workspace_id)
-    .execute(&pool)
-    .await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    if result.rows_affected() == 0 {
+    // Only allow hard-deleting notes that are already in the trash
+    let purged = trash::purge_notes(&mut tx, auth.workspace_id, &[id]).await?;
+    if purged.note_ids.is_empty() {
         return Err(AppError::NotFound("Note not found in trash".to_string()));
     }
 
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    release_purged(&pool, &auth, &purged).await;
+
     Ok(ApiResponse::ok(
         serde_json::json!({ "permanently_deleted": true }),
     ))
 }
 
+/// Remove purged media files and give their storage back to the user.
+async fn release_purged(pool: &PgPool, auth: &AuthUser, purged: &trash::PurgedNotes) {
+    purged.remove_files().await;
+    if purged.bytes > 0 {
+        let _ = plan_guard::increment_usage(
+            pool,
+            auth.user_id,
+            auth.workspace_id,
+            "storage_bytes",
+            -purged.bytes,
+        )
+        .await;
+    }
+}
+
 async fn note_entities_list(
     auth: AuthUser,
     State(pool): State<PgPool>,
@@ -849,17 +1304,1016 @@ async fn note_entities_list(
     Ok(ApiResponse::list(entities, total, 1, total.max(1)))
 }
 
+async fn note_backlinks(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<Backlink>>>, AppError> {
+    ensure_note_exists(&pool, auth.workspace_id, id).await?;
+
+    let backlinks = sqlx::query_as::<_, Backlink>(
+        "SELECT n.id, n.title, n.note_type, nl.link_count, n.updated_at \
+         FROM note_links nl \
+         JOIN notes n ON n.id = nl.source_note_id \
+         WHERE nl.target_note_id = $1 AND n.workspace_id = $2 AND n.deleted_at IS NULL \
+         ORDER BY n.updated_at DESC",
+    )
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let total = backlinks.len() as i64;
+    Ok(ApiResponse::list(backlinks, total, 1, total.max(1)))
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+#[derive(sqlx::FromRow)]
+struct MediaRefRow {
+    id: Uuid,
+    s3_key: String,
+    label: Option<String>,
+    original_filename: Option<String>,
+}
+
+/// Resolve the note's media so rendered `image` nodes point at servable URLs,
+/// built by `file_url` from the media id for files stored locally.
+pub(crate) async fn note_render_context(
+    pool: &PgPool,
+    note_id: Uuid,
+    file_url: impl Fn(Uuid) -> String,
+) -> Result<RenderContext, AppError> {
+    let rows = sqlx::query_as::<_, MediaRefRow>(
+        "SELECT id, s3_key, label, original_filename FROM media WHERE note_id = $1",
+    )
+    .bind(note_id)
+    .fetch_all(pool)
+    .await?;
+
+    let media = rows
+        .into_iter()
+        .map(|m| {
+            let url = if m.s3_key.starts_with("http://") || m.s3_key.starts_with("https://") {
+                m.s3_key
+            } else {
+                file_url(m.id)
+            };
+            (m.id, MediaRef { url, label: m.label.or(m.original_filename) })
+        })
+        .collect();
+
+    Ok(RenderContext { media })
+}
+
+/// ASCII, filesystem-friendly version of a note title, usable as a plain
+/// `Content-Disposition` filename.
+pub(crate) fn export_filename(title: &str, ext: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let stem = stem.trim_matches('-');
+    let stem = if stem.is_empty() { "note" } else { stem };
+    format!("{stem}.{ext}")
+}
+
+/// `Content-Disposition` naming the file after the title: an ASCII
+/// `filename` for old clients and the real title as an RFC 6266
+/// `filename*`.
+fn content_disposition(disposition: &str, title: &str, ext: &str) -> HeaderValue {
+    let name = Some(title.trim()).filter(|t| !t.is_empty()).unwrap_or("note");
+    let mut encoded = String::new();
+    for byte in format!("{name}.{ext}").bytes() {
+        // RFC 5987 attr-char
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    let value = format!(
+        "{disposition}; filename=\"{}\"; filename*=UTF-8''{encoded}",
+        export_filename(title, ext)
+    );
+    HeaderValue::from_str(&value).expect("Content-Disposition is printable ASCII")
+}
+
+async fn export_note(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ExportParams>,
+) -> Result<(HeaderMap, String), AppError> {
+    let note = sqlx::query_as::<_, Note>(&format!(
+        "SELECT {NOTE_COLS} FROM notes WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Note not found".to_string()))?;
+
+    let ctx = note_render_context(&pool, id, |media_id| format!("/api/v1/media/{media_id}/file")).await?;
+
+    let (content_type, ext, output) = match params.format.as_deref().unwrap_or("md") {
+        "md" | "markdown" => (
+            "text/markdown; charset=utf-8",
+            "md",
+            format!("# {}\n\n{}", note.title, render::to_markdown(&note.body, &ctx)),
+        ),
+        "html" => (
+            "text/html; charset=utf-8",
+            "html",
+            format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n\
+                 <body>\n<article>\n<h1>{title}</h1>\n{body}\n</article>\n</body>\n</html>\n",
+                title = render::escape_html(&note.title),
+                body = render::to_html(&note.body, &ctx),
+            ),
+        ),
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unsupported export format '{other}'; expected md or html"
+            )))
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        content_disposition("inline", &note.title, ext),
+    );
+
+    Ok((headers, output))
+}
+
+// ---------------------------------------------------------------------------
+// Bulk operations
+// ---------------------------------------------------------------------------
+
+/// Upper bound on the notes a single bulk request may touch.
+const MAX_BULK_NOTES: usize = 1000;
+
+/// Ids of every note matching `filters`, oldest first. Pagination is ignored.
+async fn filtered_note_ids(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    filters: &NoteFilters,
+) -> Result<Vec<Uuid>, AppError> {
+    let field_filters =
+        field_filters(&mut **tx, workspace_id, filters.fields.as_deref()).await?;
+    let geo = GeoFilter::from_filters(filters)?;
+    let (filter_joins, where_clause, _, param_idx) = note_filter_sql(filters, &field_filters, &geo);
+    let query_str = format!(
+        "SELECT n.id FROM notes n{}{} GROUP BY n.id ORDER BY n.created_at LIMIT ${}",
+        filter_joins, where_clause, param_idx
+    );
+
+    let mut q = sqlx::query_scalar::<_, Uuid>(&query_str).bind(workspace_id);
+    if let Some(ref ft_id) = filters.field_trip_id {
+        q = q.bind(ft_id);
+    }
+    if let Some(ref c_id) = filters.concept_id {
+        q = q.bind(c_id);
+    }
+    if let Some(ref e_id) = filters.entity_id {
+        q = q.bind(e_id);
+    }
+    if let Some(ref et) = filters.entity_type {
+        q = q.bind(et);
+    }
+    if let Some(ref nt) = filters.note_type {
+        q = q.bind(nt);
+    }
+    for f in &field_filters {
+        q = q.bind(&f.key).bind(&f.value);
+    }
+    for v in geo.values() {
+        q = match v {
+            GeoValue::Number(x) => q.bind(x),
+            GeoValue::Date(d) => q.bind(d),
+        };
+    }
+    // One past the cap, so an oversized selection is rejected rather than truncated.
+    let ids = q.bind(MAX_BULK_NOTES as i64 + 1).fetch_all(&mut **tx).await?;
+    Ok(ids)
+}
+
+/// Reject actions referring to field trips or concepts outside the workspace.
+async fn validate_bulk_action(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    action: &BulkNoteAction,
+) -> Result<(), AppError> {
+    match action {
+        BulkNoteAction::MoveToFieldTrip { field_trip_id } => {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM field_trips WHERE id = $1 AND workspace_id = $2)",
+            )
+            .bind(field_trip_id)
+            .bind(workspace_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            if !exists {
+                return Err(AppError::BadRequest("Field trip not found".to_string()));
+            }
+        }
+        BulkNoteAction::AddConcepts { concept_ids } => {
+            let found: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM concepts WHERE id = ANY($1) AND workspace_id = $2",
+            )
+            .bind(concept_ids)
+            .bind(workspace_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            let distinct: HashSet<&Uuid> = concept_ids.iter().collect();
+            if found as usize != distinct.len() {
+                return Err(AppError::BadRequest("Unknown concept id".to_string()));
+            }
+        }
+        BulkNoteAction::AddTags { tag_names } if tag_names.iter().any(|n| n.trim().is_empty()) => {
+            return Err(AppError::BadRequest("Tag names must not be empty".to_string()));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Apply `action` to notes already checked to be in the right trash state.
+/// Returns what a permanent delete purged, for cleanup after commit.
+async fn apply_bulk_action(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    auth: &AuthUser,
+    action: &BulkNoteAction,
+    ids: &[Uuid],
+) -> Result<trash::PurgedNotes, AppError> {
+    let touches_associations = match action {
+        BulkNoteAction::MoveToFieldTrip { field_trip_id } => {
+            sqlx::query("DELETE FROM note_field_trips WHERE note_id = ANY($1)")
+                .bind(ids)
+                .execute(&mut **tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO note_field_trips (note_id, field_trip_id) \
+                 SELECT note_id, $2 FROM UNNEST($1::uuid[]) AS note_id",
+            )
+            .bind(ids)
+            .bind(field_trip_id)
+            .execute(&mut **tx)
+            .await?;
+            true
+        }
+        BulkNoteAction::AddTags { tag_names } => {
+            let mut tag_ids = Vec::with_capacity(tag_names.len());
+            for name in tag_names {
+                let tag_id: Uuid = sqlx::query_scalar(
+                    "INSERT INTO tags (workspace_id, name) VALUES ($1, $2) \
+                     ON CONFLICT (workspace_id, name) DO UPDATE SET name = EXCLUDED.name \
+                     RETURNING id",
+                )
+                .bind(auth.workspace_id)
+                .bind(name.trim())
+                .fetch_one(&mut **tx)
+                .await?;
+                tag_ids.push(tag_id);
+            }
+            sqlx::query(
+                "INSERT INTO note_tags (note_id, tag_id) \
+                 SELECT n, t FROM UNNEST($1::uuid[]) AS n CROSS JOIN UNNEST($2::uuid[]) AS t \
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(ids)
+            .bind(&tag_ids)
+            .execute(&mut **tx)
+            .await?;
+            true
+        }
+        BulkNoteAction::RemoveTags { tag_names } => {
+            let names: Vec<&str> = tag_names.iter().map(|n| n.trim()).collect();
+            sqlx::query(
+                "DELETE FROM note_tags nt USING tags t \
+                 WHERE t.id = nt.tag_id AND t.workspace_id = $3 \
+                   AND nt.note_id = ANY($1) AND t.name = ANY($2)",
+            )
+            .bind(ids)
+            .bind(&names)
+            .bind(auth.workspace_id)
+            .execute(&mut **tx)
+            .await?;
+            true
+        }
+        BulkNoteAction::AddConcepts { concept_ids } => {
+            sqlx::query(
+                "INSERT INTO note_concepts (note_id, concept_id) \
+                 SELECT n, c FROM UNNEST($1::uuid[]) AS n CROSS JOIN UNNEST($2::uuid[]) AS c \
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(ids)
+            .bind(concept_ids)
+            .execute(&mut **tx)
+            .await?;
+            true
+        }
+        BulkNoteAction::RemoveConcepts { concept_ids } => {
+            sqlx::query("DELETE FROM note_concepts WHERE note_id = ANY($1) AND concept_id = ANY($2)")
+                .bind(ids)
+                .bind(concept_ids)
+                .execute(&mut **tx)
+                .await?;
+            true
+        }
+        BulkNoteAction::Star | BulkNoteAction::Unstar => {
+            let starred = matches!(action, BulkNoteAction::Star);
+            sqlx::query(
+                "UPDATE notes SET is_starred = $2, updated_at = now() \
+                 WHERE id = ANY($1) AND is_starred <> $2",
+            )
+            .bind(ids)
+            .bind(starred)
+            .execute(&mut **tx)
+            .await?;
+            // Keep the stars audit table in step, as toggle_star does.
+            if starred {
+                sqlx::query(
+                    "INSERT INTO stars (user_id, note_id) \
+                     SELECT $1, note_id FROM UNNEST($2::uuid[]) AS note_id ON CONFLICT DO NOTHING",
+                )
+                .bind(auth.user_id)
+                .bind(ids)
+                .execute(&mut **tx)
+                .await?;
+            } else {
+                sqlx::query("DELETE FROM stars WHERE user_id = $1 AND note_id = ANY($2)")
+                    .bind(auth.user_id)
+                    .bind(ids)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+            false
+        }
+        BulkNoteAction::Delete => {
+            sqlx::query("UPDATE notes SET deleted_at = now(), updated_at = now() WHERE id = ANY($1)")
+                .bind(ids)
+                .execute(&mut **tx)
+                .await?;
+            false
+        }
+        BulkNoteAction::Restore => {
+            sqlx::query("UPDATE notes SET deleted_at = NULL, merged_into_id = NULL, updated_at = now() WHERE id = ANY($1)")
+                .bind(ids)
+                .execute(&mut **tx)
+                .await?;
+            false
+        }
+        BulkNoteAction::PermanentDelete => {
+            return trash::purge_notes(tx, auth.workspace_id, ids).await;
+        }
+    };
+
+    if touches_associations {
+        sqlx::query("UPDATE notes SET updated_at = now() WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(trash::PurgedNotes::default())
+}
+
+/// `POST /api/v1/notes/bulk` — apply one action to many notes in a single
+/// transaction. Notes that are missing or in the wrong trash state are
+/// reported per id and left alone; graph edges are recomputed once at the end.
+async fn bulk_notes(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(req): Json<BulkNoteRequest>,
+) -> Result<Json<ApiResponse<BulkNoteReport>>, AppError> {
+    let in_trash = req.action.targets_trash();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let ids = match (req.ids, req.filters) {
+        (Some(ids), None) => {
+            let mut seen = HashSet::new();
+            ids.into_iter().filter(|id| seen.insert(*id)).collect()
+        }
+        (None, Some(mut filters)) => {
+            filters.deleted.get_or_insert(in_trash);
+            filtered_note_ids(&mut tx, auth.workspace_id, &filters).await?
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "Provide exactly one of ids or filters".to_string(),
+            ))
+        }
+    };
+    if ids.len() > MAX_BULK_NOTES {
+        return Err(AppError::BadRequest(format!(
+            "A bulk request may target at most {MAX_BULK_NOTES} notes"
+        )));
+    }
+
+    validate_bulk_action(&mut tx, auth.workspace_id, &req.action).await?;
+
+    // Lock the targets so their trash state cannot change under us.
+    let trashed: HashMap<Uuid, bool> = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT id, deleted_at IS NOT NULL FROM notes \
+         WHERE workspace_id = $1 AND id = ANY($2) FOR UPDATE",
+    )
+    .bind(auth.workspace_id)
+    .bind(&ids)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut eligible = Vec::with_capacity(ids.len());
+    let mut results = Vec::with_capacity(ids.len());
+    for id in ids {
+        let (status, reason) = match trashed.get(&id) {
+            None => (BulkNoteStatus::NotFound, None),
+            Some(&true) if !in_trash => (BulkNoteStatus::Skipped, Some("Note is in the trash")),
+            Some(&false) if in_trash => (BulkNoteStatus::Skipped, Some("Note is not in the trash")),
+            Some(_) => {
+                eligible.push(id);
+                (BulkNoteStatus::Ok, None)
+            }
+        };
+        results.push(BulkNoteResult {
+            id,
+            status,
+            reason: reason.map(str::to_string),
+        });
+    }
+
+    let mut purged = trash::PurgedNotes::default();
+    if !eligible.is_empty() {
+        purged = apply_bulk_action(&mut tx, &auth, &req.action, &eligible).await?;
+        recompute_graph_edges(&mut tx, auth.workspace_id).await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    release_purged(&pool, &auth, &purged).await;
+
+    Ok(ApiResponse::ok(BulkNoteReport {
+        succeeded: eligible.len(),
+        failed: results.len() - eligible.len(),
+        results,
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// Duplicate and merge
+// ---------------------------------------------------------------------------
+
+/// Most notes accepted by one merge.
+const MAX_MERGE_NOTES: usize = 50;
+
+/// Storage key for a copy of a local media file named `name` (plus the
+/// original extension), or `None` for a remote URL that is shared as-is.
+fn copied_media_key(key: &str, workspace_id: Uuid, name: &str) -> Option<String> {
+    if key.starts_with("http://") || key.starts_with("https://") {
+        return None;
+    }
+    let ext = key.rsplit_once('.').map(|(_, e)| e).unwrap_or("bin");
+    Some(format!("{workspace_id}/{name}.{ext}"))
+}
+
+async fn remove_written(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+}
+
+/// Copy a note's media rows and files onto `note_id` under the new ids in
+/// `ids`. Files written so far are recorded in `written` even on error.
+/// Returns the bytes copied.
+async fn copy_media(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    note_id: Uuid,
+    media: &[(Uuid, String, Option<String>, Option<i64>)],
+    ids: &HashMap<Uuid, Uuid>,
+    written: &mut Vec<PathBuf>,
+) -> Result<i64, AppError> {
+    let mut bytes = 0;
+    for (old_id, key, thumbnail, size) in media {
+        let new_id = ids[old_id];
+        let mut keys = Vec::with_capacity(2);
+        for (key, name) in [(Some(key), new_id.to_string()), (thumbnail.as_ref(), format!("{new_id}_thumb"))] {
+            let Some(key) = key else {
+                keys.push(None);
+                continue;
+            };
+            match copied_media_key(key, workspace_id, &name) {
+                Some(new_key) => {
+                    let to = PathBuf::from(UPLOADS_DIR).join(&new_key);
+                    tokio::fs::copy(PathBuf::from(UPLOADS_DIR).join(key), &to)
+                        .await
+                        .map_err(|e| AppError::Internal(format!("Failed to copy media file: {e}")))?;
+                    written.push(to);
+                    keys.push(Some(new_key));
+                }
+                None => keys.push(Some(key.clone())),
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO media (id, note_id, media_type, s3_key, original_filename, mime_type, \
+             file_size_bytes, duration_seconds, thumbnail_s3_key, label, transcription_status, \
+             transcription_text, transcription_segments, sort_order) \
+             SELECT $1, $2, media_type, $3, original_filename, mime_type, file_size_bytes, \
+             duration_seconds, $4, label, transcription_status, transcription_text, \
+             transcription_segments, sort_order \
+             FROM media WHERE id = $5",
+        )
+        .bind(new_id)
+        .bind(note_id)
+        .bind(&keys[0])
+        .bind(&keys[1])
+        .bind(old_id)
+        .execute(&mut **tx)
+        .await?;
+        bytes += size.unwrap_or(0);
+    }
+    Ok(bytes)
+}
+
+/// Copy a note with its field trips, tags, concepts, entities and outgoing
+/// note links; with `include_media`, its media files are copied too and the
+/// new body points at the copies. Revisions, stars and backlinks stay with
+/// the original.
+async fn duplicate_note(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Extension(embedder): Extension<EmbeddingProvider>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<DuplicateNote>>,
+) -> Result<Json<ApiResponse<Note>>, AppError> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+
+    plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "notes").await?;
+    if req.include_media {
+        plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "media_uploads").await?;
+        plan_guard::check_limit(&pool, auth.user_id, auth.workspace_id, "storage_bytes").await?;
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let source = lock_note(&mut tx, auth.workspace_id, id).await?;
+
+    let media: Vec<(Uuid, String, Option<String>, Option<i64>)> = if req.include_media {
+        sqlx::query_as(
+            "SELECT id, s3_key, thumbnail_s3_key, file_size_bytes FROM media \
+             WHERE note_id = $1 ORDER BY sort_order, created_at",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?
+    } else {
+        Vec::new()
+    };
+    let media_ids: HashMap<Uuid, Uuid> = media.iter().map(|m| (m.0, Uuid::new_v4())).collect();
+    let mut body = source.body.clone();
+    tiptap::remap_ids(&mut body, &media_ids);
+
+    let title = match req.title.as_deref().map(str::trim) {
+        Some(t) if !t.is_empty() => t.to_string(),
+        _ => format!("{} (copy)", source.title),
+    };
+
+    let note = sqlx::query_as::<_, Note>(&format!(
+        "INSERT INTO notes (workspace_id, title, body, body_text, note_type, \
+         location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
+         time_start, time_end, custom_fields) \
+         SELECT workspace_id, $2, $3, body_text, note_type, \
+         location_name, location_lat, location_lng, gps_coords, weather, temperature_c, \
+         time_start, time_end, custom_fields \
+         FROM notes WHERE id = $1 \
+         RETURNING {NOTE_COLS}"
+    ))
+    .bind(id)
+    .bind(&title)
+    .bind(&body)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for sql in [
+        "INSERT INTO note_field_trips (note_id, field_trip_id) \
+         SELECT $2, field_trip_id FROM note_field_trips WHERE note_id = $1",
+        "INSERT INTO note_tags (note_id, tag_id) SELECT $2, tag_id FROM note_tags WHERE note_id = $1",
+        "INSERT INTO note_concepts (note_id, concept_id) \
+         SELECT $2, concept_id FROM note_concepts WHERE note_id = $1",
+        "INSERT INTO note_entities (note_id, entity_id, mention_count, first_mention_pos) \
+         SELECT $2, entity_id, mention_count, first_mention_pos FROM note_entities WHERE note_id = $1",
+        "INSERT INTO note_links (source_note_id, target_note_id, link_count) \
+         SELECT $2, target_note_id, link_count FROM note_links WHERE source_note_id = $1",
+    ] {
+        sqlx::query(sql).bind(id).bind(note.id).execute(&mut *tx).await?;
+    }
+
+    let mut written = Vec::new();
+    let copied = copy_media(&mut tx, auth.workspace_id, note.id, &media, &media_ids, &mut written).await;
+    let bytes = match copied {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            remove_written(&written).await;
+            return Err(e);
+        }
+    };
+
+    let committed = async {
+        recompute_graph_edges(&mut tx, auth.workspace_id).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+    .await;
+    if let Err(e) = committed {
+        remove_written(&written).await;
+        return Err(e);
+    }
+
+    // Increment usage counters (best-effort)
+    let _ = plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "notes_count", 1).await;
+    if !media.is_empty() {
+        let count = media.len() as i64;
+        let _ = plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "media_uploads", count).await;
+        let _ = plan_guard::increment_usage(&pool, auth.user_id, auth.workspace_id, "storage_bytes", bytes).await;
+    }
+
+    embeddings::spawn_embed_note(pool, embedder, note.id);
+
+    Ok(ApiResponse::ok(note))
+}
+
+/// Concatenate Tiptap documents in order, separated by horizontal rules.
+fn concat_bodies<'a>(docs: impl IntoIterator<Item = &'a serde_json::Value>) -> serde_json::Value {
+    let mut content = Vec::new();
+    for doc in docs {
+        let Some(blocks) = doc.get("content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+        if !content.is_empty() {
+            content.push(serde_json::json!({ "type": "horizontalRule" }));
+        }
+        content.extend(blocks.iter().cloned());
+    }
+    serde_json::json!({ "type": "doc", "content": content })
+}
+
+/// Merge notes into the first one: bodies are appended in the given order,
+/// every link table (field trips, tags, concepts, entities, note links in
+/// both directions, stars) is unioned onto it and media is moved over.
+/// Custom field values missing on the first note are taken from the others
+/// and the time span widened to cover all of them. The other notes go to the
+/// trash with `merged_into_id` set, and keep their own links so restoring
+/// one gives back everything but its media.
+async fn merge_notes(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Extension(embedder): Extension<EmbeddingProvider>,
+    Json(req): Json<MergeNotes>,
+) -> Result<([(header::HeaderName, String); 1], Json<ApiResponse<Note>>), AppError> {
+    let mut ids: Vec<Uuid> = Vec::with_capacity(req.note_ids.len());
+    for id in req.note_ids {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.len() < 2 {
+        return Err(AppError::BadRequest(
+            "Provide at least two distinct note_ids to merge".to_string(),
+        ));
+    }
+    if ids.len() > MAX_MERGE_NOTES {
+        return Err(AppError::BadRequest(format!(
+            "At most {MAX_MERGE_NOTES} notes can be merged at once"
+        )));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Lock in id order so concurrent merges cannot deadlock.
+    let mut locked = sqlx::query_as::<_, Note>(&format!(
+        "SELECT {NOTE_COLS} FROM notes \
+         WHERE id = ANY($1) AND workspace_id = $2 AND deleted_at IS NULL \
+         ORDER BY id FOR UPDATE"
+    ))
+    .bind(&ids)
+    .bind(auth.workspace_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut notes = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let pos = locked
+            .iter()
+            .position(|n| n.id == *id)
+            .ok_or_else(|| AppError::NotFound(format!("Note not found: {id}")))?;
+        notes.push(locked.swap_remove(pos));
+    }
+    let target = &notes[0];
+    let target_id = target.id;
+    let source_ids = &ids[1..];
+
+    record_revision(&mut tx, auth.user_id, target).await?;
+
+    let title = match req.title.as_deref().map(str::trim) {
+        Some(t) if !t.is_empty() => t.to_string(),
+        _ => target.title.clone(),
+    };
+    let body = concat_bodies(notes.iter().map(|n| &n.body));
+    let body_text = notes
+        .iter()
+        .map(|n| n.body_text.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let mut custom_fields = target.custom_fields.as_object().cloned().unwrap_or_default();
+    for note in &notes[1..] {
+        for (key, value) in note.custom_fields.as_object().into_iter().flatten() {
+            custom_fields.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    let time_start = notes.iter().filter_map(|n| n.time_start).min();
+    let time_end = notes.iter().filter_map(|n| n.time_end).max();
+
+    let note = sqlx::query_as::<_, Note>(&format!(
+        "UPDATE notes SET \
+         title = $2, body = $3, body_text = $4, custom_fields = $5, \
+         time_start = $6, time_end = $7, \
+         is_starred = is_starred OR EXISTS(SELECT 1 FROM notes WHERE id = ANY($8) AND is_starred), \
+         version = version + 1, \
+         updated_at = now() \
+         WHERE id = $1 \
+         RETURNING {NOTE_COLS}"
+    ))
+    .bind(target_id)
+    .bind(&title)
+    .bind(&body)
+    .bind(&body_text)
+    .bind(serde_json::Value::Object(custom_fields))
+    .bind(time_start)
+    .bind(time_end)
+    .bind(source_ids)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for sql in [
+        "INSERT INTO note_field_trips (note_id, field_trip_id) \
+         SELECT DISTINCT $1, field_trip_id FROM note_field_trips WHERE note_id = ANY($2) \
+         ON CONFLICT DO NOTHING",
+        "INSERT INTO note_tags (note_id, tag_id) \
+         SELECT DISTINCT $1, tag_id FROM note_tags WHERE note_id = ANY($2) \
+         ON CONFLICT DO NOTHING",
+        "INSERT INTO note_concepts (note_id, concept_id) \
+         SELECT DISTINCT $1, concept_id FROM note_concepts WHERE note_id = ANY($2) \
+         ON CONFLICT DO NOTHING",
+        "INSERT INTO note_entities (note_id, entity_id, mention_count) \
+         SELECT $1, entity_id, SUM(mention_count) FROM note_entities WHERE note_id = ANY($2) \
+         GROUP BY entity_id \
+         ON CONFLICT (note_id, entity_id) \
+         DO UPDATE SET mention_count = note_entities.mention_count + EXCLUDED.mention_count",
+        "INSERT INTO stars (user_id, note_id) \
+         SELECT DISTINCT user_id, $1 FROM stars WHERE note_id = ANY($2) \
+         ON CONFLICT DO NOTHING",
+        // Links between the merged notes would become self-links; drop them.
+        "DELETE FROM note_links WHERE source_note_id = $1 AND target_note_id = ANY($2)",
+        "INSERT INTO note_links (source_note_id, target_note_id, link_count) \
+         SELECT $1, target_note_id, SUM(link_count) FROM note_links \
+         WHERE source_note_id = ANY($2) AND target_note_id <> $1 AND NOT target_note_id = ANY($2) \
+         GROUP BY target_note_id \
+         ON CONFLICT (source_note_id, target_note_id) \
+         DO UPDATE SET link_count = note_links.link_count + EXCLUDED.link_count",
+        "INSERT INTO note_links (source_note_id, target_note_id, link_count) \
+         SELECT source_note_id, $1, SUM(link_count) FROM note_links \
+         WHERE target_note_id = ANY($2) AND source_note_id <> $1 AND NOT source_note_id = ANY($2) \
+         GROUP BY source_note_id \
+         ON CONFLICT (source_note_id, target_note_id) \
+         DO UPDATE SET link_count = note_links.link_count + EXCLUDED.link_count",
+        // Media follows the merged notes' order, after the target's own.
+        "WITH base AS (SELECT COALESCE(MAX(sort_order), -1) AS n FROM media WHERE note_id = $1), \
+         moved AS ( \
+             SELECT id, ROW_NUMBER() OVER ( \
+                 ORDER BY array_position($2, note_id), sort_order, created_at \
+             ) AS rn \
+             FROM media WHERE note_id = ANY($2) \
+         ) \
+         UPDATE media m SET note_id = $1, sort_order = (SELECT n FROM base) + moved.rn \
+         FROM moved WHERE m.id = moved.id",
+        // Notes previously merged into a source now point at the target.
+        "UPDATE notes SET merged_into_id = $1 WHERE merged_into_id = ANY($2)",
+        "UPDATE notes SET deleted_at = now(), merged_into_id = $1, updated_at = now() \
+         WHERE id = ANY($2)",
+    ] {
+        sqlx::query(sql)
+            .bind(target_id)
+            .bind(source_ids)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    recompute_graph_edges(&mut tx, auth.workspace_id).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    embeddings::spawn_embed_note(pool, embedder, note.id);
+
+    Ok((etag_header(&note), ApiResponse::ok(note)))
+}
+
+// ---------------------------------------------------------------------------
+// Revision history
+// ---------------------------------------------------------------------------
+
+async fn fetch_revision<'e, E>(executor: E, note_id: Uuid, rev: i32) -> Result<NoteRevision, AppError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query_as::<_, NoteRevision>(
+        "SELECT id, note_id, revision_number, title, body, body_text, created_by, created_at \
+         FROM note_revisions WHERE note_id = $1 AND revision_number = $2",
+    )
+    .bind(note_id)
+    .bind(rev)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Revision {rev} not found")))
+}
+
+async fn list_revisions(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<ApiResponse<Vec<NoteRevisionSummary>>>, AppError> {
+    ensure_note_exists(&pool, auth.workspace_id, id).await?;
+
+    let revisions = sqlx::query_as::<_, NoteRevisionSummary>(
+        "SELECT id, revision_number, title, length(body_text) AS body_text_length, \
+         created_by, created_at \
+         FROM note_revisions WHERE note_id = $1 \
+         ORDER BY revision_number DESC \
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(id)
+    .bind(pagination.per_page())
+    .bind(pagination.offset())
+    .fetch_all(&pool)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM note_revisions WHERE note_id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+
+    Ok(ApiResponse::list(
+        revisions,
+        total,
+        pagination.page(),
+        pagination.per_page(),
+    ))
+}
+
+async fn get_revision(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path((id, rev)): Path<(Uuid, i32)>,
+) -> Result<Json<ApiResponse<NoteRevision>>, AppError> {
+    ensure_note_exists(&pool, auth.workspace_id, id).await?;
+    let revision = fetch_revision(&pool, id, rev).await?;
+    Ok(ApiResponse::ok(revision))
+}
+
+async fn diff_revisions(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<RevisionDiffQuery>,
+) -> Result<Json<ApiResponse<RevisionDiff>>, AppError> {
+    let current = sqlx::query_as::<_, Note>(&format!(
+        "SELECT {NOTE_COLS} FROM notes WHERE id = $1 AND workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Note not found".to_string()))?;
+
+    let from = fetch_revision(&pool, id, params.from).await?;
+    let (to_title, to_body) = match params.to {
+        Some(rev) => {
+            let to = fetch_revision(&pool, id, rev).await?;
+            (to.title, to.body)
+        }
+        None => (current.title, current.body),
+    };
+
+    Ok(ApiResponse::ok(RevisionDiff {
+        from: params.from,
+        to: params.to,
+        title_changed: from.title != to_title,
+        changes: diff_documents(&from.body, &to_body),
+        from_title: from.title,
+        to_title,
+    }))
+}
+
+async fn restore_revision(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Extension(embedder): Extension<EmbeddingProvider>,
+    Path((id, rev)): Path<(Uuid, i32)>,
+) -> Result<([(header::HeaderName, String); 1], Json<ApiResponse<Note>>), AppError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let current = lock_note(&mut tx, auth.workspace_id, id).await?;
+    let revision = fetch_revision(&mut *tx, id, rev).await?;
+
+    // Restoring is itself an edit: keep the content being replaced so the
+    // restore can be undone.
+    if current.title != revision.title
+        || current.body != revision.body
+        || current.body_text != revision.body_text
+    {
+        record_revision(&mut tx, auth.user_id, &current).await?;
+    }
+
+    let note = sqlx::query_as::<_, Note>(&format!(
+        "UPDATE notes SET title = $3, body = $4, body_text = $5, \
+         version = version + 1, updated_at = now() \
+         WHERE id = $1 AND workspace_id = $2 \
+         RETURNING {NOTE_COLS}"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .bind(&revision.title)
+    .bind(&revision.body)
+    .bind(&revision.body_text)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Entity/concept links and graph edges must match the restored body.
+    sync_note_links(&mut tx, auth.workspace_id, id, &revision.body).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    embeddings::spawn_embed_note(pool, embedder, id);
+
+    Ok((etag_header(&note), ApiResponse::ok(note)))
+}
+
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/api/v1/notes", get(list_notes).post(create_note))
+        .route("/api/v1/notes/bulk", post(bulk_notes))
+        .route("/api/v1/notes/merge", post(merge_notes))
         .route(
             "/api/v1/notes/{id}",
             get(get_note).put(update_note).delete(delete_note),
         )
         .route("/api/v1/notes/{id}/star", post(toggle_star))
+        .route("/api/v1/notes/{id}/duplicate", post(duplicate_note))
         .route("/api/v1/notes/{id}/connections", get(note_connections))
         .route("/api/v1/notes/{id}/entities", get(note_entities_list))
+        .route("/api/v1/notes/{id}/backlinks", get(note_backlinks))
+        .route("/api/v1/notes/{id}/export", get(export_note))
         .route("/api/v1/notes/{id}/restore", post(restore_note))
+        .route("/api/v1/notes/{id}/revisions", get(list_revisions))
+        .route("/api/v1/notes/{id}/revisions/diff", get(diff_revisions))
+        .route("/api/v1/notes/{id}/revisions/{rev}", get(get_revision))
+        .route(
+            "/api/v1/notes/{id}/revisions/{rev}/restore",
+            post(restore_revision),
+        )
         .route(
             "/api/v1/notes/{id}/permanent",
             axum::routing::delete(permanent_delete_note),
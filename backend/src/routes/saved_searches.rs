@@ -0,0 +1,319 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::custom_field::FieldFilter;
+use crate::models::note::NoteFilters;
+use crate::models::saved_search::*;
+use crate::response::{ApiResponse, PaginationParams};
+use crate::routes::custom_fields::{field_filters, stored_field_filters};
+use crate::routes::note_types::resolve_type;
+use crate::routes::search::{count_matches, mark_seen, run_search, SearchResults, SearchSpec};
+use crate::search::geo::GeoFilter;
+use crate::search::query::{self, Node};
+
+const SAVED_SEARCH_COLS: &str = "id, workspace_id, name, icon, query, filters, pinned, \
+     last_viewed_at, seen_note_ids, created_by, created_at, updated_at";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route(
+            "/api/v1/saved-searches",
+            get(list_saved_searches).post(create_saved_search),
+        )
+        .route(
+            "/api/v1/saved-searches/{id}",
+            get(get_saved_search)
+                .put(update_saved_search)
+                .delete(delete_saved_search),
+        )
+        .route("/api/v1/saved-searches/{id}/results", get(saved_search_results))
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Parse a saved query; a blank one matches every note.
+fn parse_query(q: &str) -> Result<Node, AppError> {
+    if q.trim().is_empty() {
+        return Ok(Node::And(Vec::new()));
+    }
+    Ok(query::parse(q)?)
+}
+
+/// Validate the filtering fields of `filters` and return them as stored,
+/// without paging, sorting, trash or unset fields.
+async fn stored_filters(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    filters: NoteFilters,
+) -> Result<serde_json::Value, AppError> {
+    if let Some(key) = &filters.note_type {
+        resolve_type(pool, workspace_id, key).await?;
+    }
+    field_filters(pool, workspace_id, filters.fields.as_deref()).await?;
+    GeoFilter::from_filters(&filters)?;
+
+    let kept = NoteFilters {
+        note_type: filters.note_type,
+        field_trip_id: filters.field_trip_id,
+        concept_id: filters.concept_id,
+        entity_id: filters.entity_id,
+        entity_type: filters.entity_type,
+        starred: filters.starred,
+        fields: filters.fields.filter(|f| !f.trim().is_empty()),
+        near: filters.near,
+        km: filters.km,
+        bbox: filters.bbox,
+        from: filters.from,
+        to: filters.to,
+        ..NoteFilters::default()
+    };
+    let mut value = serde_json::to_value(kept).map_err(|e| AppError::Internal(e.to_string()))?;
+    if let Some(map) = value.as_object_mut() {
+        map.retain(|_, v| !v.is_null());
+    }
+    Ok(value)
+}
+
+/// The parsed query, note filters, custom field filters and place and time
+/// filters of a saved search.
+async fn search_parts(
+    pool: &PgPool,
+    saved: &SavedSearch,
+) -> Result<(Node, NoteFilters, Vec<FieldFilter>, GeoFilter), AppError> {
+    let node = parse_query(&saved.query)?;
+    let filters: NoteFilters = serde_json::from_value(saved.filters.clone())
+        .map_err(|e| AppError::Internal(format!("Invalid saved filters: {e}")))?;
+    // Custom fields may have been removed or retyped since the search was saved.
+    let fields = stored_field_filters(pool, saved.workspace_id, filters.fields.as_deref()).await?;
+    let geo = GeoFilter::from_filters(&filters)?;
+    Ok((node, filters, fields, geo))
+}
+
+/// All notes matching a saved search, and those new since it was viewed.
+async fn counts(pool: &PgPool, saved: &SavedSearch) -> Result<(i64, i64), AppError> {
+    let (node, filters, fields, geo) = search_parts(pool, saved).await?;
+    let spec = SearchSpec {
+        node: &node,
+        filters: &filters,
+        field_filters: &fields,
+        geo: &geo,
+        fuzzy: true,
+    };
+    count_matches(
+        pool,
+        saved.workspace_id,
+        &spec,
+        saved.seen_note_ids.as_deref(),
+        saved.last_viewed_at,
+    )
+    .await
+}
+
+async fn with_counts(pool: &PgPool, saved: SavedSearch) -> Result<SavedSearchWithCounts, AppError> {
+    let (match_count, new_match_count) = counts(pool, &saved).await?;
+    Ok(SavedSearchWithCounts {
+        search: saved,
+        match_count,
+        new_match_count,
+        error: None,
+    })
+}
+
+async fn fetch_saved_search(pool: &PgPool, workspace_id: Uuid, id: Uuid) -> Result<SavedSearch, AppError> {
+    sqlx::query_as::<_, SavedSearch>(&format!(
+        "SELECT {SAVED_SEARCH_COLS} FROM saved_searches WHERE id = $1 AND workspace_id = $2"
+    ))
+    .bind(id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// CRUD
+// ---------------------------------------------------------------------------
+
+/// All saved searches with their match counts; `?pinned=true` for the
+/// sidebar.
+async fn list_saved_searches(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Query(params): Query<SavedSearchFilters>,
+) -> Result<Json<ApiResponse<Vec<SavedSearchWithCounts>>>, AppError> {
+    let searches = sqlx::query_as::<_, SavedSearch>(&format!(
+        "SELECT {SAVED_SEARCH_COLS} FROM saved_searches \
+         WHERE workspace_id = $1 AND ($2::boolean IS NULL OR pinned = $2) \
+         ORDER BY name ASC, id"
+    ))
+    .bind(auth.workspace_id)
+    .bind(params.pinned)
+    .fetch_all(&pool)
+    .await?;
+
+    // One broken search must not hide the others from the sidebar.
+    let mut results = Vec::with_capacity(searches.len());
+    for saved in searches {
+        let ((match_count, new_match_count), error) = match counts(&pool, &saved).await {
+            Ok(found) => (found, None),
+            Err(e) => {
+                tracing::warn!("Failed to count matches of saved search {}: {}", saved.id, e);
+                ((0, 0), Some(e.to_string()))
+            }
+        };
+        results.push(SavedSearchWithCounts {
+            search: saved,
+            match_count,
+            new_match_count,
+            error,
+        });
+    }
+
+    let total = results.len() as i64;
+    Ok(ApiResponse::list(results, total, 1, total.max(1)))
+}
+
+async fn get_saved_search(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<SavedSearchWithCounts>>, AppError> {
+    let saved = fetch_saved_search(&pool, auth.workspace_id, id).await?;
+    Ok(ApiResponse::ok(with_counts(&pool, saved).await?))
+}
+
+async fn create_saved_search(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(body): Json<CreateSavedSearch>,
+) -> Result<Json<ApiResponse<SavedSearchWithCounts>>, AppError> {
+    if body.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Name is required".to_string()));
+    }
+    parse_query(&body.query)?;
+    let filters = stored_filters(&pool, auth.workspace_id, body.filters).await?;
+    let icon = body.icon.unwrap_or_else(|| "🔍".to_string());
+
+    let saved = sqlx::query_as::<_, SavedSearch>(&format!(
+        "INSERT INTO saved_searches (workspace_id, name, icon, query, filters, pinned, created_by) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+         RETURNING {SAVED_SEARCH_COLS}"
+    ))
+    .bind(auth.workspace_id)
+    .bind(body.name.trim())
+    .bind(&icon)
+    .bind(body.query.trim())
+    .bind(&filters)
+    .bind(body.pinned)
+    .bind(auth.user_id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(ApiResponse::ok(with_counts(&pool, saved).await?))
+}
+
+async fn update_saved_search(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateSavedSearch>,
+) -> Result<Json<ApiResponse<SavedSearchWithCounts>>, AppError> {
+    if body.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::BadRequest("Name is required".to_string()));
+    }
+    if let Some(q) = &body.query {
+        parse_query(q)?;
+    }
+    let filters = match body.filters {
+        Some(filters) => Some(stored_filters(&pool, auth.workspace_id, filters).await?),
+        None => None,
+    };
+
+    let saved = sqlx::query_as::<_, SavedSearch>(&format!(
+        "UPDATE saved_searches SET \
+         name = COALESCE($3, name), \
+         icon = COALESCE($4, icon), \
+         query = COALESCE($5, query), \
+         filters = COALESCE($6, filters), \
+         pinned = COALESCE($7, pinned), \
+         updated_at = now() \
+         WHERE id = $1 AND workspace_id = $2 \
+         RETURNING {SAVED_SEARCH_COLS}"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .bind(body.name.as_deref().map(str::trim))
+    .bind(&body.icon)
+    .bind(body.query.as_deref().map(str::trim))
+    .bind(&filters)
+    .bind(body.pinned)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+    Ok(ApiResponse::ok(with_counts(&pool, saved).await?))
+}
+
+async fn delete_saved_search(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let result = sqlx::query("DELETE FROM saved_searches WHERE id = $1 AND workspace_id = $2")
+        .bind(id)
+        .bind(auth.workspace_id)
+        .execute(&pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Saved search not found".to_string()));
+    }
+
+    Ok(ApiResponse::ok(serde_json::json!({ "deleted": true })))
+}
+
+// ---------------------------------------------------------------------------
+// Results
+// ---------------------------------------------------------------------------
+
+/// Re-run a saved search, in the shape of `GET /api/v1/search`. Viewing the
+/// first page marks its current matches as seen.
+async fn saved_search_results(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<SavedSearchPage>,
+) -> Result<Json<ApiResponse<SearchResults>>, AppError> {
+    let saved = fetch_saved_search(&pool, auth.workspace_id, id).await?;
+    let (node, filters, fields, geo) = search_parts(&pool, &saved).await?;
+    let spec = SearchSpec {
+        node: &node,
+        filters: &filters,
+        field_filters: &fields,
+        geo: &geo,
+        fuzzy: params.fuzzy.unwrap_or(true),
+    };
+    let first_page = params.cursor.is_none() && params.page.unwrap_or(1) <= 1;
+    let pagination = PaginationParams {
+        page: params.page,
+        per_page: params.per_page,
+        cursor: params.cursor,
+    };
+    let results = run_search(&pool, auth.workspace_id, &spec, &pagination).await?;
+
+    if first_page {
+        // Seen with the same matching as the counts.
+        mark_seen(&pool, id, auth.workspace_id, &SearchSpec { fuzzy: true, ..spec }).await?;
+    }
+
+    Ok(results)
+}
@@ -1,47 +1,218 @@
 use axum::{
     extract::{Query, State},
     routing::get,
-    Json, Router,
+    Extension, Json, Router,
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::auth::middleware::AuthUser;
+use crate::embeddings::{Embedder, EmbeddingProvider};
 use crate::error::AppError;
-use crate::response::ApiResponse;
+use crate::response::{ApiResponse, Cursor, PaginationParams};
+use crate::models::custom_field::FieldFilter;
+use crate::models::note::NoteFilters;
+use crate::search::geo::GeoFilter;
+use crate::search::query::{self, Node};
+use crate::search::sql::{
+    escape_like, fuzzy_words, name_terms, push_condition, push_note_filters, rank_tsquery,
+    without_stop_words, SearchTarget, FUZZY_THRESHOLD, MEDIA_TEXT, NOTE_TEXT,
+};
+use crate::tiptap::render::escape_html;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchParams {
+    /// In the query language of `crate::search::query`.
     pub q: String,
-    #[allow(dead_code)]
     pub page: Option<i64>,
-    #[allow(dead_code)]
     pub per_page: Option<i64>,
+    /// `next_cursor` from a previous page of note results.
+    pub cursor: Option<String>,
+    /// Also match words and names spelled similarly; on by default.
+    pub fuzzy: Option<bool>,
+    /// Place and time filters on notes (and the notes of media); see
+    /// `crate::search::geo`.
+    pub near: Option<String>,
+    pub km: Option<f64>,
+    pub bbox: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
 }
 
+/// Notes are counted in `meta.total` and can be paged by cursor; media,
+/// entities and concepts are paged by `page`/`per_page` only and carry their
+/// own totals.
 #[derive(Debug, Serialize)]
 pub struct SearchResults {
     pub notes: Vec<NoteSearchResult>,
+    pub media: Vec<MediaSearchResult>,
+    pub media_total: i64,
     pub entities: Vec<EntitySearchResult>,
+    pub entities_total: i64,
     pub concepts: Vec<ConceptSearchResult>,
+    pub concepts_total: i64,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize)]
 pub struct NoteSearchResult {
     pub id: Uuid,
     pub title: String,
     pub note_type: String,
+    pub note_type_name: String,
+    pub note_type_icon: Option<String>,
+    /// Plain-text fragments of the body around the matched terms.
+    pub excerpt: String,
+    /// `excerpt` as escaped HTML with matched terms wrapped in `<mark>`.
+    pub snippet: String,
+    /// Every exact match in the title and body text, in document order.
+    pub matches: Vec<MatchSpan>,
+    /// Average similarity (0–1) of the query's words to the note's text,
+    /// ignoring case and accents. Included in `rank`.
+    pub similarity: f32,
+    pub rank: f32,
+    /// Kilometres from the `near` point, if given.
+    pub distance_km: Option<f64>,
+}
+
+/// A matched term, as a half-open range of UTF-16 code units (JavaScript
+/// string indices) into the note's `title` or `body_text`.
+#[derive(Debug, Serialize)]
+pub struct MatchSpan {
+    /// `title` or `body_text`
+    pub field: &'static str,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(sqlx::FromRow)]
+struct NoteSearchRow {
+    id: Uuid,
+    title: String,
+    note_type: String,
+    note_type_name: String,
+    note_type_icon: Option<String>,
+    similarity: f32,
+    rank: f32,
+    distance_km: Option<f64>,
+    /// `ts_headline` outputs, with matches between `MATCH_START` and `MATCH_END`.
+    snippet: String,
+    title_marked: String,
+    body_marked: String,
+}
+
+/// A media item whose filename, label or transcription matched.
+#[derive(Debug, Serialize)]
+pub struct MediaSearchResult {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub note_title: String,
+    pub media_type: String,
+    pub original_filename: Option<String>,
+    pub label: Option<String>,
+    pub duration_seconds: Option<f32>,
+    /// Plain-text fragments of the transcription around the matched terms.
     pub excerpt: String,
+    /// `excerpt` as escaped HTML with matched terms wrapped in `<mark>`.
+    pub snippet: String,
+    /// Timed transcript segments containing a match, for transcriptions
+    /// with timings.
+    pub transcript_matches: Vec<TranscriptMatch>,
+    pub similarity: f32,
     pub rank: f32,
 }
 
+/// A transcript segment containing a match; offsets are in seconds.
+#[derive(Debug, Serialize)]
+pub struct TranscriptMatch {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    /// `text` as escaped HTML with matched terms wrapped in `<mark>`.
+    pub snippet: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct MediaSearchRow {
+    id: Uuid,
+    note_id: Uuid,
+    note_title: String,
+    media_type: String,
+    original_filename: Option<String>,
+    label: Option<String>,
+    duration_seconds: Option<f32>,
+    similarity: f32,
+    rank: f32,
+    snippet: String,
+    segment_matches: Option<sqlx::types::Json<Vec<SegmentMatchRow>>>,
+}
+
+#[derive(Deserialize)]
+struct SegmentMatchRow {
+    start: f64,
+    end: f64,
+    /// `text` with matches between `MATCH_START` and `MATCH_END`.
+    marked: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SemanticSearchParams {
+    /// Free text; not parsed as a query.
+    pub q: String,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Lowest cosine similarity (-1–1) to include; defaults to 0.
+    pub min_score: Option<f32>,
+}
+
+/// A chunk of a note's text, ranked by similarity of meaning to the query.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SemanticSearchResult {
+    pub chunk_id: Uuid,
+    pub chunk_index: i32,
+    /// The chunk's words of the note's body text.
+    pub content: String,
+    /// Cosine similarity of the chunk to the query.
+    pub score: f32,
+    pub note_id: Uuid,
+    pub note_title: String,
+    pub note_type: String,
+    pub note_type_name: String,
+    pub note_type_icon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    /// Prefix of a name or title, or of one of its words.
+    pub q: String,
+    /// Comma-separated subset of `entity,concept,tag,note`; all by default.
+    pub kinds: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Suggestion {
+    /// `entity`, `concept`, `tag` or `note`
+    pub kind: String,
+    pub id: Uuid,
+    /// Name, or title for notes.
+    pub label: String,
+    /// Entity type, concept category or note type.
+    pub detail: Option<String>,
+    /// Live notes mentioning the entity or concept or carrying the tag, or
+    /// linking to the note.
+    pub usage_count: i64,
+}
+
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct EntitySearchResult {
     pub id: Uuid,
     pub name: String,
     pub entity_type: String,
     pub role: Option<String>,
+    /// 1 when a term occurs in the name, otherwise its best fuzzy similarity.
+    pub similarity: f32,
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -49,14 +220,206 @@ pub struct ConceptSearchResult {
     pub id: Uuid,
     pub name: String,
     pub category: Option<String>,
+    /// 1 when a term occurs in the name, otherwise its best fuzzy similarity.
+    pub similarity: f32,
+}
+
+/// Delimiters `ts_headline` puts around matched terms. Control characters
+/// cannot occur in titles or body text typed by users.
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+/// `ts_headline` options: a few short fragments for the snippet, or the
+/// whole text for locating every match.
+const SNIPPET_OPTIONS: &str = "'StartSel=' || chr(2) || ', StopSel=' || chr(3) || \
+     ', MaxFragments=2, MinWords=8, MaxWords=24, FragmentDelimiter=\" … \"'";
+const HIGHLIGHT_ALL_OPTIONS: &str =
+    "'StartSel=' || chr(2) || ', StopSel=' || chr(3) || ', HighlightAll=true'";
+
+/// Strip match delimiters from `ts_headline` output, returning the plain
+/// text and the UTF-16 ranges that were delimited.
+fn match_spans(marked: &str) -> (String, Vec<(usize, usize)>) {
+    let mut plain = String::with_capacity(marked.len());
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    let mut start = None;
+    for c in marked.chars() {
+        match c {
+            MATCH_START => start = Some(pos),
+            MATCH_END => {
+                if let Some(start) = start.take() {
+                    spans.push((start, pos));
+                }
+            }
+            _ => {
+                plain.push(c);
+                pos += c.len_utf16();
+            }
+        }
+    }
+    (plain, spans)
 }
 
-/// Escape special ILIKE wildcard characters so user input is treated as a
-/// literal substring, not a pattern. Escapes `\`, `%`, and `_`.
-fn escape_like(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('%', "\\%")
-        .replace('_', "\\_")
+/// Escape `ts_headline` output for HTML, turning match delimiters into `<mark>`.
+fn snippet_html(marked: &str) -> String {
+    escape_html(marked)
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+impl From<NoteSearchRow> for NoteSearchResult {
+    fn from(row: NoteSearchRow) -> Self {
+        let (_, title_spans) = match_spans(&row.title_marked);
+        let (_, body_spans) = match_spans(&row.body_marked);
+        let matches = title_spans
+            .into_iter()
+            .map(|(start, end)| MatchSpan { field: "title", start, end })
+            .chain(
+                body_spans
+                    .into_iter()
+                    .map(|(start, end)| MatchSpan { field: "body_text", start, end }),
+            )
+            .collect();
+
+        NoteSearchResult {
+            id: row.id,
+            title: row.title,
+            note_type: row.note_type,
+            note_type_name: row.note_type_name,
+            note_type_icon: row.note_type_icon,
+            excerpt: match_spans(&row.snippet).0,
+            snippet: snippet_html(&row.snippet),
+            matches,
+            similarity: row.similarity,
+            rank: row.rank,
+            distance_km: row.distance_km,
+        }
+    }
+}
+
+impl From<MediaSearchRow> for MediaSearchResult {
+    fn from(row: MediaSearchRow) -> Self {
+        let transcript_matches = row
+            .segment_matches
+            .map(|json| json.0)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|seg| TranscriptMatch {
+                start: seg.start,
+                end: seg.end,
+                text: match_spans(&seg.marked).0,
+                snippet: snippet_html(&seg.marked),
+            })
+            .collect();
+
+        MediaSearchResult {
+            id: row.id,
+            note_id: row.note_id,
+            note_title: row.note_title,
+            media_type: row.media_type,
+            original_filename: row.original_filename,
+            label: row.label,
+            duration_seconds: row.duration_seconds,
+            excerpt: match_spans(&row.snippet).0,
+            snippet: snippet_html(&row.snippet),
+            transcript_matches,
+            similarity: row.similarity,
+            rank: row.rank,
+        }
+    }
+}
+
+/// How well the query's terms match the name of an entity or concept
+/// aliased `x`: 1 when one occurs in it, otherwise the best similarity after
+/// unaccenting. `$2` and `$3` are the two halves of `name_terms`.
+const NAME_SIMILARITY: &str = "(SELECT max(CASE \
+         WHEN search_normalize(x.name) LIKE search_normalize(t.pattern) THEN 1 \
+         ELSE word_similarity(search_normalize(t.term), search_normalize(x.name)) END) \
+     FROM unnest($2::text[], $3::text[]) AS t(pattern, term))::real";
+
+/// A search to run: a parsed query, narrowed by note filters for saved
+/// searches.
+pub(crate) struct SearchSpec<'a> {
+    pub node: &'a Node,
+    pub filters: &'a NoteFilters,
+    pub field_filters: &'a [FieldFilter],
+    pub geo: &'a GeoFilter,
+    pub fuzzy: bool,
+}
+
+/// Restrict to live notes in the workspace, and to results matching the
+/// search.
+fn push_match(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    workspace_id: Uuid,
+    spec: &SearchSpec<'_>,
+    target: &SearchTarget,
+) {
+    qb.push("n.workspace_id = ")
+        .push_bind(workspace_id)
+        .push(" AND n.deleted_at IS NULL AND ");
+    push_condition(spec.node, target, spec.fuzzy, qb);
+    push_note_filters(spec.filters, spec.field_filters, qb);
+    for fragment in spec.geo.conditions() {
+        qb.push(" AND ");
+        fragment.push(qb);
+    }
+}
+
+/// Count the notes matching a search, and those of them new: not in `seen`,
+/// or without it created after `since`.
+pub(crate) async fn count_matches(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    spec: &SearchSpec<'_>,
+    seen: Option<&[Uuid]>,
+    since: DateTime<Utc>,
+) -> Result<(i64, i64), AppError> {
+    let node = without_stop_words(pool, spec.node).await?;
+    let spec = &SearchSpec { node: &node, ..*spec };
+    let mut query = QueryBuilder::new("SELECT COUNT(*), COUNT(*) FILTER (WHERE ");
+    match seen {
+        Some(ids) => query.push("NOT n.id = ANY(").push_bind(ids.to_vec()).push(")"),
+        None => query.push("n.created_at > ").push_bind(since),
+    };
+    query.push(") FROM notes n WHERE ");
+    push_match(&mut query, workspace_id, spec, &NOTE_TEXT);
+    Ok(query.build_query_as::<(i64, i64)>().fetch_one(pool).await?)
+}
+
+/// Mark the current matches of a search as seen by saved search `id`.
+pub(crate) async fn mark_seen(
+    pool: &PgPool,
+    id: Uuid,
+    workspace_id: Uuid,
+    spec: &SearchSpec<'_>,
+) -> Result<(), AppError> {
+    let node = without_stop_words(pool, spec.node).await?;
+    let spec = &SearchSpec { node: &node, ..*spec };
+    let mut query = QueryBuilder::new(
+        "UPDATE saved_searches SET last_viewed_at = now(), \
+         seen_note_ids = ARRAY(SELECT n.id FROM notes n WHERE ",
+    );
+    push_match(&mut query, workspace_id, spec, &NOTE_TEXT);
+    query.push(") WHERE id = ").push_bind(id);
+    query.build().execute(pool).await?;
+    Ok(())
+}
+
+/// Push `ts_rank` against `q.query` as `text_rank`, and the average
+/// similarity of `words` to the target as `similarity`.
+fn push_scores<'a>(
+    qb: &mut QueryBuilder<'a, Postgres>,
+    target: &SearchTarget,
+    words: &'a [String],
+) {
+    qb.push("ts_rank(")
+        .push(target.tsvector)
+        .push(", q.query) AS text_rank, COALESCE((SELECT avg(word_similarity(search_normalize(w), ")
+        .push(target.normalized)
+        .push(")) FROM unnest(")
+        .push_bind(words)
+        .push("::text[]) AS w), 0)::real AS similarity");
 }
 
 async fn search(
@@ -64,60 +427,413 @@ async fn search(
     State(pool): State<PgPool>,
     Query(params): Query<SearchParams>,
 ) -> Result<Json<ApiResponse<SearchResults>>, AppError> {
-    if params.q.trim().is_empty() {
-        return Err(AppError::BadRequest(
-            "Search query cannot be empty".to_string(),
-        ));
-    }
+    let node = query::parse(&params.q)?;
+    let geo = GeoFilter::parse(
+        params.near.as_deref(),
+        params.km,
+        params.bbox.as_deref(),
+        params.from,
+        params.to,
+    )?;
+    let spec = SearchSpec {
+        node: &node,
+        filters: &NoteFilters::default(),
+        field_filters: &[],
+        geo: &geo,
+        fuzzy: params.fuzzy.unwrap_or(true),
+    };
+    let pagination = PaginationParams {
+        page: params.page,
+        per_page: params.per_page,
+        cursor: params.cursor,
+    };
+    run_search(&pool, auth.workspace_id, &spec, &pagination).await
+}
 
-    let like_pattern = format!("%{}%", escape_like(&params.q));
-
-    // Run all three queries concurrently for better performance
-    let (notes, entities, concepts) = tokio::try_join!(
-        sqlx::query_as::<_, NoteSearchResult>(
-            "SELECT id, title, note_type::text, \
-             left(body_text, 200) AS excerpt, \
-             ts_rank(\
-                 to_tsvector('english', coalesce(title, '') || ' ' || coalesce(body_text, '')), \
-                 plainto_tsquery('english', $2)\
-             ) AS rank \
-             FROM notes \
-             WHERE workspace_id = $1 AND deleted_at IS NULL \
-               AND to_tsvector('english', coalesce(title, '') || ' ' || coalesce(body_text, '')) \
-                   @@ plainto_tsquery('english', $2) \
-             ORDER BY rank DESC \
-             LIMIT 20",
-        )
-        .bind(auth.workspace_id)
-        .bind(&params.q)
-        .fetch_all(&pool),
-        sqlx::query_as::<_, EntitySearchResult>(
-            "SELECT id, name, entity_type::text, role \
-             FROM entities \
-             WHERE workspace_id = $1 AND name ILIKE $2 ESCAPE '\\' \
-             ORDER BY name ASC LIMIT 10",
+/// Run a search, paging notes by rank or cursor and the other sections by
+/// offset.
+pub(crate) async fn run_search(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    spec: &SearchSpec<'_>,
+    pagination: &PaginationParams,
+) -> Result<Json<ApiResponse<SearchResults>>, AppError> {
+    let node = without_stop_words(pool, spec.node).await?;
+    let spec = &SearchSpec { node: &node, ..*spec };
+    let fuzzy = spec.fuzzy;
+    let ts = rank_tsquery(spec.node);
+    let words = if fuzzy { fuzzy_words(spec.node) } else { Vec::new() };
+    let (name_patterns, name_texts) = name_terms(spec.node);
+    // Without fuzzy matching, names must contain a term.
+    let name_threshold = if fuzzy { FUZZY_THRESHOLD } else { 1.0 };
+
+    const SORT: &str = "rank_desc";
+    let cursor = pagination
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c, SORT))
+        .transpose()?;
+    let (after_rank, after_id) = match cursor {
+        Some(ref c) => (Some(c.key::<f32>()?), Some(c.id)),
+        None => (None, None),
+    };
+    let per_page = pagination.per_page();
+    let offset = if cursor.is_none() { pagination.offset() } else { 0 };
+
+    let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM notes n WHERE ");
+    push_match(&mut count_query, workspace_id, spec, &NOTE_TEXT);
+
+    // Headlines are only computed for the page, after ranking and limiting.
+    let mut notes_query = QueryBuilder::new(format!(
+        "SELECT page.id, page.title, page.note_type, page.note_type_name, page.note_type_icon, \
+         page.similarity, page.rank, page.distance_km, \
+         ts_headline('english', page.body_text, page.query, {SNIPPET_OPTIONS}) AS snippet, \
+         ts_headline('english', page.title, page.query, {HIGHLIGHT_ALL_OPTIONS}) AS title_marked, \
+         ts_headline('english', page.body_text, page.query, {HIGHLIGHT_ALL_OPTIONS}) AS body_marked \
+         FROM ( \
+             SELECT * FROM ( \
+                 SELECT scored.*, scored.text_rank + scored.similarity AS rank FROM ( \
+                     SELECT n.id, n.title, coalesce(n.body_text, '') AS body_text, n.note_type, \
+                     ty.name AS note_type_name, ty.icon AS note_type_icon, q.query, "
+    ));
+    spec.geo.distance().push(&mut notes_query);
+    notes_query.push(" AS distance_km, ");
+    push_scores(&mut notes_query, &NOTE_TEXT, &words);
+    notes_query
+        .push(
+            " FROM notes n \
+             JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type \
+             CROSS JOIN to_tsquery('english', ",
         )
-        .bind(auth.workspace_id)
-        .bind(&like_pattern)
-        .fetch_all(&pool),
-        sqlx::query_as::<_, ConceptSearchResult>(
-            "SELECT id, name, category \
-             FROM concepts \
-             WHERE workspace_id = $1 AND name ILIKE $2 ESCAPE '\\' \
-             ORDER BY name ASC LIMIT 10",
+        .push_bind(&ts)
+        .push(") AS q(query) WHERE ");
+    push_match(&mut notes_query, workspace_id, spec, &NOTE_TEXT);
+    notes_query
+        .push(") scored) ranked WHERE ")
+        .push_bind(after_rank)
+        .push("::real IS NULL OR (rank, id) < (")
+        .push_bind(after_rank)
+        .push(", ")
+        .push_bind(after_id)
+        .push(") ORDER BY rank DESC, id DESC LIMIT ")
+        .push_bind(per_page + 1)
+        .push(" OFFSET ")
+        .push_bind(offset)
+        .push(") page ORDER BY page.rank DESC, page.id DESC");
+
+    // Media of live notes; words and phrases match the filename, label and
+    // transcription, filters the parent note.
+    let mut media_count_query = QueryBuilder::new(
+        "SELECT COUNT(*) FROM media m JOIN notes n ON n.id = m.note_id WHERE ",
+    );
+    push_match(&mut media_count_query, workspace_id, spec, &MEDIA_TEXT);
+
+    let mut media_query = QueryBuilder::new(format!(
+        "SELECT page.id, page.note_id, page.note_title, page.media_type, page.original_filename, \
+         page.label, page.duration_seconds, page.similarity, page.rank, \
+         ts_headline('english', page.transcription, page.query, {SNIPPET_OPTIONS}) AS snippet, \
+         (SELECT jsonb_agg(jsonb_build_object( \
+                     'start', seg.s->'start', 'end', seg.s->'end', \
+                     'marked', ts_headline('english', seg.s->>'text', page.query, {HIGHLIGHT_ALL_OPTIONS})) \
+                 ORDER BY seg.ord) \
+          FROM jsonb_array_elements(page.segments) WITH ORDINALITY AS seg(s, ord) \
+          WHERE to_tsvector('english', seg.s->>'text') @@ page.query) AS segment_matches \
+         FROM ( \
+             SELECT scored.*, scored.text_rank + scored.similarity AS rank FROM ( \
+                 SELECT m.id, m.note_id, n.title AS note_title, m.media_type::text, \
+                 m.original_filename, m.label, m.duration_seconds, \
+                 coalesce(m.transcription_text, '') AS transcription, \
+                 m.transcription_segments AS segments, q.query, "
+    ));
+    push_scores(&mut media_query, &MEDIA_TEXT, &words);
+    media_query
+        .push(
+            " FROM media m JOIN notes n ON n.id = m.note_id \
+             CROSS JOIN to_tsquery('english', ",
         )
-        .bind(auth.workspace_id)
-        .bind(&like_pattern)
-        .fetch_all(&pool),
-    )?;
+        .push_bind(&ts)
+        .push(") AS q(query) WHERE ");
+    push_match(&mut media_query, workspace_id, spec, &MEDIA_TEXT);
+    media_query
+        .push(") scored ORDER BY rank DESC, id DESC LIMIT ")
+        .push_bind(per_page)
+        .push(" OFFSET ")
+        .push_bind(pagination.offset())
+        .push(") page ORDER BY page.rank DESC, page.id DESC");
+
+    // Entities and concepts, best match first.
+    let entities_count_sql = format!(
+        "SELECT COUNT(*) FROM entities x WHERE x.workspace_id = $1 AND {NAME_SIMILARITY} >= $4"
+    );
+    let entities_sql = format!(
+        "SELECT * FROM ( \
+             SELECT x.id, x.name, x.entity_type::text, x.role, {NAME_SIMILARITY} AS similarity \
+             FROM entities x WHERE x.workspace_id = $1 \
+         ) m WHERE similarity >= $4 \
+         ORDER BY similarity DESC, name ASC, id LIMIT $5 OFFSET $6"
+    );
+    let concepts_count_sql = format!(
+        "SELECT COUNT(*) FROM concepts x WHERE x.workspace_id = $1 AND {NAME_SIMILARITY} >= $4"
+    );
+    let concepts_sql = format!(
+        "SELECT * FROM ( \
+             SELECT x.id, x.name, x.category, {NAME_SIMILARITY} AS similarity \
+             FROM concepts x WHERE x.workspace_id = $1 \
+         ) m WHERE similarity >= $4 \
+         ORDER BY similarity DESC, name ASC, id LIMIT $5 OFFSET $6"
+    );
 
-    Ok(ApiResponse::ok(SearchResults {
-        notes,
+    // Run all queries concurrently for better performance
+    let (
+        total,
+        mut notes,
+        media_total,
+        media,
+        entities_total,
         entities,
+        concepts_total,
         concepts,
-    }))
+    ) = tokio::try_join!(
+        count_query.build_query_scalar::<i64>().fetch_one(pool),
+        notes_query.build_query_as::<NoteSearchRow>().fetch_all(pool),
+        media_count_query.build_query_scalar::<i64>().fetch_one(pool),
+        media_query.build_query_as::<MediaSearchRow>().fetch_all(pool),
+        sqlx::query_scalar::<_, i64>(&entities_count_sql)
+            .bind(workspace_id)
+            .bind(&name_patterns)
+            .bind(&name_texts)
+            .bind(name_threshold)
+            .fetch_one(pool),
+        sqlx::query_as::<_, EntitySearchResult>(&entities_sql)
+            .bind(workspace_id)
+            .bind(&name_patterns)
+            .bind(&name_texts)
+            .bind(name_threshold)
+            .bind(per_page)
+            .bind(pagination.offset())
+            .fetch_all(pool),
+        sqlx::query_scalar::<_, i64>(&concepts_count_sql)
+            .bind(workspace_id)
+            .bind(&name_patterns)
+            .bind(&name_texts)
+            .bind(name_threshold)
+            .fetch_one(pool),
+        sqlx::query_as::<_, ConceptSearchResult>(&concepts_sql)
+            .bind(workspace_id)
+            .bind(&name_patterns)
+            .bind(&name_texts)
+            .bind(name_threshold)
+            .bind(per_page)
+            .bind(pagination.offset())
+            .fetch_all(pool),
+    )?;
+
+    let next_cursor = if notes.len() as i64 > per_page {
+        notes.truncate(per_page as usize);
+        notes
+            .last()
+            .map(|n| Cursor::new(SORT, n.rank, n.id).encode())
+    } else {
+        None
+    };
+
+    Ok(ApiResponse::cursor_list(
+        SearchResults {
+            notes: notes.into_iter().map(NoteSearchResult::from).collect(),
+            media: media.into_iter().map(MediaSearchResult::from).collect(),
+            media_total,
+            entities,
+            entities_total,
+            concepts,
+            concepts_total,
+        },
+        total,
+        cursor.is_none().then(|| pagination.page()),
+        per_page,
+        next_cursor,
+    ))
+}
+
+async fn semantic_search(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Extension(embedder): Extension<EmbeddingProvider>,
+    Query(params): Query<SemanticSearchParams>,
+) -> Result<Json<ApiResponse<Vec<SemanticSearchResult>>>, AppError> {
+    let q = params.q.trim();
+    if q.is_empty() {
+        return Err(AppError::BadRequest("Search query cannot be empty".to_string()));
+    }
+    let pagination = PaginationParams {
+        page: params.page,
+        per_page: params.per_page,
+        cursor: None,
+    };
+    let min_score = params.min_score.unwrap_or(0.0);
+
+    let vector = embedder
+        .embed(&[q.to_string()])
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::Internal("Embedder returned no vector".to_string()))?;
+
+    // Chunks of other models are in a different vector space.
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM note_chunks c JOIN notes n ON n.id = c.note_id \
+         WHERE n.workspace_id = $1 AND n.deleted_at IS NULL AND c.model = $2 \
+         AND embedding_similarity(c.embedding, $3) >= $4",
+    )
+    .bind(auth.workspace_id)
+    .bind(embedder.model())
+    .bind(&vector)
+    .bind(min_score)
+    .fetch_one(&pool)
+    .await?;
+
+    let results = sqlx::query_as::<_, SemanticSearchResult>(
+        "SELECT * FROM ( \
+             SELECT c.id AS chunk_id, c.chunk_index, c.content, \
+             embedding_similarity(c.embedding, $3) AS score, \
+             n.id AS note_id, n.title AS note_title, n.note_type, \
+             ty.name AS note_type_name, ty.icon AS note_type_icon \
+             FROM note_chunks c \
+             JOIN notes n ON n.id = c.note_id \
+             JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type \
+             WHERE n.workspace_id = $1 AND n.deleted_at IS NULL AND c.model = $2 \
+         ) scored WHERE score >= $4 \
+         ORDER BY score DESC, chunk_id LIMIT $5 OFFSET $6",
+    )
+    .bind(auth.workspace_id)
+    .bind(embedder.model())
+    .bind(&vector)
+    .bind(min_score)
+    .bind(pagination.per_page())
+    .bind(pagination.offset())
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(ApiResponse::list(
+        results,
+        total,
+        pagination.page(),
+        pagination.per_page(),
+    ))
+}
+
+/// Where suggestions of one kind come from; SQL over the row aliased `x`.
+struct SuggestSource {
+    kind: &'static str,
+    table: &'static str,
+    label: &'static str,
+    detail: &'static str,
+    /// Subquery counting the live notes using the row.
+    usage: &'static str,
+    /// Further conditions, starting with ` AND`.
+    extra: &'static str,
+}
+
+const SUGGEST_SOURCES: &[SuggestSource] = &[
+    SuggestSource {
+        kind: "entity",
+        table: "entities",
+        label: "x.name",
+        detail: "x.entity_type::text",
+        usage: "SELECT COUNT(*) FROM note_entities u JOIN notes un ON un.id = u.note_id \
+                WHERE u.entity_id = x.id AND un.deleted_at IS NULL",
+        extra: "",
+    },
+    SuggestSource {
+        kind: "concept",
+        table: "concepts",
+        label: "x.name",
+        detail: "x.category",
+        usage: "SELECT COUNT(*) FROM note_concepts u JOIN notes un ON un.id = u.note_id \
+                WHERE u.concept_id = x.id AND un.deleted_at IS NULL",
+        extra: "",
+    },
+    SuggestSource {
+        kind: "tag",
+        table: "tags",
+        label: "x.name",
+        detail: "NULL::text",
+        usage: "SELECT COUNT(*) FROM note_tags u JOIN notes un ON un.id = u.note_id \
+                WHERE u.tag_id = x.id AND un.deleted_at IS NULL",
+        extra: "",
+    },
+    SuggestSource {
+        kind: "note",
+        table: "notes",
+        label: "x.title",
+        detail: "x.note_type",
+        usage: "SELECT COUNT(*) FROM note_links u JOIN notes un ON un.id = u.source_note_id \
+                WHERE u.target_note_id = x.id AND un.deleted_at IS NULL",
+        extra: " AND x.deleted_at IS NULL",
+    },
+];
+
+const MAX_SUGGESTIONS: i64 = 50;
+
+/// Names and titles starting with `q`, or with a word starting with it,
+/// ignoring case and accents. Exact matches rank first, then matches at the
+/// start, then by usage.
+async fn suggest(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Query(params): Query<SuggestParams>,
+) -> Result<Json<ApiResponse<Vec<Suggestion>>>, AppError> {
+    let q = params.q.trim();
+    if q.is_empty() {
+        return Err(AppError::BadRequest("Search query cannot be empty".to_string()));
+    }
+    let kinds: Vec<&str> = match params.kinds.as_deref() {
+        Some(raw) => raw.split(',').map(str::trim).filter(|k| !k.is_empty()).collect(),
+        None => Vec::new(),
+    };
+    if let Some(unknown) = kinds.iter().find(|k| !SUGGEST_SOURCES.iter().any(|s| s.kind == **k)) {
+        return Err(AppError::BadRequest(format!(
+            "Unknown suggestion kind '{unknown}'; expected entity, concept, tag or note"
+        )));
+    }
+    let limit = params.limit.unwrap_or(10).clamp(1, MAX_SUGGESTIONS);
+
+    // $2 is the query, $3 it escaped as a LIKE prefix. The LIKE expressions
+    // match the trigram indexes of migration 041.
+    let selects: Vec<String> = SUGGEST_SOURCES
+        .iter()
+        .filter(|s| kinds.is_empty() || kinds.contains(&s.kind))
+        .map(|SuggestSource { kind, table, label, detail, usage, extra }| {
+            format!(
+                "SELECT '{kind}' AS kind, x.id, {label} AS label, {detail} AS detail, \
+                 ({usage}) AS usage_count, \
+                 CASE WHEN search_normalize({label}) = search_normalize($2) THEN 0 \
+                      WHEN search_normalize({label}) LIKE search_normalize($3) || '%' THEN 1 \
+                      ELSE 2 END AS match_rank \
+                 FROM {table} x WHERE x.workspace_id = $1{extra} \
+                 AND (search_normalize({label}) LIKE search_normalize($3) || '%' \
+                      OR search_normalize({label}) LIKE '% ' || search_normalize($3) || '%')"
+            )
+        })
+        .collect();
+    let sql = format!(
+        "SELECT kind, id, label, detail, usage_count FROM ({}) s \
+         ORDER BY match_rank, usage_count DESC, length(label), label, id LIMIT $4",
+        selects.join(" UNION ALL ")
+    );
+
+    let suggestions = sqlx::query_as::<_, Suggestion>(&sql)
+        .bind(auth.workspace_id)
+        .bind(q)
+        .bind(escape_like(q))
+        .bind(limit)
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(ApiResponse::ok(suggestions))
 }
 
 pub fn routes() -> Router<PgPool> {
-    Router::new().route("/api/v1/search", get(search))
+    Router::new()
+        .route("/api/v1/search", get(search))
+        .route("/api/v1/search/suggest", get(suggest))
+        .route("/api/v1/search/semantic", get(semantic_search))
 }
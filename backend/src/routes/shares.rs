@@ -0,0 +1,359 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::auth::password::{hash_password_async, verify_password_async};
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::media::Media;
+use crate::models::share::*;
+use crate::response::ApiResponse;
+use crate::routes::media::media_file_response;
+use crate::routes::notes::note_render_context;
+use crate::tiptap::render;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SHARE_COLS: &str = "id, workspace_id, note_id, field_trip_id, \
+     password_hash IS NOT NULL AS has_password, expires_at, revoked_at, created_by, \
+     view_count, last_viewed_at, created_at";
+
+/// Header a viewer sends the password of a protected share link in.
+const PASSWORD_HEADER: &str = "x-share-password";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/api/v1/shares", get(list_shares).post(create_share))
+        .route("/api/v1/shares/{id}", delete(revoke_share))
+        // Unauthenticated
+        .route("/api/v1/public/shares/{token}", get(view_share))
+        .route("/api/v1/public/shares/{token}/media/{media_id}", get(serve_shared_file))
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Key that lets `<img>`/`<audio>` tags of a password-protected share fetch
+/// media without the password, since they cannot send headers. Derived from
+/// the server secret, so only viewers who passed the password receive it.
+fn media_key(secret: &str, share_id: Uuid) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("share-media:{share_id}").as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn verify_media_key(secret: &str, share_id: Uuid, key: &str) -> bool {
+    let Ok(expected) = hex::decode(key) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("share-media:{share_id}").as_bytes());
+    mac.verify_slice(&expected).is_ok()
+}
+
+// ---------------------------------------------------------------------------
+// Managing links (authenticated)
+// ---------------------------------------------------------------------------
+
+async fn list_shares(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Query(filters): Query<ShareLinkFilters>,
+) -> Result<Json<ApiResponse<Vec<ShareLink>>>, AppError> {
+    let links = sqlx::query_as::<_, ShareLink>(&format!(
+        "SELECT {SHARE_COLS} FROM share_links \
+         WHERE workspace_id = $1 \
+           AND ($2::uuid IS NULL OR note_id = $2) \
+           AND ($3::uuid IS NULL OR field_trip_id = $3) \
+         ORDER BY created_at DESC"
+    ))
+    .bind(auth.workspace_id)
+    .bind(filters.note_id)
+    .bind(filters.field_trip_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let total = links.len() as i64;
+    Ok(ApiResponse::list(links, total, 1, total.max(1)))
+}
+
+async fn create_share(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(body): Json<CreateShareLink>,
+) -> Result<Json<ApiResponse<CreatedShareLink>>, AppError> {
+    let exists: bool = match (body.note_id, body.field_trip_id) {
+        (Some(note_id), None) => {
+            sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM notes \
+                 WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)",
+            )
+            .bind(note_id)
+            .bind(auth.workspace_id)
+            .fetch_one(&pool)
+            .await?
+        }
+        (None, Some(field_trip_id)) => {
+            sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM field_trips WHERE id = $1 AND workspace_id = $2)",
+            )
+            .bind(field_trip_id)
+            .bind(auth.workspace_id)
+            .fetch_one(&pool)
+            .await?
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "Provide exactly one of note_id or field_trip_id".to_string(),
+            ))
+        }
+    };
+    if !exists {
+        return Err(AppError::NotFound("Share target not found".to_string()));
+    }
+    if body.expires_at.is_some_and(|at| at <= Utc::now()) {
+        return Err(AppError::BadRequest("expires_at must be in the future".to_string()));
+    }
+    let password_hash = match body.password {
+        Some(password) if password.is_empty() => {
+            return Err(AppError::BadRequest("Password cannot be empty".to_string()))
+        }
+        Some(password) => Some(hash_password_async(password).await?),
+        None => None,
+    };
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let link = sqlx::query_as::<_, ShareLink>(&format!(
+        "INSERT INTO share_links \
+         (workspace_id, note_id, field_trip_id, token_hash, password_hash, expires_at, created_by) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+         RETURNING {SHARE_COLS}"
+    ))
+    .bind(auth.workspace_id)
+    .bind(body.note_id)
+    .bind(body.field_trip_id)
+    .bind(hash_token(&token))
+    .bind(&password_hash)
+    .bind(body.expires_at)
+    .bind(auth.user_id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(ApiResponse::ok(CreatedShareLink { link, token }))
+}
+
+/// Revoked links stay listed so it is clear what was once shared.
+async fn revoke_share(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ShareLink>>, AppError> {
+    let link = sqlx::query_as::<_, ShareLink>(&format!(
+        "UPDATE share_links SET revoked_at = COALESCE(revoked_at, now()) \
+         WHERE id = $1 AND workspace_id = $2 \
+         RETURNING {SHARE_COLS}"
+    ))
+    .bind(id)
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Share link not found".to_string()))?;
+
+    Ok(ApiResponse::ok(link))
+}
+
+// ---------------------------------------------------------------------------
+// Public view (unauthenticated)
+// ---------------------------------------------------------------------------
+
+#[derive(sqlx::FromRow)]
+struct ActiveShare {
+    id: Uuid,
+    workspace_id: Uuid,
+    note_id: Option<Uuid>,
+    field_trip_id: Option<Uuid>,
+    password_hash: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SharedNoteRow {
+    id: Uuid,
+    title: String,
+    body: serde_json::Value,
+    note_type_name: String,
+    note_type_icon: Option<String>,
+    location_name: Option<String>,
+    time_start: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Look up a link that is neither revoked nor expired. Unknown, revoked and
+/// expired tokens are indistinguishable to the viewer.
+async fn active_share(pool: &PgPool, token: &str) -> Result<ActiveShare, AppError> {
+    sqlx::query_as::<_, ActiveShare>(
+        "SELECT id, workspace_id, note_id, field_trip_id, password_hash, expires_at \
+         FROM share_links \
+         WHERE token_hash = $1 AND revoked_at IS NULL \
+           AND (expires_at IS NULL OR expires_at > now())",
+    )
+    .bind(hash_token(token))
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Share link not found or expired".to_string()))
+}
+
+async fn view_share(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<PublicShare>>, AppError> {
+    let share = active_share(&pool, &token).await?;
+
+    let mut key_query = String::new();
+    if let Some(ref hash) = share.password_hash {
+        let password = headers
+            .get(PASSWORD_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| AppError::Unauthorized("This share link requires a password".to_string()))?;
+        if !verify_password_async(password.to_string(), hash.clone()).await? {
+            return Err(AppError::Unauthorized("Incorrect password".to_string()));
+        }
+        key_query = format!("?key={}", media_key(&config.jwt_secret, share.id));
+    }
+
+    const NOTE_SELECT: &str = "SELECT n.id, n.title, n.body, ty.name AS note_type_name, \
+         ty.icon AS note_type_icon, n.location_name, n.time_start, n.created_at, n.updated_at \
+         FROM notes n \
+         JOIN note_types ty ON ty.workspace_id = n.workspace_id AND ty.key = n.note_type";
+
+    let (kind, title, icon, rows) = match (share.note_id, share.field_trip_id) {
+        (Some(note_id), _) => {
+            let row = sqlx::query_as::<_, SharedNoteRow>(&format!(
+                "{NOTE_SELECT} WHERE n.id = $1 AND n.workspace_id = $2 AND n.deleted_at IS NULL"
+            ))
+            .bind(note_id)
+            .bind(share.workspace_id)
+            .fetch_optional(&pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Share link not found or expired".to_string()))?;
+            ("note", row.title.clone(), row.note_type_icon.clone(), vec![row])
+        }
+        (None, Some(field_trip_id)) => {
+            let (name, icon): (String, String) = sqlx::query_as(
+                "SELECT name, icon FROM field_trips WHERE id = $1 AND workspace_id = $2",
+            )
+            .bind(field_trip_id)
+            .bind(share.workspace_id)
+            .fetch_optional(&pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Share link not found or expired".to_string()))?;
+            let rows = sqlx::query_as::<_, SharedNoteRow>(&format!(
+                "{NOTE_SELECT} \
+                 JOIN note_field_trips nft ON nft.note_id = n.id AND nft.field_trip_id = $1 \
+                 WHERE n.workspace_id = $2 AND n.deleted_at IS NULL \
+                 ORDER BY COALESCE(n.time_start, n.created_at), n.id"
+            ))
+            .bind(field_trip_id)
+            .bind(share.workspace_id)
+            .fetch_all(&pool)
+            .await?;
+            ("field_trip", name, Some(icon), rows)
+        }
+        (None, None) => return Err(AppError::Internal("Share link has no target".to_string())),
+    };
+
+    let mut notes = Vec::with_capacity(rows.len());
+    for row in rows {
+        let ctx = note_render_context(&pool, row.id, |media_id| {
+            format!("/api/v1/public/shares/{token}/media/{media_id}{key_query}")
+        })
+        .await?;
+        notes.push(PublicNote {
+            id: row.id,
+            title: row.title,
+            note_type_name: row.note_type_name,
+            note_type_icon: row.note_type_icon,
+            location_name: row.location_name,
+            time_start: row.time_start,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            html: render::to_html(&row.body, &ctx),
+        });
+    }
+
+    sqlx::query(
+        "UPDATE share_links SET view_count = view_count + 1, last_viewed_at = now() WHERE id = $1",
+    )
+    .bind(share.id)
+    .execute(&pool)
+    .await?;
+
+    Ok(ApiResponse::ok(PublicShare {
+        kind,
+        title,
+        icon,
+        expires_at: share.expires_at,
+        notes,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct SharedFileQuery {
+    /// Required for password-protected links; see `media_key`.
+    key: Option<String>,
+}
+
+/// Token-scoped variant of `serve_file`: only media of the shared note, or
+/// of live notes in the shared field trip, is served.
+async fn serve_shared_file(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path((token, media_id)): Path<(String, Uuid)>,
+    Query(query): Query<SharedFileQuery>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, HeaderMap, Body), AppError> {
+    let share = active_share(&pool, &token).await?;
+    if share.password_hash.is_some()
+        && !query
+            .key
+            .as_deref()
+            .is_some_and(|key| verify_media_key(&config.jwt_secret, share.id, key))
+    {
+        return Err(AppError::Unauthorized("Invalid media key".to_string()));
+    }
+
+    let record = sqlx::query_as::<_, Media>(
+        "SELECT m.id, m.note_id, m.media_type::text, m.s3_key, m.original_filename, m.mime_type, \
+         m.file_size_bytes, m.duration_seconds, m.thumbnail_s3_key, m.label, \
+         m.transcription_status::text, m.transcription_text, m.transcription_segments, m.sort_order, m.created_at \
+         FROM media m JOIN notes n ON n.id = m.note_id \
+         WHERE m.id = $1 AND n.workspace_id = $2 AND n.deleted_at IS NULL \
+           AND (n.id = $3 OR EXISTS( \
+               SELECT 1 FROM note_field_trips nft \
+               WHERE nft.note_id = n.id AND nft.field_trip_id = $4))",
+    )
+    .bind(media_id)
+    .bind(share.workspace_id)
+    .bind(share.note_id)
+    .bind(share.field_trip_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Media not found".to_string()))?;
+
+    media_file_response(&record, &headers).await
+}
@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use sqlx::PgPool;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::trash::{TrashSettings, TrashedNote};
+use crate::response::{ApiResponse, PaginationParams};
+
+/// Upper bound on `trash_retention_days`, matching the column's CHECK.
+const MAX_RETENTION_DAYS: i32 = 3650;
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/api/v1/trash", get(list_trash))
+        .route(
+            "/api/v1/settings/trash",
+            get(get_trash_settings).put(update_trash_settings),
+        )
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/v1/trash — trashed notes with their purge time, soonest first
+// ---------------------------------------------------------------------------
+async fn list_trash(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<ApiResponse<Vec<TrashedNote>>>, AppError> {
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notes WHERE workspace_id = $1 AND deleted_at IS NOT NULL",
+    )
+    .bind(auth.workspace_id)
+    .fetch_one(&pool)
+    .await?;
+
+    let notes = sqlx::query_as::<_, TrashedNote>(
+        "SELECT n.id, n.title, n.note_type, n.deleted_at, \
+         n.deleted_at + make_interval(days => w.trash_retention_days) AS purge_at \
+         FROM notes n JOIN workspaces w ON w.id = n.workspace_id \
+         WHERE n.workspace_id = $1 AND n.deleted_at IS NOT NULL \
+         ORDER BY n.deleted_at ASC, n.id \
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(auth.workspace_id)
+    .bind(pagination.per_page())
+    .bind(pagination.offset())
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(ApiResponse::list(
+        notes,
+        total,
+        pagination.page(),
+        pagination.per_page(),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// GET/PUT /api/v1/settings/trash — workspace trash retention period
+// ---------------------------------------------------------------------------
+async fn get_trash_settings(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<TrashSettings>>, AppError> {
+    let settings = sqlx::query_as::<_, TrashSettings>(
+        "SELECT trash_retention_days FROM workspaces WHERE id = $1",
+    )
+    .bind(auth.workspace_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Workspace not found".to_string()))?;
+
+    Ok(ApiResponse::ok(settings))
+}
+
+async fn update_trash_settings(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    Json(body): Json<TrashSettings>,
+) -> Result<Json<ApiResponse<TrashSettings>>, AppError> {
+    if let Some(days) = body.trash_retention_days {
+        if !(1..=MAX_RETENTION_DAYS).contains(&days) {
+            return Err(AppError::BadRequest(format!(
+                "trash_retention_days must be between 1 and {MAX_RETENTION_DAYS}, or null to keep notes indefinitely"
+            )));
+        }
+    }
+
+    let settings = sqlx::query_as::<_, TrashSettings>(
+        "UPDATE workspaces SET trash_retention_days = $2, updated_at = now() \
+         WHERE id = $1 RETURNING trash_retention_days",
+    )
+    .bind(auth.workspace_id)
+    .bind(body.trash_retention_days)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Workspace not found".to_string()))?;
+
+    Ok(ApiResponse::ok(settings))
+}
@@ -11,7 +11,11 @@ use crate::auth::middleware::AuthUser;
 use crate::auth::password::{hash_password_async, verify_password_async};
 use crate::config::Config;
 use crate::error::AppError;
-use crate::models::user::{AuthResponse, LoginRequest, RegisterRequest, User, UserProfile};
+use crate::models::user::{
+    AuthResponse, LoginRequest, RegisterRequest, SwitchWorkspaceRequest, User, UserProfile,
+    WorkspaceSummary,
+};
+use crate::routes::note_types::seed_builtin_types;
 
 async fn register(
     State(pool): State<PgPool>,
@@ -67,6 +71,7 @@ async fn register(
             .bind("Field Research")
             .fetch_one(&mut *tx)
             .await?;
+    seed_builtin_types(&mut tx, workspace_id).await?;
 
     tx.commit()
         .await
@@ -144,9 +149,70 @@ async fn me(auth: AuthUser, State(pool): State<PgPool>) -> Result<Json<UserProfi
     Ok(Json(UserProfile::from(user)))
 }
 
+/// GET /api/v1/auth/workspaces — the user's workspaces, oldest first (the
+/// one login picks).
+async fn list_workspaces(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+) -> Result<Json<Vec<WorkspaceSummary>>, AppError> {
+    let workspaces = sqlx::query_as::<_, WorkspaceSummary>(
+        "SELECT id, name, created_at, id = $2 AS current FROM workspaces \
+         WHERE user_id = $1 ORDER BY created_at ASC, id",
+    )
+    .bind(auth.user_id)
+    .bind(auth.workspace_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(workspaces))
+}
+
+/// POST /api/v1/auth/switch-workspace — a new session token scoped to
+/// another of the user's workspaces.
+async fn switch_workspace(
+    auth: AuthUser,
+    State(pool): State<PgPool>,
+    axum::Extension(config): axum::Extension<Config>,
+    Json(body): Json<SwitchWorkspaceRequest>,
+) -> Result<Json<AuthResponse>, AppError> {
+    let owned: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM workspaces WHERE id = $1 AND user_id = $2)",
+    )
+    .bind(body.workspace_id)
+    .bind(auth.user_id)
+    .fetch_one(&pool)
+    .await?;
+    if !owned {
+        return Err(AppError::NotFound("Workspace not found".to_string()));
+    }
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, email, password_hash, display_name, avatar_initials, created_at, updated_at, \
+         plan::text, plan_started_at, plan_expires_at, lemonsqueezy_customer_id, lemonsqueezy_subscription_id, lemonsqueezy_variant_id \
+         FROM users WHERE id = $1"
+    )
+        .bind(auth.user_id)
+        .fetch_one(&pool)
+        .await?;
+
+    let token = create_token(
+        user.id,
+        body.workspace_id,
+        &config.jwt_secret,
+        config.jwt_expiry_hours,
+    )?;
+
+    Ok(Json(AuthResponse {
+        token,
+        user: UserProfile::from(user),
+    }))
+}
+
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/api/v1/auth/register", post(register))
         .route("/api/v1/auth/login", post(login))
         .route("/api/v1/auth/me", get(me))
+        .route("/api/v1/auth/workspaces", get(list_workspaces))
+        .route("/api/v1/auth/switch-workspace", post(switch_workspace))
 }
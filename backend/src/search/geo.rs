@@ -0,0 +1,251 @@
+//! Where and when a note was recorded: the `near`/`km`, `bbox` and
+//! `from`/`to` filters shared by note listing and search.
+//!
+//! ```text
+//! near=7.2906,80.6337&km=25       within 25 km of a point (lat,lng)
+//! near=7.2906,80.6337             any located note, with its distance
+//! bbox=79.5,5.9,81.9,9.9          min_lng,min_lat,max_lng,max_lat
+//! from=2024-01-01&to=2024-03-31   notes whose time span overlaps the days
+//! ```
+
+use chrono::NaiveDate;
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::error::AppError;
+use crate::models::note::NoteFilters;
+
+/// Mean Earth radius used by `geo_distance_km`, for the radius prefilter.
+const KM_PER_DEGREE: f64 = 6371.0088 * std::f64::consts::PI / 180.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Near {
+    pub lat: f64,
+    pub lng: f64,
+    /// Radius; without it notes are only required to have coordinates.
+    pub km: Option<f64>,
+}
+
+/// A longitude/latitude box. `west > east` when it crosses the
+/// antimeridian.
+#[derive(Debug, Clone, Copy)]
+pub struct BBox {
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub north: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GeoFilter {
+    pub near: Option<Near>,
+    pub bbox: Option<BBox>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// A bind parameter of a `Fragment`.
+#[derive(Debug, Clone, Copy)]
+pub enum GeoValue {
+    Number(f64),
+    Date(NaiveDate),
+}
+
+/// SQL over the note aliased `n` whose `?` placeholders take `values` in
+/// order, so it can be numbered for hand-built queries or pushed onto a
+/// `QueryBuilder`.
+#[derive(Debug, Clone)]
+pub struct Fragment {
+    pub sql: String,
+    pub values: Vec<GeoValue>,
+}
+
+impl Fragment {
+    fn new(sql: &str, values: &[GeoValue]) -> Self {
+        Fragment { sql: sql.to_string(), values: values.to_vec() }
+    }
+
+    /// The SQL with placeholders numbered from `*idx`, which is advanced
+    /// past them.
+    pub fn numbered(&self, idx: &mut u32) -> String {
+        let mut out = String::with_capacity(self.sql.len());
+        for (i, part) in self.sql.split('?').enumerate() {
+            if i > 0 {
+                out.push_str(&format!("${idx}"));
+                *idx += 1;
+            }
+            out.push_str(part);
+        }
+        out
+    }
+
+    pub fn push(&self, qb: &mut QueryBuilder<'_, Postgres>) {
+        let mut values = self.values.iter();
+        for (i, part) in self.sql.split('?').enumerate() {
+            if i > 0 {
+                match values.next() {
+                    Some(GeoValue::Number(x)) => qb.push_bind(*x),
+                    Some(GeoValue::Date(d)) => qb.push_bind(*d),
+                    None => unreachable!("a value per placeholder"),
+                };
+            }
+            qb.push(part);
+        }
+    }
+}
+
+fn numbers(raw: &str, name: &str, count: usize) -> Result<Vec<f64>, AppError> {
+    let parsed: Option<Vec<f64>> = raw
+        .split(',')
+        .map(|p| p.trim().parse::<f64>().ok().filter(|x| x.is_finite()))
+        .collect();
+    parsed.filter(|v| v.len() == count).ok_or_else(|| {
+        AppError::BadRequest(format!("'{name}' expects {count} comma-separated numbers"))
+    })
+}
+
+fn check_lat_lng(lat: f64, lng: f64, name: &str) -> Result<(), AppError> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+        return Err(AppError::BadRequest(format!(
+            "'{name}' latitudes must be within ±90 and longitudes within ±180"
+        )));
+    }
+    Ok(())
+}
+
+/// Located notes only: lets the partial index `idx_notes_location` apply.
+const HAS_LOCATION: &str = "n.location_lat IS NOT NULL AND n.location_lng IS NOT NULL";
+const POINT_IN_BOX: &str =
+    "point(n.location_lng, n.location_lat) <@ box(point(?, ?), point(?, ?))";
+
+impl GeoFilter {
+    pub fn parse(
+        near: Option<&str>,
+        km: Option<f64>,
+        bbox: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Self, AppError> {
+        let near = match near.filter(|s| !s.trim().is_empty()) {
+            Some(raw) => {
+                let v = numbers(raw, "near", 2)?;
+                check_lat_lng(v[0], v[1], "near")?;
+                if km.is_some_and(|km| !(km > 0.0 && km.is_finite())) {
+                    return Err(AppError::BadRequest("'km' must be a positive number".to_string()));
+                }
+                Some(Near { lat: v[0], lng: v[1], km })
+            }
+            None if km.is_some() => {
+                return Err(AppError::BadRequest("'km' requires 'near'".to_string()));
+            }
+            None => None,
+        };
+        let bbox = match bbox.filter(|s| !s.trim().is_empty()) {
+            Some(raw) => {
+                let v = numbers(raw, "bbox", 4)?;
+                let b = BBox { west: v[0], south: v[1], east: v[2], north: v[3] };
+                check_lat_lng(b.south, b.west, "bbox")?;
+                check_lat_lng(b.north, b.east, "bbox")?;
+                if b.south > b.north {
+                    return Err(AppError::BadRequest(
+                        "'bbox' must be min_lng,min_lat,max_lng,max_lat".to_string(),
+                    ));
+                }
+                Some(b)
+            }
+            None => None,
+        };
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(AppError::BadRequest("'from' must not be after 'to'".to_string()));
+            }
+        }
+        Ok(GeoFilter { near, bbox, from, to })
+    }
+
+    pub fn from_filters(filters: &NoteFilters) -> Result<Self, AppError> {
+        Self::parse(
+            filters.near.as_deref(),
+            filters.km,
+            filters.bbox.as_deref(),
+            filters.from,
+            filters.to,
+        )
+    }
+
+    /// Conditions to AND together.
+    pub fn conditions(&self) -> Vec<Fragment> {
+        use GeoValue::{Date, Number};
+        let mut out = Vec::new();
+        if self.near.is_some() || self.bbox.is_some() {
+            out.push(Fragment::new(HAS_LOCATION, &[]));
+        }
+        if let Some(Near { lat, lng, km: Some(km) }) = self.near {
+            // A box around the circle narrows the search through the
+            // index; near the poles or the antimeridian the exact distance
+            // is used alone.
+            let dlat = km / KM_PER_DEGREE;
+            let dlng = dlat / lat.to_radians().cos();
+            if lat.abs() + dlat < 90.0 && lng.abs() + dlng < 180.0 {
+                out.push(Fragment::new(
+                    POINT_IN_BOX,
+                    &[Number(lng - dlng), Number(lat - dlat), Number(lng + dlng), Number(lat + dlat)],
+                ));
+            }
+            out.push(Fragment::new(
+                "geo_distance_km(n.location_lat, n.location_lng, ?, ?) <= ?",
+                &[Number(lat), Number(lng), Number(km)],
+            ));
+        }
+        if let Some(b) = self.bbox {
+            if b.west <= b.east {
+                out.push(Fragment::new(
+                    POINT_IN_BOX,
+                    &[Number(b.west), Number(b.south), Number(b.east), Number(b.north)],
+                ));
+            } else {
+                out.push(Fragment::new(
+                    &format!("({POINT_IN_BOX} OR {POINT_IN_BOX})"),
+                    &[
+                        Number(b.west), Number(b.south), Number(180.0), Number(b.north),
+                        Number(-180.0), Number(b.south), Number(b.east), Number(b.north),
+                    ],
+                ));
+            }
+        }
+        // A note spans its start to end time; undated notes their creation.
+        if let Some(from) = self.from {
+            out.push(Fragment::new(
+                "COALESCE(n.time_end, n.time_start, n.created_at) >= ?::date",
+                &[Date(from)],
+            ));
+        }
+        if let Some(to) = self.to {
+            out.push(Fragment::new(
+                "COALESCE(n.time_start, n.created_at) < ?::date + 1",
+                &[Date(to)],
+            ));
+        }
+        out
+    }
+
+    /// Values of `conditions()` followed by those of `distance()`, for
+    /// binding after numbering them in that order.
+    pub fn values(&self) -> Vec<GeoValue> {
+        self.conditions()
+            .into_iter()
+            .chain([self.distance()])
+            .flat_map(|f| f.values)
+            .collect()
+    }
+
+    /// Kilometres from `near` to the note, or NULL.
+    pub fn distance(&self) -> Fragment {
+        match self.near {
+            Some(Near { lat, lng, .. }) => Fragment::new(
+                "geo_distance_km(n.location_lat, n.location_lng, ?, ?)",
+                &[GeoValue::Number(lat), GeoValue::Number(lng)],
+            ),
+            None => Fragment::new("NULL::double precision", &[]),
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Note search: the query language typed into the search box, place and
+//! time filters, and their translation into SQL conditions over `notes`.
+
+pub mod geo;
+pub mod query;
+pub mod sql;
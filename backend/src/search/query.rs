@@ -0,0 +1,340 @@
+//! Parser for the search query language:
+//!
+//! ```text
+//! harvest "rice ceremony" ritu*        all of these
+//! kandy OR colombo                     either
+//! NOT monsoon, -monsoon                without
+//! (kandy OR colombo) -monsoon          grouping
+//! type:interview tag:ritual            filters; quote values with spaces:
+//! trip:"Kandy 2024" entity:"Mr. Perera"
+//! before:2024-06-01 after:2024-01-01 starred:true
+//! ```
+//!
+//! Terms are combined with AND unless joined by `OR`, which binds tighter:
+//! `a b OR c` means `a (b OR c)`. `OR`, `AND` and `NOT` are only operators
+//! in upper case.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// A word, matched after stemming; `prefix` for `word*`.
+    Word { text: String, prefix: bool },
+    /// A quoted phrase, matched as consecutive words.
+    Phrase(String),
+    Filter(Filter),
+    Not(Box<Node>),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// A note type key.
+    Type(String),
+    /// Tag, field trip and entity names match case-insensitively.
+    Tag(String),
+    Trip(String),
+    Entity(String),
+    /// Notes whose start time (or creation time when unset) is before the
+    /// day, or after it.
+    Before(NaiveDate),
+    After(NaiveDate),
+    Starred(bool),
+}
+
+const FILTER_KEYS: &[&str] = &["type", "tag", "trip", "entity", "before", "after", "starred"];
+
+/// Longest query accepted, in characters.
+pub const MAX_QUERY_CHARS: usize = 1000;
+
+/// Deepest nesting of `(` and `NOT`/`-` accepted. The parser and the SQL
+/// builder recurse once per level.
+pub const MAX_DEPTH: usize = 32;
+
+/// Why a query could not be parsed. `position` is the character offset in
+/// the query where the problem was found.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl ParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        ParseError { message: message.into(), position }
+    }
+}
+
+impl From<ParseError> for AppError {
+    fn from(err: ParseError) -> Self {
+        AppError::BadRequestDetails(
+            format!("Invalid search query: {}", err.message),
+            serde_json::json!(err),
+        )
+    }
+}
+
+/// Parse a search query. Blank queries and queries over
+/// `MAX_QUERY_CHARS` or nested deeper than `MAX_DEPTH` are rejected.
+pub fn parse(input: &str) -> Result<Node, ParseError> {
+    let end = input.chars().count();
+    if end > MAX_QUERY_CHARS {
+        return Err(ParseError::new(
+            MAX_QUERY_CHARS,
+            format!("Search query is longer than {MAX_QUERY_CHARS} characters"),
+        ));
+    }
+    let tokens = lex(input)?;
+    let mut parser = Parser { tokens, pos: 0, end, depth: 0 };
+    if parser.tokens.is_empty() {
+        return Err(ParseError::new(0, "Search query cannot be empty"));
+    }
+    let node = parser.and_expr()?;
+    // `and_expr` only stops early at a `)`.
+    match parser.peek() {
+        None => Ok(node),
+        Some((_, at)) => Err(ParseError::new(*at, "Unmatched ')'")),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lexer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word { text: String, prefix: bool },
+    Phrase(String),
+    Filter(Filter),
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || c == '(' || c == ')' || c == '"'
+}
+
+/// Read a quoted string starting at the opening quote `chars[start]`,
+/// returning its contents and the index after the closing quote.
+fn quoted(chars: &[char], start: usize) -> Result<(String, usize), ParseError> {
+    let mut i = start + 1;
+    while i < chars.len() && chars[i] != '"' {
+        i += 1;
+    }
+    if i == chars.len() {
+        return Err(ParseError::new(start, "Unterminated quote"));
+    }
+    Ok((chars[start + 1..i].iter().collect(), i + 1))
+}
+
+fn lex(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push((Token::LParen, start));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, start));
+                i += 1;
+            }
+            '"' => {
+                let (text, next) = quoted(&chars, start)?;
+                if text.trim().is_empty() {
+                    return Err(ParseError::new(start, "Empty phrase"));
+                }
+                tokens.push((Token::Phrase(text), start));
+                i = next;
+            }
+            '-' if chars.get(i + 1).is_some_and(|c| !c.is_whitespace()) => {
+                tokens.push((Token::Not, start));
+                i += 1;
+            }
+            _ => {
+                while i < chars.len() && !is_delimiter(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let token = match word.split_once(':') {
+                    Some((key, value))
+                        if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphabetic()) =>
+                    {
+                        let value = if value.is_empty() && chars.get(i) == Some(&'"') {
+                            let (value, next) = quoted(&chars, i)?;
+                            i = next;
+                            value
+                        } else {
+                            value.to_string()
+                        };
+                        Token::Filter(filter(key, value.trim(), start)?)
+                    }
+                    _ => match word.as_str() {
+                        "AND" => Token::And,
+                        "OR" => Token::Or,
+                        "NOT" => Token::Not,
+                        _ => {
+                            let prefix = word.ends_with('*');
+                            let text = word.trim_end_matches('*');
+                            if text.is_empty() {
+                                return Err(ParseError::new(start, "'*' must follow a word"));
+                            }
+                            Token::Word { text: text.to_string(), prefix }
+                        }
+                    },
+                };
+                tokens.push((token, start));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn filter(key: &str, value: &str, at: usize) -> Result<Filter, ParseError> {
+    let key = key.to_ascii_lowercase();
+    if !FILTER_KEYS.contains(&key.as_str()) {
+        return Err(ParseError::new(
+            at,
+            format!("Unknown filter '{key}:'; expected one of {}", FILTER_KEYS.join(", ")),
+        ));
+    }
+    if value.is_empty() {
+        return Err(ParseError::new(at, format!("Filter '{key}:' needs a value")));
+    }
+    let date = || {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+            ParseError::new(at, format!("Filter '{key}:' expects a date like 2024-06-01"))
+        })
+    };
+    Ok(match key.as_str() {
+        "type" => Filter::Type(value.to_lowercase()),
+        "tag" => Filter::Tag(value.to_string()),
+        "trip" => Filter::Trip(value.to_string()),
+        "entity" => Filter::Entity(value.to_string()),
+        "before" => Filter::Before(date()?),
+        "after" => Filter::After(date()?),
+        _ => match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" => Filter::Starred(true),
+            "false" | "no" => Filter::Starred(false),
+            _ => {
+                return Err(ParseError::new(at, "Filter 'starred:' expects true or false"));
+            }
+        },
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Query length, reported for errors at the end of input.
+    end: usize,
+    /// Open `(` and `NOT` around the current position.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn position(&self) -> usize {
+        self.peek().map_or(self.end, |(_, at)| *at)
+    }
+
+    /// Enter a `(` or `NOT` at `at`.
+    fn descend(&mut self, at: usize) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(ParseError::new(
+                at,
+                format!("Query is nested more than {MAX_DEPTH} levels deep"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// `or_expr ((AND)? or_expr)*`, up to a `)` or the end.
+    fn and_expr(&mut self) -> Result<Node, ParseError> {
+        let mut items = vec![self.or_expr()?];
+        loop {
+            match self.peek() {
+                None | Some((Token::RParen, _)) => break,
+                Some((Token::And, _)) => {
+                    self.pos += 1;
+                    items.push(self.or_expr()?);
+                }
+                Some(_) => items.push(self.or_expr()?),
+            }
+        }
+        Ok(if items.len() == 1 { items.pop().unwrap() } else { Node::And(items) })
+    }
+
+    /// `unary (OR unary)*`
+    fn or_expr(&mut self) -> Result<Node, ParseError> {
+        let mut items = vec![self.unary()?];
+        while let Some((Token::Or, _)) = self.peek() {
+            self.pos += 1;
+            items.push(self.unary()?);
+        }
+        Ok(if items.len() == 1 { items.pop().unwrap() } else { Node::Or(items) })
+    }
+
+    fn unary(&mut self) -> Result<Node, ParseError> {
+        if let Some((Token::Not, at)) = self.peek() {
+            self.descend(*at)?;
+            self.pos += 1;
+            let node = Node::Not(Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(node);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Node, ParseError> {
+        let at = self.position();
+        let Some((token, _)) = self.tokens.get(self.pos).cloned() else {
+            return Err(ParseError::new(at, "Expected a search term"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Word { text, prefix } => Ok(Node::Word { text, prefix }),
+            Token::Phrase(text) => Ok(Node::Phrase(text)),
+            Token::Filter(filter) => Ok(Node::Filter(filter)),
+            Token::LParen => {
+                self.descend(at)?;
+                let node = self.and_expr()?;
+                self.depth -= 1;
+                match self.peek() {
+                    Some((Token::RParen, _)) => {
+                        self.pos += 1;
+                        Ok(node)
+                    }
+                    _ => Err(ParseError::new(at, "Unclosed '('")),
+                }
+            }
+            Token::RParen => Err(ParseError::new(at, "Unmatched ')'")),
+            Token::And | Token::Or | Token::Not => {
+                Err(ParseError::new(at, "Expected a search term before this operator"))
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! Compile a parsed search query into SQL. Every user-supplied value is a
+//! bind parameter; words and phrases are additionally quoted as `tsquery`
+//! lexemes so operator characters in them are never interpreted.
+
+use std::collections::HashSet;
+
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use super::query::{Filter, Node};
+use crate::error::AppError;
+use crate::models::custom_field::FieldFilter;
+use crate::models::note::NoteFilters;
+
+/// A searchable document: notes aliased `n`, or media aliased `m`.
+pub struct SearchTarget {
+    /// Full-text document.
+    pub tsvector: &'static str,
+    /// Lowercased, unaccented text, for trigram matching.
+    pub normalized: &'static str,
+}
+
+/// A note's title and body. `normalized` matches the expression of
+/// `idx_notes_search_trgm`.
+pub const NOTE_TEXT: SearchTarget = SearchTarget {
+    tsvector: "to_tsvector('english', coalesce(n.title, '') || ' ' || coalesce(n.body_text, ''))",
+    normalized: "search_normalize(coalesce(n.title, '') || ' ' || coalesce(n.body_text, ''))",
+};
+
+/// A media item's filename, label and transcription. The expressions match
+/// the indexes `idx_media_search` and `idx_media_search_trgm`.
+pub const MEDIA_TEXT: SearchTarget = SearchTarget {
+    tsvector: "to_tsvector('english', coalesce(m.original_filename, '') || ' ' || \
+               coalesce(m.label, '') || ' ' || coalesce(m.transcription_text, ''))",
+    normalized: "search_normalize(coalesce(m.original_filename, '') || ' ' || \
+                 coalesce(m.label, '') || ' ' || coalesce(m.transcription_text, ''))",
+};
+
+/// Minimum `word_similarity` for a fuzzy match; the default of pg_trgm's
+/// `word_similarity_threshold`, which the `<%` operator uses.
+pub const FUZZY_THRESHOLD: f32 = 0.6;
+
+/// Shorter words have too few trigrams to be matched fuzzily.
+const MIN_FUZZY_CHARS: usize = 4;
+
+fn is_fuzzy(node: &Node) -> bool {
+    matches!(node, Node::Word { text, prefix: false } if text.chars().count() >= MIN_FUZZY_CHARS)
+}
+
+/// Quote a string as a single `tsquery` operand.
+fn lexeme(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+/// `to_tsquery` source for a word or phrase. A phrase is matched as its
+/// words in sequence.
+fn term_tsquery(node: &Node) -> Option<String> {
+    match node {
+        Node::Word { text, prefix } => {
+            Some(format!("{}{}", lexeme(text), if *prefix { ":*" } else { "" }))
+        }
+        Node::Phrase(text) => {
+            let words: Vec<String> = text.split_whitespace().map(lexeme).collect();
+            Some(format!("({})", words.join(" <-> ")))
+        }
+        _ => None,
+    }
+}
+
+/// `to_tsquery` sources of all words and phrases, negated or not.
+fn all_terms(node: &Node, out: &mut Vec<String>) {
+    match node {
+        Node::Word { .. } | Node::Phrase(_) => out.extend(term_tsquery(node)),
+        Node::Not(inner) => all_terms(inner, out),
+        Node::And(items) | Node::Or(items) => items.iter().for_each(|n| all_terms(n, out)),
+        Node::Filter(_) => {}
+    }
+}
+
+/// `node` without the words and phrases in `empty`. `None` when nothing is
+/// left.
+fn prune(node: &Node, empty: &HashSet<String>) -> Option<Node> {
+    match node {
+        Node::Word { .. } | Node::Phrase(_) => {
+            let source = term_tsquery(node).unwrap_or_default();
+            (!empty.contains(&source)).then(|| node.clone())
+        }
+        Node::Not(inner) => prune(inner, empty).map(|n| Node::Not(Box::new(n))),
+        Node::And(items) | Node::Or(items) => {
+            let mut kept: Vec<Node> = items.iter().filter_map(|n| prune(n, empty)).collect();
+            match kept.len() {
+                0 => None,
+                1 => kept.pop(),
+                _ if matches!(node, Node::And(_)) => Some(Node::And(kept)),
+                _ => Some(Node::Or(kept)),
+            }
+        }
+        Node::Filter(_) => Some(node.clone()),
+    }
+}
+
+/// Drop words and phrases that reduce to no lexemes, such as the stop words
+/// "of" and "the", as `plainto_tsquery` does. Each term is a condition of
+/// its own and an empty `tsquery` matches nothing, so left in they would
+/// empty the results. A query of nothing but stop words is kept as it is.
+pub async fn without_stop_words(pool: &PgPool, node: &Node) -> Result<Node, AppError> {
+    let mut sources = Vec::new();
+    all_terms(node, &mut sources);
+    if sources.is_empty() {
+        return Ok(node.clone());
+    }
+    let empty: HashSet<String> = sqlx::query_scalar(
+        "SELECT t FROM unnest($1::text[]) AS t WHERE numnode(to_tsquery('english', t)) = 0",
+    )
+    .bind(&sources)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+    if empty.is_empty() {
+        return Ok(node.clone());
+    }
+    Ok(prune(node, &empty).unwrap_or_else(|| node.clone()))
+}
+
+/// Words and phrases that are not negated, in query order.
+fn positive_terms(node: &Node, out: &mut Vec<Node>) {
+    match node {
+        Node::Word { .. } | Node::Phrase(_) => out.push(node.clone()),
+        Node::And(items) | Node::Or(items) => items.iter().for_each(|n| positive_terms(n, out)),
+        Node::Not(_) | Node::Filter(_) => {}
+    }
+}
+
+/// `to_tsquery` source matching any positive term, used for ranking and
+/// highlighting. Empty when the query only has filters or negations.
+pub fn rank_tsquery(node: &Node) -> String {
+    let mut terms = Vec::new();
+    positive_terms(node, &mut terms);
+    terms.iter().filter_map(term_tsquery).collect::<Vec<_>>().join(" | ")
+}
+
+/// Positive words long enough to match fuzzily. Their average similarity
+/// to a result adds to its rank.
+pub fn fuzzy_words(node: &Node) -> Vec<String> {
+    let mut terms = Vec::new();
+    positive_terms(node, &mut terms);
+    terms
+        .into_iter()
+        .filter(is_fuzzy)
+        .map(|term| match term {
+            Node::Word { text, .. } => text,
+            _ => unreachable!("is_fuzzy only accepts words"),
+        })
+        .collect()
+}
+
+/// The positive terms as `(LIKE pattern, text)` pairs for matching entity
+/// and concept names by substring or similarity. The patterns rely on the
+/// default escape character, `\`.
+pub fn name_terms(node: &Node) -> (Vec<String>, Vec<String>) {
+    let mut terms = Vec::new();
+    positive_terms(node, &mut terms);
+    terms
+        .into_iter()
+        .map(|term| match term {
+            Node::Word { text, .. } | Node::Phrase(text) => {
+                let text = text.trim().to_string();
+                (format!("%{}%", escape_like(&text)), text)
+            }
+            _ => unreachable!("positive_terms only yields words and phrases"),
+        })
+        .unzip()
+}
+
+/// Escape special ILIKE wildcard characters so user input is treated as a
+/// literal substring, not a pattern. Escapes `\`, `%`, and `_`.
+pub fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Push a boolean SQL condition. Words and phrases are matched against
+/// `target`; filters always apply to the note aliased `n`. With `fuzzy`,
+/// words also match text they are similar to after unaccenting; phrases and
+/// prefixes always match exactly.
+pub fn push_condition(
+    node: &Node,
+    target: &SearchTarget,
+    fuzzy: bool,
+    qb: &mut QueryBuilder<'_, Postgres>,
+) {
+    match node {
+        Node::Word { text, .. } if fuzzy && is_fuzzy(node) => {
+            qb.push("(")
+                .push(target.tsvector)
+                .push(" @@ to_tsquery('english', ")
+                .push_bind(term_tsquery(node).unwrap_or_default())
+                .push(") OR search_normalize(")
+                .push_bind(text.clone())
+                .push(") <% ")
+                .push(target.normalized)
+                .push(")");
+        }
+        Node::Word { .. } | Node::Phrase(_) => {
+            qb.push(target.tsvector)
+                .push(" @@ to_tsquery('english', ")
+                .push_bind(term_tsquery(node).unwrap_or_default())
+                .push(")");
+        }
+        Node::Not(inner) => {
+            qb.push("NOT (");
+            push_condition(inner, target, fuzzy, qb);
+            qb.push(")");
+        }
+        // A blank saved search matches every note.
+        Node::And(items) if items.is_empty() => {
+            qb.push("TRUE");
+        }
+        Node::And(items) | Node::Or(items) => {
+            let op = if matches!(node, Node::And(_)) { " AND " } else { " OR " };
+            qb.push("(");
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    qb.push(op);
+                }
+                push_condition(item, target, fuzzy, qb);
+            }
+            qb.push(")");
+        }
+        Node::Filter(filter) => push_filter(filter, qb),
+    }
+}
+
+fn push_filter(filter: &Filter, qb: &mut QueryBuilder<'_, Postgres>) {
+    match filter {
+        Filter::Type(key) => {
+            qb.push("n.note_type = ").push_bind(key.clone());
+        }
+        Filter::Tag(name) => {
+            qb.push(
+                "EXISTS (SELECT 1 FROM note_tags nt JOIN tags t ON t.id = nt.tag_id \
+                 WHERE nt.note_id = n.id AND lower(t.name) = lower(",
+            )
+            .push_bind(name.clone())
+            .push("))");
+        }
+        Filter::Trip(name) => {
+            qb.push(
+                "EXISTS (SELECT 1 FROM note_field_trips nft JOIN field_trips f ON f.id = nft.field_trip_id \
+                 WHERE nft.note_id = n.id AND lower(f.name) = lower(",
+            )
+            .push_bind(name.clone())
+            .push("))");
+        }
+        Filter::Entity(name) => {
+            qb.push(
+                "EXISTS (SELECT 1 FROM note_entities ne JOIN entities e ON e.id = ne.entity_id \
+                 WHERE ne.note_id = n.id AND lower(e.name) = lower(",
+            )
+            .push_bind(name.clone())
+            .push("))");
+        }
+        Filter::Before(date) => {
+            qb.push("COALESCE(n.time_start, n.created_at) < ").push_bind(*date);
+        }
+        Filter::After(date) => {
+            qb.push("COALESCE(n.time_start, n.created_at) >= ")
+                .push_bind(*date)
+                .push(" + 1");
+        }
+        Filter::Starred(starred) => {
+            qb.push("n.is_starred = ").push_bind(*starred);
+        }
+    }
+}
+
+/// Push ` AND` conditions restricting the note aliased `n` by the filtering
+/// fields of `NoteFilters`, as `GET /api/v1/notes` applies them. Paging,
+/// sorting and `deleted` are ignored, and place and time filters are
+/// applied through `GeoFilter`.
+pub fn push_note_filters(
+    filters: &NoteFilters,
+    field_filters: &[FieldFilter],
+    qb: &mut QueryBuilder<'_, Postgres>,
+) {
+    if let Some(id) = filters.field_trip_id {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM note_field_trips nft \
+             WHERE nft.note_id = n.id AND nft.field_trip_id = ",
+        )
+        .push_bind(id)
+        .push(")");
+    }
+    if let Some(id) = filters.concept_id {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM note_concepts nc \
+             WHERE nc.note_id = n.id AND nc.concept_id = ",
+        )
+        .push_bind(id)
+        .push(")");
+    }
+    if let Some(id) = filters.entity_id {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM note_entities ne \
+             WHERE ne.note_id = n.id AND ne.entity_id = ",
+        )
+        .push_bind(id)
+        .push(")");
+    }
+    if let Some(entity_type) = &filters.entity_type {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM note_entities ne JOIN entities e ON e.id = ne.entity_id \
+             WHERE ne.note_id = n.id AND e.entity_type::text = ",
+        )
+        .push_bind(entity_type.clone())
+        .push(")");
+    }
+    if let Some(note_type) = &filters.note_type {
+        qb.push(" AND n.note_type = ").push_bind(note_type.clone());
+    }
+    if filters.starred.unwrap_or(false) {
+        qb.push(" AND n.is_starred = true");
+    }
+    // Same conditions as `field_filter_sql`.
+    for filter in field_filters {
+        if filter.op == "=" {
+            qb.push(" AND n.custom_fields @> jsonb_build_object(")
+                .push_bind(filter.key.clone())
+                .push("::text, ")
+                .push_bind(filter.value.clone())
+                .push("::jsonb)");
+        } else {
+            qb.push(" AND n.custom_fields -> ")
+                .push_bind(filter.key.clone())
+                .push("::text ")
+                .push(filter.op)
+                .push(" ")
+                .push_bind(filter.value.clone())
+                .push("::jsonb");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::parse;
+
+    /// Prune `query` as if Postgres had "of", "the" and "in" as its only
+    /// stop words.
+    fn pruned(query: &str) -> Option<Node> {
+        let empty = ["'of'", "'the'", "'in'", "'the':*"].map(String::from).into();
+        prune(&parse(query).unwrap(), &empty)
+    }
+
+    fn word(text: &str) -> Node {
+        Node::Word { text: text.to_string(), prefix: false }
+    }
+
+    fn condition_sql(node: &Node) -> String {
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        push_condition(node, &NOTE_TEXT, false, &mut qb);
+        qb.sql().to_string()
+    }
+
+    #[test]
+    fn stop_words_are_dropped_from_and() {
+        assert_eq!(pruned("history of kandy"), Some(Node::And(vec![word("history"), word("kandy")])));
+        assert_eq!(pruned("the* kandy"), Some(word("kandy")));
+    }
+
+    #[test]
+    fn stop_words_are_dropped_from_or() {
+        assert_eq!(pruned("kandy OR the"), Some(word("kandy")));
+        assert_eq!(
+            pruned("history (kandy OR of OR colombo)"),
+            Some(Node::And(vec![word("history"), Node::Or(vec![word("kandy"), word("colombo")])]))
+        );
+    }
+
+    #[test]
+    fn negated_stop_words_are_dropped() {
+        assert_eq!(pruned("kandy -the"), Some(word("kandy")));
+        assert_eq!(pruned("kandy NOT (of OR in)"), Some(word("kandy")));
+        assert_eq!(
+            pruned("kandy -monsoon"),
+            Some(Node::And(vec![word("kandy"), Node::Not(Box::new(word("monsoon")))]))
+        );
+    }
+
+    #[test]
+    fn phrases_and_filters_are_kept() {
+        assert_eq!(pruned("\"history of kandy\""), Some(Node::Phrase("history of kandy".to_string())));
+        assert_eq!(
+            pruned("the tag:ritual"),
+            Some(Node::Filter(Filter::Tag("ritual".to_string())))
+        );
+    }
+
+    #[test]
+    fn only_stop_words_leave_nothing() {
+        assert_eq!(pruned("the OR of"), None);
+        assert_eq!(pruned("-in"), None);
+    }
+
+    #[test]
+    fn conditions_only_cover_remaining_terms() {
+        let sql = condition_sql(&pruned("history of kandy -the").unwrap());
+        assert_eq!(sql.matches("to_tsquery").count(), 2, "{sql}");
+        assert!(!sql.contains("NOT"), "{sql}");
+
+        let sql = condition_sql(&pruned("kandy OR the OR colombo").unwrap());
+        assert_eq!(sql.matches("to_tsquery").count(), 2, "{sql}");
+        assert_eq!(sql.matches(" OR ").count(), 1, "{sql}");
+    }
+
+    #[test]
+    fn rank_query_skips_stop_words() {
+        assert_eq!(rank_tsquery(&pruned("history of kandy -the").unwrap()), "'history' | 'kandy'");
+    }
+}
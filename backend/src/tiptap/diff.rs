@@ -0,0 +1,156 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// One entry of a block-level diff between two Tiptap documents.
+///
+/// Documents are compared on their top-level `content` array (paragraphs,
+/// headings, lists, …). Blocks are matched with a longest-common-subsequence
+/// pass; an unmatched removal immediately followed by an unmatched insertion
+/// of the same node type is reported as `modified` so the client can render
+/// an inline change rather than a delete + add pair.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BlockChange {
+    Unchanged {
+        from_index: usize,
+        to_index: usize,
+    },
+    Added {
+        to_index: usize,
+        node: Value,
+        text: String,
+    },
+    Removed {
+        from_index: usize,
+        node: Value,
+        text: String,
+    },
+    Modified {
+        from_index: usize,
+        to_index: usize,
+        from: Value,
+        to: Value,
+        from_text: String,
+        to_text: String,
+    },
+}
+
+/// Top-level blocks of a Tiptap document. A body that is not a `doc` node
+/// (e.g. the default `{}`) is treated as empty.
+fn blocks(doc: &Value) -> &[Value] {
+    doc.get("content")
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or(&[])
+}
+
+/// Concatenate all `text` leaves under a node, separating inline atoms
+/// (mentions) by their `label` attribute so diffs stay readable.
+pub fn node_text(node: &Value) -> String {
+    let mut out = String::new();
+    push_text(node, &mut out);
+    out
+}
+
+fn push_text(node: &Value, out: &mut String) {
+    if let Some(text) = node.get("text").and_then(|v| v.as_str()) {
+        out.push_str(text);
+    } else if let Some(label) = node
+        .get("attrs")
+        .and_then(|a| a.get("label"))
+        .and_then(|v| v.as_str())
+    {
+        out.push_str(label);
+    }
+    if let Some(children) = node.get("content").and_then(|v| v.as_array()) {
+        for child in children {
+            push_text(child, out);
+        }
+    }
+}
+
+fn node_type(node: &Value) -> Option<&str> {
+    node.get("type").and_then(|v| v.as_str())
+}
+
+/// Compute a block-level structural diff from `from` to `to`.
+pub fn diff_documents(from: &Value, to: &Value) -> Vec<BlockChange> {
+    let a = blocks(from);
+    let b = blocks(to);
+    let (n, m) = (a.len(), b.len());
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            changes.push(BlockChange::Unchanged {
+                from_index: i,
+                to_index: j,
+            });
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] > lcs[i + 1][j]) {
+            changes.push(BlockChange::Added {
+                to_index: j,
+                node: b[j].clone(),
+                text: node_text(&b[j]),
+            });
+            j += 1;
+        } else {
+            changes.push(BlockChange::Removed {
+                from_index: i,
+                node: a[i].clone(),
+                text: node_text(&a[i]),
+            });
+            i += 1;
+        }
+    }
+
+    pair_modifications(changes)
+}
+
+/// Collapse `Removed` + `Added` runs of the same node type into `Modified`.
+fn pair_modifications(changes: Vec<BlockChange>) -> Vec<BlockChange> {
+    let mut out: Vec<BlockChange> = Vec::with_capacity(changes.len());
+    for change in changes {
+        if let BlockChange::Added { to_index, node, text } = change {
+            match out.last() {
+                Some(BlockChange::Removed { node: prev, .. })
+                    if node_type(prev) == node_type(&node) =>
+                {
+                    if let Some(BlockChange::Removed {
+                        from_index,
+                        node: from,
+                        text: from_text,
+                    }) = out.pop()
+                    {
+                        out.push(BlockChange::Modified {
+                            from_index,
+                            to_index,
+                            from,
+                            to: node,
+                            from_text,
+                            to_text: text,
+                        });
+                    }
+                }
+                _ => out.push(BlockChange::Added { to_index, node, text }),
+            }
+        } else {
+            out.push(change);
+        }
+    }
+    out
+}
@@ -0,0 +1,322 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, LinkType, Options, Parser, Tag, TagEnd};
+use serde_json::{json, Map, Value};
+
+/// Result of converting a Markdown document into a Tiptap `doc`.
+pub struct ParsedMarkdown {
+    pub doc: Value,
+    /// Raw YAML front-matter (without the `---` fences), if present.
+    pub front_matter: Option<String>,
+    /// `[[Wiki link]]` targets the resolver did not recognise.
+    pub unresolved_links: Vec<String>,
+}
+
+/// One open node on the builder stack. `implicit` paragraphs are opened to
+/// hold inline content that Markdown allows directly inside list items or
+/// block quotes (tight lists) but Tiptap does not.
+struct Frame {
+    node: Value,
+    implicit: bool,
+}
+
+enum Capture {
+    None,
+    FrontMatter(String),
+    WikiLink { target: String, label: String },
+    Image { src: String, title: String, alt: String },
+}
+
+struct Builder<'r> {
+    stack: Vec<Frame>,
+    marks: Vec<Value>,
+    capture: Capture,
+    front_matter: Option<String>,
+    unresolved: Vec<String>,
+    resolve: &'r mut dyn FnMut(&str, &str) -> Option<Value>,
+}
+
+/// Convert Markdown into a Tiptap document.
+///
+/// `[[Target]]` and `[[Target|Label]]` wiki links are passed to `resolve`
+/// with the target and display label; it returns the inline node to insert
+/// (e.g. an `entityMention`) or `None`, in which case the label is kept as
+/// plain text and the target is reported in `unresolved_links`.
+pub fn from_markdown(
+    src: &str,
+    resolve: &mut dyn FnMut(&str, &str) -> Option<Value>,
+) -> ParsedMarkdown {
+    let options = Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_WIKILINKS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+
+    let mut b = Builder {
+        stack: vec![Frame {
+            node: json!({ "type": "doc", "content": [] }),
+            implicit: false,
+        }],
+        marks: Vec::new(),
+        capture: Capture::None,
+        front_matter: None,
+        unresolved: Vec::new(),
+        resolve,
+    };
+
+    for event in Parser::new_ext(src, options) {
+        b.event(event);
+    }
+
+    while b.stack.len() > 1 {
+        b.close();
+    }
+    let doc = b.stack.pop().map(|f| f.node).unwrap_or_else(|| json!({}));
+
+    ParsedMarkdown {
+        doc,
+        front_matter: b.front_matter,
+        unresolved_links: b.unresolved,
+    }
+}
+
+impl Builder<'_> {
+    fn event(&mut self, event: Event<'_>) {
+        // Text inside front-matter, wiki links and image alt text is buffered
+        // rather than emitted as nodes.
+        match (&mut self.capture, &event) {
+            (Capture::FrontMatter(buf), Event::Text(t)) => return buf.push_str(t),
+            (Capture::WikiLink { label, .. }, Event::Text(t) | Event::Code(t)) => {
+                return label.push_str(t)
+            }
+            (Capture::Image { alt, .. }, Event::Text(t) | Event::Code(t)) => {
+                return alt.push_str(t)
+            }
+            _ => {}
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                if self.top_type() == Some("codeBlock") {
+                    self.push_child(json!({ "type": "text", "text": text.as_ref() }));
+                } else {
+                    self.text(&text, None);
+                }
+            }
+            Event::Code(text) => self.text(&text, Some(json!({ "type": "code" }))),
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let html = html.trim_end_matches('\n');
+                self.text(html, None);
+            }
+            Event::SoftBreak => self.text(" ", None),
+            Event::HardBreak => self.inline(json!({ "type": "hardBreak" })),
+            Event::Rule => self.block(json!({ "type": "horizontalRule" })),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::MetadataBlock(_) => self.capture = Capture::FrontMatter(String::new()),
+            Tag::Paragraph => self.open("paragraph", None),
+            Tag::Heading { level, .. } => {
+                let level = match level {
+                    HeadingLevel::H1 => 1,
+                    HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    HeadingLevel::H4 => 4,
+                    HeadingLevel::H5 => 5,
+                    HeadingLevel::H6 => 6,
+                };
+                self.open("heading", Some(json!({ "level": level })));
+            }
+            Tag::BlockQuote(_) => self.open("blockquote", None),
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .map(|s| Value::String(s.to_string()))
+                        .unwrap_or(Value::Null),
+                    CodeBlockKind::Indented => Value::Null,
+                };
+                self.open("codeBlock", Some(json!({ "language": language })));
+            }
+            Tag::List(Some(start)) => self.open("orderedList", Some(json!({ "start": start }))),
+            Tag::List(None) => self.open("bulletList", None),
+            Tag::Item => self.open("listItem", None),
+            Tag::Emphasis => self.marks.push(json!({ "type": "italic" })),
+            Tag::Strong => self.marks.push(json!({ "type": "bold" })),
+            Tag::Strikethrough => self.marks.push(json!({ "type": "strike" })),
+            Tag::Link {
+                link_type: LinkType::WikiLink { .. },
+                dest_url,
+                ..
+            } => {
+                self.capture = Capture::WikiLink {
+                    target: dest_url.trim().to_string(),
+                    label: String::new(),
+                };
+            }
+            Tag::Link { dest_url, .. } => {
+                self.marks
+                    .push(json!({ "type": "link", "attrs": { "href": dest_url.as_ref() } }));
+            }
+            Tag::Image { dest_url, title, .. } => {
+                self.capture = Capture::Image {
+                    src: dest_url.to_string(),
+                    title: title.to_string(),
+                    alt: String::new(),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::MetadataBlock(_) => {
+                if let Capture::FrontMatter(buf) = std::mem::replace(&mut self.capture, Capture::None) {
+                    self.front_matter = Some(buf);
+                }
+            }
+            TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::BlockQuote(_)
+            | TagEnd::List(_)
+            | TagEnd::Item => {
+                self.close_implicit();
+                self.close();
+            }
+            TagEnd::CodeBlock => {
+                // Drop the trailing newline pulldown-cmark keeps on the last line.
+                if let Some(Frame { node, .. }) = self.stack.last_mut() {
+                    if let Some(Value::Array(children)) = node.get_mut("content") {
+                        if let Some(Value::String(text)) =
+                            children.last_mut().and_then(|c| c.get_mut("text"))
+                        {
+                            if text.ends_with('\n') {
+                                text.pop();
+                            }
+                        }
+                    }
+                }
+                self.close();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.marks.pop();
+            }
+            TagEnd::Link => match std::mem::replace(&mut self.capture, Capture::None) {
+                Capture::WikiLink { target, label } => {
+                    let label = if label.trim().is_empty() { target.clone() } else { label };
+                    match (self.resolve)(&target, &label) {
+                        Some(node) => self.inline(node),
+                        None => {
+                            if !self.unresolved.contains(&target) {
+                                self.unresolved.push(target);
+                            }
+                            self.text(&label, None);
+                        }
+                    }
+                }
+                _ => {
+                    self.marks.pop();
+                }
+            },
+            TagEnd::Image => {
+                if let Capture::Image { src, title, alt } =
+                    std::mem::replace(&mut self.capture, Capture::None)
+                {
+                    let mut attrs = json!({ "src": src, "alt": alt });
+                    if !title.is_empty() {
+                        attrs["title"] = Value::String(title);
+                    }
+                    self.inline(json!({ "type": "image", "attrs": attrs }));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn top_type(&self) -> Option<&str> {
+        self.stack
+            .last()
+            .and_then(|f| f.node.get("type"))
+            .and_then(|v| v.as_str())
+    }
+
+    fn open(&mut self, node_type: &str, attrs: Option<Value>) {
+        self.close_implicit();
+        let mut node = Map::new();
+        node.insert("type".into(), Value::String(node_type.to_string()));
+        if let Some(attrs) = attrs {
+            node.insert("attrs".into(), attrs);
+        }
+        node.insert("content".into(), json!([]));
+        self.stack.push(Frame {
+            node: Value::Object(node),
+            implicit: false,
+        });
+    }
+
+    /// Pop the top frame and append it to its parent. Empty content arrays
+    /// are dropped, matching what the editor serialises.
+    fn close(&mut self) {
+        let Some(Frame { mut node, .. }) = self.stack.pop() else {
+            return;
+        };
+        if node
+            .get("content")
+            .and_then(|c| c.as_array())
+            .is_some_and(|c| c.is_empty())
+        {
+            if let Some(obj) = node.as_object_mut() {
+                obj.remove("content");
+            }
+        }
+        self.push_child(node);
+    }
+
+    fn close_implicit(&mut self) {
+        if self.stack.last().is_some_and(|f| f.implicit) {
+            self.close();
+        }
+    }
+
+    fn push_child(&mut self, child: Value) {
+        if let Some(Value::Array(children)) =
+            self.stack.last_mut().and_then(|f| f.node.get_mut("content"))
+        {
+            children.push(child);
+        }
+    }
+
+    /// Append a block-level leaf (e.g. a horizontal rule).
+    fn block(&mut self, node: Value) {
+        self.close_implicit();
+        self.push_child(node);
+    }
+
+    /// Append an inline node, opening an implicit paragraph if the current
+    /// container only accepts blocks.
+    fn inline(&mut self, node: Value) {
+        if matches!(self.top_type(), Some("doc" | "listItem" | "blockquote")) {
+            self.stack.push(Frame {
+                node: json!({ "type": "paragraph", "content": [] }),
+                implicit: true,
+            });
+        }
+        self.push_child(node);
+    }
+
+    fn text(&mut self, text: &str, extra_mark: Option<Value>) {
+        if text.is_empty() {
+            return;
+        }
+        let mut marks = self.marks.clone();
+        marks.extend(extra_mark);
+        let mut node = json!({ "type": "text", "text": text });
+        if !marks.is_empty() {
+            node["marks"] = Value::Array(marks);
+        }
+        self.inline(node);
+    }
+}
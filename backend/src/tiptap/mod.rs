@@ -0,0 +1,116 @@
+//! Helpers for working with Tiptap (ProseMirror) JSON documents outside of
+//! the editor: structural diffing, rendering to Markdown/HTML and parsing
+//! Markdown back into a document.
+
+pub mod diff;
+pub mod markdown;
+pub mod render;
+pub mod template;
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Plain-text projection of a document, equivalent to the editor's
+/// `getText()`: blocks are separated by a blank line and inline atoms use
+/// their `renderText` form (`@Entity`, `#Concept`, `📍 Place`).
+/// Used to populate `notes.body_text` for server-built bodies.
+pub fn plain_text(doc: &Value) -> String {
+    let mut blocks = Vec::new();
+    collect_blocks(doc, &mut blocks);
+    blocks.join("\n\n")
+}
+
+fn is_inline(node: &Value) -> bool {
+    matches!(
+        node.get("type").and_then(|v| v.as_str()),
+        Some(
+            "text"
+                | "hardBreak"
+                | "image"
+                | "entityMention"
+                | "conceptTag"
+                | "locationTag"
+                | "noteLink"
+        )
+    )
+}
+
+/// Push one entry per textblock (a node whose children are inline).
+fn collect_blocks(node: &Value, out: &mut Vec<String>) {
+    let Some(children) = node.get("content").and_then(|v| v.as_array()) else {
+        return;
+    };
+    if children.iter().any(is_inline) {
+        let mut line = String::new();
+        for child in children {
+            match child.get("type").and_then(|v| v.as_str()) {
+                Some("text") => {
+                    line.push_str(child.get("text").and_then(|v| v.as_str()).unwrap_or(""))
+                }
+                Some("hardBreak") => line.push('\n'),
+                Some(kind) => {
+                    let label = child
+                        .get("attrs")
+                        .and_then(|a| a.get("label"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    match kind {
+                        "entityMention" => line.push('@'),
+                        "conceptTag" => line.push('#'),
+                        "locationTag" => line.push_str("📍 "),
+                        _ => {}
+                    }
+                    line.push_str(label);
+                }
+                None => {}
+            }
+        }
+        out.push(line);
+    } else {
+        for child in children {
+            collect_blocks(child, out);
+        }
+    }
+}
+
+/// Rewrite id references inside a document after rows were copied under new
+/// UUIDs: any `attrs` value that is a mapped UUID (mention/tag/link `id`,
+/// image `mediaId`) is replaced, and `src`/`href` URLs embedding a mapped id
+/// (e.g. `/api/v1/media/{id}/file`) are rewritten. Applies to marks too.
+pub fn remap_ids(node: &mut Value, ids: &HashMap<Uuid, Uuid>) {
+    match node {
+        Value::Object(obj) => {
+            if let Some(Value::Object(attrs)) = obj.get_mut("attrs") {
+                for (key, value) in attrs.iter_mut() {
+                    let Value::String(s) = value else {
+                        continue;
+                    };
+                    if let Some(new_id) = Uuid::parse_str(s).ok().and_then(|id| ids.get(&id)) {
+                        *s = new_id.to_string();
+                    } else if key == "src" || key == "href" {
+                        for segment in s.clone().split('/') {
+                            if let Some(new_id) =
+                                Uuid::parse_str(segment).ok().and_then(|id| ids.get(&id))
+                            {
+                                *s = s.replace(segment, &new_id.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            for key in ["content", "marks"] {
+                if let Some(child) = obj.get_mut(key) {
+                    remap_ids(child, ids);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                remap_ids(item, ids);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+/// How an uploaded `media` row should be referenced from rendered output.
+#[derive(Debug, Clone)]
+pub struct MediaRef {
+    pub url: String,
+    pub label: Option<String>,
+}
+
+/// Lookups the renderer needs beyond the document itself.
+#[derive(Debug, Default)]
+pub struct RenderContext {
+    /// Media rows belonging to the note, keyed by media id.
+    pub media: HashMap<Uuid, MediaRef>,
+}
+
+// ---------------------------------------------------------------------------
+// Node helpers
+// ---------------------------------------------------------------------------
+
+fn node_type(node: &Value) -> &str {
+    node.get("type").and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or(&[])
+}
+
+fn attr_str<'a>(node: &'a Value, key: &str) -> Option<&'a str> {
+    node.get("attrs")
+        .and_then(|a| a.get(key))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+fn attr_i64(node: &Value, key: &str) -> Option<i64> {
+    node.get("attrs").and_then(|a| a.get(key)).and_then(|v| v.as_i64())
+}
+
+/// Label shown for an inline atom (mention, tag, note link).
+fn atom_label(node: &Value) -> String {
+    attr_str(node, "label")
+        .or_else(|| attr_str(node, "id"))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Only allow link/image targets that cannot execute script when rendered.
+fn is_safe_url(url: &str) -> bool {
+    let lower = url.trim().to_ascii_lowercase();
+    lower.starts_with("http://")
+        || lower.starts_with("https://")
+        || lower.starts_with("mailto:")
+        || (lower.starts_with('/') && !lower.starts_with("//"))
+        || (!lower.contains(':') && !lower.starts_with("//"))
+}
+
+/// Pull a media id out of an `image` node: either an explicit `mediaId`
+/// attr, or a `src` pointing at `/api/v1/media/{id}/file`.
+fn image_media_id(node: &Value) -> Option<Uuid> {
+    if let Some(id) = attr_str(node, "mediaId").and_then(|s| Uuid::parse_str(s).ok()) {
+        return Some(id);
+    }
+    let src = attr_str(node, "src")?;
+    let rest = &src[src.find("/media/")? + "/media/".len()..];
+    let id = rest.split('/').next()?;
+    Uuid::parse_str(id).ok()
+}
+
+/// Resolve an `image` node to `(src, alt)`, preferring the note's media rows.
+fn resolve_image(node: &Value, ctx: &RenderContext) -> Option<(String, String)> {
+    let alt = attr_str(node, "alt").map(str::to_string);
+    if let Some(media) = image_media_id(node).and_then(|id| ctx.media.get(&id)) {
+        let alt = alt.or_else(|| media.label.clone()).unwrap_or_default();
+        return Some((media.url.clone(), alt));
+    }
+    let src = attr_str(node, "src").filter(|s| is_safe_url(s))?;
+    Some((src.to_string(), alt.unwrap_or_default()))
+}
+
+// ---------------------------------------------------------------------------
+// Markdown (CommonMark)
+// ---------------------------------------------------------------------------
+
+/// Render a Tiptap document as CommonMark.
+pub fn to_markdown(doc: &Value, ctx: &RenderContext) -> String {
+    let mut out = md_blocks(children(doc), ctx, "\n\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+fn escape_md(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escape a marker at the start of a line that would otherwise begin a
+/// list, or underline the previous line into a heading.
+fn escape_block_start(line: &str) -> String {
+    let body = line.trim_start_matches(' ');
+    let indent = &line[..line.len() - body.len()];
+    if body.starts_with(['-', '+', '=']) {
+        return format!("{indent}\\{body}");
+    }
+    let digits = body.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0 && body[digits..].starts_with(['.', ')']) {
+        return format!("{indent}{}\\{}", &body[..digits], &body[digits..]);
+    }
+    line.to_string()
+}
+
+/// Inline content as the text of a paragraph, with block markers escaped at
+/// the start of each line.
+fn md_paragraph(nodes: &[Value], ctx: &RenderContext) -> String {
+    md_inline(nodes, ctx)
+        .split('\n')
+        .map(escape_block_start)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn md_blocks(nodes: &[Value], ctx: &RenderContext, sep: &str) -> String {
+    nodes
+        .iter()
+        .map(|n| md_block(n, ctx))
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+/// Prefix every line after the first with `indent`.
+fn indent_rest(text: &str, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{indent}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn md_block(node: &Value, ctx: &RenderContext) -> String {
+    match node_type(node) {
+        "paragraph" => md_paragraph(children(node), ctx),
+        "heading" => {
+            let level = attr_i64(node, "level").unwrap_or(1).clamp(1, 6) as usize;
+            format!("{} {}", "#".repeat(level), md_inline(children(node), ctx))
+        }
+        "bulletList" | "orderedList" => {
+            let ordered = node_type(node) == "orderedList";
+            let start = attr_i64(node, "start").unwrap_or(1);
+            children(node)
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let marker = if ordered {
+                        format!("{}. ", start + i as i64)
+                    } else {
+                        "- ".to_string()
+                    };
+                    let body = md_blocks(children(item), ctx, "\n");
+                    format!("{marker}{}", indent_rest(&body, &" ".repeat(marker.len())))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        "blockquote" => md_blocks(children(node), ctx, "\n\n")
+            .lines()
+            .map(|l| if l.is_empty() { ">".to_string() } else { format!("> {l}") })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "codeBlock" => {
+            let code: String = children(node)
+                .iter()
+                .filter_map(|c| c.get("text").and_then(|v| v.as_str()))
+                .collect();
+            // A fence longer than any backtick run in the code cannot close early.
+            let fence = "`".repeat((longest_run(&code, '`') + 1).max(3));
+            let lang = attr_str(node, "language").unwrap_or("").replace('`', "");
+            format!("{fence}{lang}\n{code}\n{fence}")
+        }
+        "horizontalRule" => "---".to_string(),
+        "image" => match resolve_image(node, ctx) {
+            Some((src, alt)) => format!("![{}]({})", escape_md(&alt), src.replace(' ', "%20")),
+            None => String::new(),
+        },
+        // Unknown block: fall back to its inline content so text is not lost.
+        _ => md_paragraph(children(node), ctx),
+    }
+}
+
+fn md_inline(nodes: &[Value], ctx: &RenderContext) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node_type(node) {
+            "text" => {
+                let text = node.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                out.push_str(&md_marks(text, node));
+            }
+            "hardBreak" => out.push_str("\\\n"),
+            "entityMention" => out.push_str(&format!("@{}", escape_md(&atom_label(node)))),
+            "conceptTag" => out.push_str(&format!("\\#{}", escape_md(&atom_label(node)))),
+            "locationTag" => out.push_str(&format!("📍 {}", escape_md(&atom_label(node)))),
+            "noteLink" => out.push_str(&format!("[[{}]]", escape_md(&atom_label(node)))),
+            "image" => {
+                if let Some((src, alt)) = resolve_image(node, ctx) {
+                    out.push_str(&format!("![{}]({})", escape_md(&alt), src.replace(' ', "%20")));
+                }
+            }
+            _ => out.push_str(&md_inline(children(node), ctx)),
+        }
+    }
+    out
+}
+
+/// Length of the longest run of `c` in `text`.
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|x| x != c).map(|run| run.len()).max().unwrap_or(0)
+}
+
+fn md_marks(text: &str, node: &Value) -> String {
+    let marks = node.get("marks").and_then(|v| v.as_array());
+    let has = |t: &str| marks.is_some_and(|m| m.iter().any(|mk| node_type(mk) == t));
+
+    if has("code") {
+        // CommonMark strips one space from each end of a code span, so a
+        // leading or trailing backtick can be padded away from the delimiters.
+        let ticks = "`".repeat(longest_run(text, '`') + 1);
+        let pad = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+        return format!("{ticks}{pad}{text}{pad}{ticks}");
+    }
+
+    // Emphasis delimiters must hug non-whitespace in CommonMark, so keep
+    // surrounding spaces outside the markers.
+    let core = text.trim();
+    if core.is_empty() {
+        return text.to_string();
+    }
+    let lead = &text[..text.len() - text.trim_start().len()];
+    let trail = &text[text.trim_end().len()..];
+
+    let mut s = escape_md(core);
+    if has("strike") {
+        s = format!("~~{s}~~");
+    }
+    if has("italic") {
+        s = format!("*{s}*");
+    }
+    if has("bold") {
+        s = format!("**{s}**");
+    }
+    if let Some(link) = marks.and_then(|m| m.iter().find(|mk| node_type(mk) == "link")) {
+        if let Some(href) = attr_str(link, "href").filter(|h| is_safe_url(h)) {
+            s = format!("[{s}]({})", href.replace(' ', "%20"));
+        }
+    }
+    if let Some(link) = marks.and_then(|m| m.iter().find(|mk| node_type(mk) == "noteLink")) {
+        if attr_str(link, "id").is_some() {
+            s = format!("[[{s}]]");
+        }
+    }
+    format!("{lead}{s}{trail}")
+}
+
+// ---------------------------------------------------------------------------
+// HTML
+// ---------------------------------------------------------------------------
+
+/// Render a Tiptap document as an HTML fragment. All text and attribute
+/// values are escaped and only safe URL schemes are emitted, so the output
+/// can be embedded without further sanitising.
+pub fn to_html(doc: &Value, ctx: &RenderContext) -> String {
+    html_blocks(children(doc), ctx)
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn html_blocks(nodes: &[Value], ctx: &RenderContext) -> String {
+    nodes.iter().map(|n| html_block(n, ctx)).collect::<Vec<_>>().join("\n")
+}
+
+fn html_block(node: &Value, ctx: &RenderContext) -> String {
+    match node_type(node) {
+        "paragraph" => format!("<p>{}</p>", html_inline(children(node), ctx)),
+        "heading" => {
+            let level = attr_i64(node, "level").unwrap_or(1).clamp(1, 6);
+            format!("<h{level}>{}</h{level}>", html_inline(children(node), ctx))
+        }
+        "bulletList" => format!("<ul>\n{}\n</ul>", html_blocks(children(node), ctx)),
+        "orderedList" => match attr_i64(node, "start").filter(|s| *s != 1) {
+            Some(start) => format!(
+                "<ol start=\"{start}\">\n{}\n</ol>",
+                html_blocks(children(node), ctx)
+            ),
+            None => format!("<ol>\n{}\n</ol>", html_blocks(children(node), ctx)),
+        },
+        "listItem" => format!("<li>{}</li>", html_blocks(children(node), ctx)),
+        "blockquote" => format!(
+            "<blockquote>\n{}\n</blockquote>",
+            html_blocks(children(node), ctx)
+        ),
+        "codeBlock" => {
+            let code: String = children(node)
+                .iter()
+                .filter_map(|c| c.get("text").and_then(|v| v.as_str()))
+                .collect();
+            match attr_str(node, "language") {
+                Some(lang) => format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>",
+                    escape_html(lang),
+                    escape_html(&code)
+                ),
+                None => format!("<pre><code>{}</code></pre>", escape_html(&code)),
+            }
+        }
+        "horizontalRule" => "<hr>".to_string(),
+        "image" => html_image(node, ctx),
+        _ => {
+            let inner = html_inline(children(node), ctx);
+            if inner.is_empty() {
+                inner
+            } else {
+                format!("<p>{inner}</p>")
+            }
+        }
+    }
+}
+
+fn html_image(node: &Value, ctx: &RenderContext) -> String {
+    match resolve_image(node, ctx) {
+        Some((src, alt)) => format!(
+            "<img src=\"{}\" alt=\"{}\">",
+            escape_html(&src),
+            escape_html(&alt)
+        ),
+        None => String::new(),
+    }
+}
+
+fn html_inline(nodes: &[Value], ctx: &RenderContext) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node_type(node) {
+            "text" => {
+                let text = node.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                out.push_str(&html_marks(text, node));
+            }
+            "hardBreak" => out.push_str("<br>"),
+            "entityMention" => out.push_str(&format!(
+                "<span class=\"entity-mention\" data-id=\"{}\">@{}</span>",
+                escape_html(attr_str(node, "id").unwrap_or_default()),
+                escape_html(&atom_label(node))
+            )),
+            "conceptTag" => out.push_str(&format!(
+                "<span class=\"concept-tag\" data-id=\"{}\">#{}</span>",
+                escape_html(attr_str(node, "id").unwrap_or_default()),
+                escape_html(&atom_label(node))
+            )),
+            "locationTag" => out.push_str(&format!(
+                "<span class=\"location-tag\">📍 {}</span>",
+                escape_html(&atom_label(node))
+            )),
+            "noteLink" => out.push_str(&format!(
+                "<a class=\"note-link\" href=\"/notes/{}\">{}</a>",
+                escape_html(attr_str(node, "id").unwrap_or_default()),
+                escape_html(&atom_label(node))
+            )),
+            "image" => out.push_str(&html_image(node, ctx)),
+            _ => out.push_str(&html_inline(children(node), ctx)),
+        }
+    }
+    out
+}
+
+fn html_marks(text: &str, node: &Value) -> String {
+    let mut s = escape_html(text);
+    let Some(marks) = node.get("marks").and_then(|v| v.as_array()) else {
+        return s;
+    };
+    for mark in marks {
+        s = match node_type(mark) {
+            "bold" => format!("<strong>{s}</strong>"),
+            "italic" => format!("<em>{s}</em>"),
+            "strike" => format!("<s>{s}</s>"),
+            "underline" => format!("<u>{s}</u>"),
+            "code" => format!("<code>{s}</code>"),
+            "link" => match attr_str(mark, "href").filter(|h| is_safe_url(h)) {
+                Some(href) => format!(
+                    "<a href=\"{}\" rel=\"noopener noreferrer\">{s}</a>",
+                    escape_html(href)
+                ),
+                None => s,
+            },
+            "noteLink" => match attr_str(mark, "id") {
+                Some(id) => format!(
+                    "<a class=\"note-link\" href=\"/notes/{}\">{s}</a>",
+                    escape_html(id)
+                ),
+                None => s,
+            },
+            _ => s,
+        };
+    }
+    s
+}
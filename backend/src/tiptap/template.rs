@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Replace `{{ name }}` placeholders in `s`. Names missing from `vars` are
+/// left as written so a typo stays visible in the new note.
+pub fn expand_str(s: &str, vars: &HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        match vars.get(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Expand placeholders in every text node of a document. Text nodes that
+/// become empty are dropped, since ProseMirror rejects empty text nodes.
+pub fn expand_document(node: &mut Value, vars: &HashMap<&str, String>) {
+    let Some(children) = node.get_mut("content").and_then(|c| c.as_array_mut()) else {
+        return;
+    };
+    for child in children.iter_mut() {
+        if let Some(Value::String(text)) = child.get_mut("text") {
+            *text = expand_str(text, vars);
+        } else {
+            expand_document(child, vars);
+        }
+    }
+    children.retain(|c| c.get("text").and_then(|t| t.as_str()) != Some(""));
+    if children.is_empty() {
+        if let Some(obj) = node.as_object_mut() {
+            obj.remove("content");
+        }
+    }
+}
+
+/// True for `{}`, a `doc` without content, or a doc holding only empty
+/// paragraphs — what the editor sends for a blank note.
+pub fn is_blank(doc: &Value) -> bool {
+    doc.get("content")
+        .and_then(|c| c.as_array())
+        .is_none_or(|blocks| {
+            blocks.iter().all(|b| {
+                b.get("type").and_then(|t| t.as_str()) == Some("paragraph")
+                    && b.get("content")
+                        .and_then(|c| c.as_array())
+                        .is_none_or(|c| c.is_empty())
+            })
+        })
+}
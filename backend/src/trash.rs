@@ -0,0 +1,179 @@
+//! Trash retention. Soft-deleted notes are purged once they have been in the
+//! trash longer than their workspace's `trash_retention_days`; purging also
+//! removes their media files from `uploads/` and gives the storage back.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::plan_guard;
+use crate::routes::media::UPLOADS_DIR;
+use crate::routes::notes::recompute_graph_edges;
+
+/// Notes hard-deleted by [`purge_notes`] and the media they left behind. The
+/// media rows go with the notes; the files are removed with
+/// [`PurgedNotes::remove_files`] once the transaction has committed.
+#[derive(Debug, Default)]
+pub struct PurgedNotes {
+    pub note_ids: Vec<Uuid>,
+    /// Storage keys under `uploads/`, including thumbnails.
+    pub files: Vec<String>,
+    pub bytes: i64,
+}
+
+impl PurgedNotes {
+    /// Best-effort delete from disk, as `delete_media` does.
+    pub async fn remove_files(&self) {
+        for key in &self.files {
+            let _ = tokio::fs::remove_file(PathBuf::from(UPLOADS_DIR).join(key)).await;
+        }
+    }
+}
+
+/// Permanently delete the given notes if they are in the trash, together
+/// with their media rows and the graph edges pointing at them. Derived graph
+/// edges are left for the caller to recompute.
+pub async fn purge_notes(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    workspace_id: Uuid,
+    ids: &[Uuid],
+) -> Result<PurgedNotes, AppError> {
+    let note_ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM notes WHERE id = ANY($1) AND workspace_id = $2 \
+         AND deleted_at IS NOT NULL FOR UPDATE",
+    )
+    .bind(ids)
+    .bind(workspace_id)
+    .fetch_all(&mut **tx)
+    .await?;
+    if note_ids.is_empty() {
+        return Ok(PurgedNotes::default());
+    }
+
+    // Media rows cascade with the notes, so collect their files first.
+    let media: Vec<(String, Option<String>, Option<i64>)> = sqlx::query_as(
+        "SELECT s3_key, thumbnail_s3_key, file_size_bytes FROM media WHERE note_id = ANY($1)",
+    )
+    .bind(&note_ids)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let mut files = Vec::new();
+    let mut bytes = 0;
+    for (key, thumbnail, size) in media {
+        bytes += size.unwrap_or(0);
+        // Remote URLs (e.g. imported links) have nothing on disk.
+        for key in std::iter::once(key).chain(thumbnail) {
+            if !key.starts_with("http://") && !key.starts_with("https://") {
+                files.push(key);
+            }
+        }
+    }
+
+    sqlx::query("DELETE FROM notes WHERE id = ANY($1)")
+        .bind(&note_ids)
+        .execute(&mut **tx)
+        .await?;
+
+    // graph_edges has no FK to notes; drop edges touching these notes.
+    sqlx::query(
+        "DELETE FROM graph_edges WHERE workspace_id = $2 \
+         AND ((source_type = 'note' AND source_id = ANY($1)) \
+           OR (target_type = 'note' AND target_id = ANY($1)))",
+    )
+    .bind(&note_ids)
+    .bind(workspace_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(PurgedNotes { note_ids, files, bytes })
+}
+
+// ---------------------------------------------------------------------------
+// Background purge
+// ---------------------------------------------------------------------------
+
+/// Purge expired trash and export archives every `interval`, starting
+/// immediately. Intended to be spawned for the lifetime of the server.
+pub async fn run_purge_loop(pool: PgPool, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match purge_expired(&pool).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Purged {} expired notes from trash", n),
+            Err(e) => tracing::error!("Trash purge failed: {}", e),
+        }
+        match crate::archive::export::purge_expired(&pool).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Deleted {} expired workspace exports", n),
+            Err(e) => tracing::error!("Export purge failed: {}", e),
+        }
+    }
+}
+
+/// Workspaces with trashed notes past their retention period.
+#[derive(sqlx::FromRow)]
+struct ExpiredTrash {
+    workspace_id: Uuid,
+    owner_id: Uuid,
+    note_ids: Vec<Uuid>,
+}
+
+/// Purge every expired trashed note, one transaction per workspace so a
+/// failure in one does not hold back the rest. Returns the number purged.
+pub async fn purge_expired(pool: &PgPool) -> Result<usize, AppError> {
+    let expired = sqlx::query_as::<_, ExpiredTrash>(
+        "SELECT n.workspace_id, w.user_id AS owner_id, ARRAY_AGG(n.id) AS note_ids \
+         FROM notes n JOIN workspaces w ON w.id = n.workspace_id \
+         WHERE n.deleted_at IS NOT NULL AND w.trash_retention_days IS NOT NULL \
+           AND n.deleted_at <= now() - make_interval(days => w.trash_retention_days) \
+         GROUP BY n.workspace_id, w.user_id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut total = 0;
+    for ws in expired {
+        match purge_workspace(pool, &ws).await {
+            Ok(n) => total += n,
+            Err(e) => tracing::error!("Trash purge failed for workspace {}: {}", ws.workspace_id, e),
+        }
+    }
+    Ok(total)
+}
+
+async fn purge_workspace(pool: &PgPool, ws: &ExpiredTrash) -> Result<usize, AppError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let purged = purge_notes(&mut tx, ws.workspace_id, &ws.note_ids).await?;
+    if purged.note_ids.is_empty() {
+        return Ok(0);
+    }
+    recompute_graph_edges(&mut tx, ws.workspace_id).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    purged.remove_files().await;
+    if purged.bytes > 0 {
+        let _ = plan_guard::increment_usage(
+            pool,
+            ws.owner_id,
+            ws.workspace_id,
+            "storage_bytes",
+            -purged.bytes,
+        )
+        .await;
+    }
+
+    Ok(purged.note_ids.len())
+}
@@ -156,7 +156,7 @@ function ChatPanel() {
   };
 
   const isComingSoon = aiStatus && !aiStatus.enabled;
-  const providerLabel = aiStatus?.provider === 'claude' ? 'Claude' : aiStatus?.provider === 'perplexity' ? 'Perplexity' : null;
+  const providerLabel = aiStatus?.label ?? null;
 
   return (
     <div className="flex flex-col flex-1 min-h-0">
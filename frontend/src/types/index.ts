@@ -275,6 +275,8 @@ export interface AiMessage {
 export interface AiStatusResponse {
   enabled: boolean;
   provider: string;
+  label: string | null;
+  model: string | null;
   message: string;
 }
 